@@ -27,6 +27,7 @@ fn main() -> Result<(), hotline_rs::Error> {
         shader_heap_size: 100,
         render_target_heap_size: 100,
         depth_stencil_heap_size: 100,
+        sampler_heap_size: 0,
     });
     print!("{}", device.get_adapter_info());
 
@@ -54,6 +55,9 @@ fn main() -> Result<(), hotline_rs::Error> {
             b: 0.60,
             a: 1.00,
         }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
     let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&swap_chain_info, &win)?;
 
@@ -202,7 +206,10 @@ fn main() -> Result<(), hotline_rs::Error> {
                 b: 1.0,
                 a: 1.0,
             }),
+            rt_clears: None,
+            rt_views: None,
             depth_stencil: Some(&depth_stencil),
+            ds_view: None,
             ds_clear: Some(gfx::ClearDepthStencil {
                 depth: Some(1.0),
                 stencil: None,
@@ -316,9 +323,9 @@ fn main() -> Result<(), hotline_rs::Error> {
 
         cmdbuffer.close()?;
 
-        dev.execute(&cmdbuffer);
+        dev.execute(&cmdbuffer)?;
 
-        swap_chain.swap(&dev);
+        swap_chain.swap(&dev)?;
         ci = (ci + 1) % 4;
     }
 
@@ -22,6 +22,7 @@ fn main() -> Result<(), hotline_rs::Error> {
         shader_heap_size: 100,
         render_target_heap_size: 100,
         depth_stencil_heap_size: 100,
+        sampler_heap_size: 0,
     });
 
     // window
@@ -46,6 +47,9 @@ fn main() -> Result<(), hotline_rs::Error> {
             b: 0.60,
             a: 1.00,
         }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
     let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&swap_chain_info, &win)?;
     let mut cmdbuffer = dev.create_cmd_buf(2);
@@ -108,9 +112,9 @@ fn main() -> Result<(), hotline_rs::Error> {
 
         cmdbuffer.close()?;
 
-        dev.execute(&cmdbuffer);
+        dev.execute(&cmdbuffer)?;
 
-        swap_chain.swap(&dev);
+        swap_chain.swap(&dev)?;
         ci = (ci + 1) % 4;
     }
 
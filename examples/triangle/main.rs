@@ -48,6 +48,9 @@ fn main() -> Result<(), hotline_rs::Error> {
             b: 0.60,
             a: 1.00,
         }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
 
     let mut swap_chain = device.create_swap_chain::<os_platform::App>(&swap_chain_info, &window)?;
@@ -99,6 +102,9 @@ fn main() -> Result<(), hotline_rs::Error> {
     let pso = device.create_render_pipeline(&gfx::RenderPipelineInfo {
         vs: Some(&vs),
         fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
         input_layout: vec![
             gfx::InputElementInfo {
                 semantic: String::from("POSITION"),
@@ -129,6 +135,7 @@ fn main() -> Result<(), hotline_rs::Error> {
         },
         topology: gfx::Topology::TriangleList,
         patch_index: 0,
+        sample_mask: u32::max_value(),
         pass: swap_chain.get_backbuffer_pass(),
     })?;
 
@@ -170,10 +177,10 @@ fn main() -> Result<(), hotline_rs::Error> {
         cmd.close()?;
 
         // execute command buffer
-        device.execute(&cmd);
+        device.execute(&cmd)?;
 
         // swap for the next frame
-        swap_chain.swap(&device);
+        swap_chain.swap(&device)?;
     }
 
     // must wait for the final frame to be completed
@@ -164,7 +164,13 @@ fn render_grid(
     let mut view = arc_view.lock().unwrap();
 
     let bb = view.cmd_buf.get_backbuffer_index();
-    let fmt = view.pass.get_format_hash();
+    let pass = if let Some(pass) = &view.pass {
+        pass
+    }
+    else {
+        return;
+    };
+    let fmt = pass.get_format_hash();
 
     let pipeline = pmfx.get_render_pipeline_for_format("imdraw_3d", fmt);
     if pipeline.is_err() {
@@ -200,12 +206,12 @@ fn render_grid(
 
     imdraw.submit(&mut device.0, bb as usize).unwrap();
 
-    view.cmd_buf.begin_render_pass(&view.pass);
+    view.cmd_buf.begin_render_pass(pass);
     view.cmd_buf.set_viewport(&view.viewport);
     view.cmd_buf.set_scissor_rect(&view.scissor_rect);
 
     view.cmd_buf.set_render_pipeline(&pipeline);
-    view.cmd_buf.push_constants(0, 16, 0, &camera.view_projection_matrix);
+    view.cmd_buf.push_constants_typed(0, 0, &camera.view_projection_matrix);
 
     imdraw.draw_3d(&mut view.cmd_buf, bb as usize);
 
@@ -367,13 +373,14 @@ impl BevyPlugin {
 
             // flag missing views
             for (k, v) in &*view_errors {
-                if !queue.contains(k) {
+                if !queue.iter().any(|node| node.name == *k) {
                     client.imgui.colour_text(&format!("  {}: error: `{}`", k, v), error_col);
                 }
             }
 
             // flag errors with present views
-            for f in queue {
+            for node in queue {
+                let f = &node.name;
                 if view_errors.contains_key(f) {
                     client.imgui.colour_text(&format!("  {}: error: `{}`", f, view_errors[f]), error_col);
                 }
@@ -564,6 +571,12 @@ impl Plugin<gfx_platform::Device, os_platform::App> for BevyPlugin {
                 }
             }
 
+            // debug wireframe toggle for render functions which select the wireframe pipeline permutation
+            let mut wireframe = client.pmfx.is_wireframe();
+            if client.imgui.checkbox("Wireframe", &mut wireframe) {
+                client.pmfx.set_wireframe(wireframe);
+            }
+
             client = self.schedule_ui(client);
         }
 
@@ -123,8 +123,15 @@ pub fn subdivide_triangle(t0: &Vertex3D, t1: &Vertex3D, t2: &Vertex3D, order: u3
 
 /// Utility to create faceted meshes with varying index sizes depending on the index requirements
 fn create_mesh_3d<D: gfx::Device>(dev: &mut D, vertices: Vec<Vertex3D>, indices: Vec<usize>) -> pmfx::Mesh<D> {
-    let max_index = vertices.len();
-    let index_buffer = if max_index > 65535 {
+    // pick 16 bit indices if all vertices can be addressed, otherwise use 32 bit
+    let index_format = if vertices.len() > 65536 {
+        gfx::Format::R32u
+    }
+    else {
+        gfx::Format::R16u
+    };
+
+    let index_buffer = if matches!(index_format, gfx::Format::R32u) {
         let mut indices32 : Vec<u32> = Vec::new();
         for i in &indices {
             indices32.push(*i as u32);
@@ -168,7 +175,8 @@ fn create_mesh_3d<D: gfx::Device>(dev: &mut D, vertices: Vec<Vertex3D>, indices:
             Some(vertices.as_slice())
         ).unwrap(),
         ib: index_buffer,
-        num_indices: indices.len() as u32
+        num_indices: indices.len() as u32,
+        index_format
     }
 }
 
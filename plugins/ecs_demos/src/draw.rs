@@ -90,4 +90,67 @@ pub fn setup_draw_indexed(
         MeshComponent(cube_mesh.clone()),
         WorldMatrix(pos * scale)
     ));
-}
\ No newline at end of file
+}
+
+/// Setup a grid of cubes drawn with a single draw indexed instanced call, world matrices are packed into a per-instance
+/// vertex buffer. Requires the `mesh_debug_instanced` render graph from the `data/shaders/debug` pmfx, which is built
+/// from the data repository alongside `mesh_debug`
+#[no_mangle]
+pub fn draw_indexed_instanced(client: &mut Client<gfx_platform::Device, os_platform::App>) -> ScheduleInfo {
+    client.pmfx.load(&hotline_rs::get_data_path("data/shaders/debug").as_str()).unwrap();
+
+    ScheduleInfo {
+        setup: systems![
+            "setup_draw_indexed_instanced"
+        ],
+        update: systems![
+            "update_cameras",
+            "update_main_camera_config"
+        ],
+        render_graph: "mesh_debug_instanced".to_string(),
+    }
+}
+
+#[no_mangle]
+pub fn setup_draw_indexed_instanced(
+    mut device: bevy_ecs::change_detection::ResMut<DeviceRes>,
+    mut commands: bevy_ecs::system::Commands) {
+
+    let cube_mesh = hotline_rs::primitives::create_cube_mesh(&mut device.0);
+    let dim = 64;
+    let dim2 = dim / 2;
+    let cube_size = 2.5;
+
+    let half_extent = dim2 as f32 * cube_size;
+
+    let mut world_matrices = Vec::new();
+    for y in 0..dim {
+        for x in 0..dim {
+            let wave_x = f32::abs(f32::sin((x as f32) / 20.0 as f32)) * 20.0;
+            let wave_y = f32::abs(f32::sin((y as f32) / 20.0 as f32)) * 20.0;
+            let wave_h = f32::cos(y as f32) + f32::sin(x as f32 / 0.5);
+
+            let pos = Mat4f::from_translation(
+                vec3f(
+                    x as f32 * cube_size - half_extent,
+                    50.0,
+                    y as f32 * cube_size - cube_size * dim as f32 + half_extent
+                )
+            );
+
+            let scale = Mat4::from_scale(vec3f(1.0, wave_x + wave_y + wave_h, 1.0));
+            world_matrices.push(WorldMatrix(pos * scale));
+        }
+    }
+
+    // the instances are static so we only need a single buffer written once
+    let instance_data = pack_world_matrices(world_matrices.iter());
+    let mut instance_buffer = pmfx::InstanceBuffer::create(
+        &mut device.0, std::mem::size_of::<Mat4f>(), instance_data.len(), 1).unwrap();
+    instance_buffer.update_all(&mut device.0, &instance_data).unwrap();
+
+    commands.spawn((
+        MeshComponent(cube_mesh),
+        InstanceBufferComponent(instance_buffer)
+    ));
+}
@@ -22,6 +22,7 @@ pub fn get_demos_ecs_demos() -> Vec<String> {
         "primitives",
         "draw_indexed",
         "draw_indexed_push_constants",
+        "draw_indexed_instanced",
 
         // tests
         "test_missing_demo",
@@ -42,9 +43,11 @@ pub fn get_system_ecs_demos(name: String, view_name: String) -> Option<SystemDes
         "setup_draw_indexed" => system_func![setup_draw_indexed],
         "setup_primitives" => system_func![setup_primitives],
         "setup_draw_indexed_push_constants" => system_func![setup_draw_indexed_push_constants],
+        "setup_draw_indexed_instanced" => system_func![setup_draw_indexed_instanced],
 
         // render functions
         "render_meshes" => render_func![render_meshes, view_name],
+        "render_meshes_instanced" => render_func![render_meshes_instanced, view_name, (&MeshComponent, &InstanceBufferComponent)],
 
         // test functions
         "render_missing_camera" => render_func![render_missing_camera, view_name],
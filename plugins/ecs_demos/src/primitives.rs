@@ -33,6 +33,7 @@ pub fn setup_primitives(
 
     let meshes = vec![
         hotline_rs::primitives::create_plane_mesh(&mut device.0, 1),
+        hotline_rs::primitives::create_grid_mesh(&mut device.0, 4, 8),
         
         hotline_rs::primitives::create_tetrahedron_mesh(&mut device.0),
         hotline_rs::primitives::create_cube_mesh(&mut device.0),
@@ -43,6 +44,7 @@ pub fn setup_primitives(
 
         crate::dev::create_sphere_mesh(&mut device.0, 16),
         crate::dev::create_sphere_mesh_ex(&mut device.0, 16, 8, true),
+        hotline_rs::primitives::create_uv_sphere_mesh(&mut device.0, 8, 16),
 
         crate::dev::create_prism_mesh(&mut device.0, 3, false, true),
         crate::dev::create_prism_mesh(&mut device.0, 4, false, true),
@@ -95,18 +97,25 @@ pub fn render_meshes(
     mesh_draw_query: bevy_ecs::prelude::Query<(&WorldMatrix, &MeshComponent)>) -> Result<(), hotline_rs::Error> {
         
     let pmfx = &pmfx.0;
+    let pass = view.get_pass()?;
 
-    let fmt = view.pass.get_format_hash();
-    let mesh_debug = pmfx.get_render_pipeline_for_format(&view.view_pipeline, fmt)?;
+    let fmt = pass.get_format_hash();
+    let permutation = if pmfx.is_wireframe() {
+        pmfx::PERMUTATION_WIREFRAME
+    }
+    else {
+        0
+    };
+    let mesh_debug = pmfx.get_render_pipeline_permutation_for_format(&view.view_pipeline, permutation, fmt)?;
     let camera = pmfx.get_camera_constants(&view.camera)?;
 
     // setup pass
-    view.cmd_buf.begin_render_pass(&view.pass);
+    view.cmd_buf.begin_render_pass(pass);
     view.cmd_buf.set_viewport(&view.viewport);
     view.cmd_buf.set_scissor_rect(&view.scissor_rect);
 
     view.cmd_buf.set_render_pipeline(&mesh_debug);
-    view.cmd_buf.push_constants(0, 16 * 3, 0, gfx::as_u8_slice(camera));
+    view.cmd_buf.push_constants_typed(0, 0, camera);
 
     // let inv_rot = Mat3f::from(camera.view_matrix.transpose());
 
@@ -114,7 +123,7 @@ pub fn render_meshes(
 
         //let bbmat = world_matrix.0 * Mat4f::from(inv_rot);
 
-        view.cmd_buf.push_constants(1, 16, 0, &world_matrix.0);
+        view.cmd_buf.push_constants_typed(1, 0, &world_matrix.0);
         view.cmd_buf.set_index_buffer(&mesh.0.ib);
         view.cmd_buf.set_vertex_buffer(&mesh.0.vb, 0);
         view.cmd_buf.draw_indexed_instanced(mesh.0.num_indices, 1, 0, 0, 0);
@@ -124,4 +133,42 @@ pub fn render_meshes(
     view.cmd_buf.end_render_pass();
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Renders each entity's mesh with a single instanced draw call for all of the instances in its instance buffer
+#[no_mangle]
+pub fn render_meshes_instanced(
+    pmfx: &bevy_ecs::prelude::Res<PmfxRes>,
+    view: &pmfx::View<gfx_platform::Device>,
+    mesh_draw_query: bevy_ecs::prelude::Query<(&MeshComponent, &InstanceBufferComponent)>) -> Result<(), hotline_rs::Error> {
+
+    let pmfx = &pmfx.0;
+    let pass = view.get_pass()?;
+
+    let fmt = pass.get_format_hash();
+    let permutation = if pmfx.is_wireframe() {
+        pmfx::PERMUTATION_WIREFRAME
+    }
+    else {
+        0
+    };
+    let pipeline = pmfx.get_render_pipeline_permutation_for_format(&view.view_pipeline, permutation, fmt)?;
+    let camera = pmfx.get_camera_constants(&view.camera)?;
+
+    // setup pass
+    view.cmd_buf.begin_render_pass(pass);
+    view.cmd_buf.set_viewport(&view.viewport);
+    view.cmd_buf.set_scissor_rect(&view.scissor_rect);
+
+    view.cmd_buf.set_render_pipeline(&pipeline);
+    view.cmd_buf.push_constants_typed(0, 0, camera);
+
+    for (mesh, instance_buffer) in &mesh_draw_query {
+        pmfx::draw_mesh_instanced(&view.cmd_buf, &mesh.0, &instance_buffer.0);
+    }
+
+    // end / transition / execute
+    view.cmd_buf.end_render_pass();
+
+    Ok(())
+}
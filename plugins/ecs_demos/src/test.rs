@@ -133,7 +133,8 @@ pub fn render_missing_pipeline(
     _: bevy_ecs::prelude::Query<(&WorldMatrix, &MeshComponent)>) -> Result<(), hotline_rs::Error> {
         
     let pmfx = &pmfx.0;
-    let fmt = view.pass.get_format_hash();
+    let pass = view.get_pass()?;
+    let fmt = pass.get_format_hash();
     pmfx.get_render_pipeline_for_format("missing", fmt)?;
 
     Ok(())
@@ -45,6 +45,8 @@ pub struct HotlineInfo {
     pub render_target_heap_size: usize,
     /// Size of the default device heap for depth stencil targets
     pub depth_stencil_heap_size: usize,
+    /// Size of the default device heap for samplers
+    pub sampler_heap_size: usize,
     /// Optional user config, the default will be automatically located in the file system, this allows to override the launch configuration
     pub user_config: Option<UserConfig>
 }
@@ -72,6 +74,7 @@ impl Default for HotlineInfo {
             shader_heap_size: 1024,
             render_target_heap_size: 128,
             depth_stencil_heap_size: 64,
+            sampler_heap_size: 16,
             user_config: None
         }
     }
@@ -165,6 +168,7 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
             shader_heap_size: info.shader_heap_size,
             render_target_heap_size: info.render_target_heap_size,
             depth_stencil_heap_size: info.depth_stencil_heap_size,
+            sampler_heap_size: info.sampler_heap_size,
         });
     
         // main window
@@ -179,7 +183,10 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
         let swap_chain_info = gfx::SwapChainInfo {
             num_buffers: info.num_buffers,
             format: gfx::Format::RGBA8n,
-            clear_colour: info.clear_colour
+            clear_colour: info.clear_colour,
+            max_frame_latency: None,
+            sync_interval: 1,
+            usage: gfx::TextureUsage::NONE,
         };
         let mut swap_chain = device.create_swap_chain::<A>(&swap_chain_info, &main_window)?;
 
@@ -336,10 +343,11 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
         }
     }
 
-    /// Render and display a pmfx target 'blit_view_name' to the main window, draw imgui and swap buffers
-    pub fn present(&mut self, blit_view_name: &str) {
+    /// Render and display a pmfx target 'blit_view_name' to the main window, draw imgui and swap buffers.
+    /// Returns an error if the device has been removed
+    pub fn present(&mut self, blit_view_name: &str) -> Result<(), super::Error> {
         // execute pmfx command buffers first
-        self.pmfx.execute(&mut self.device);
+        self.pmfx.execute(&mut self.device)?;
 
         // main pass
         self.cmd_buf.transition_barrier(&gfx::TransitionBarrier {
@@ -393,9 +401,10 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
         self.cmd_buf.close().unwrap();
 
         // execute the main window command buffer + swap
-        self.device.execute(&self.cmd_buf);
-        self.swap_chain.swap(&self.device);
+        self.device.execute(&self.cmd_buf)?;
+        self.swap_chain.swap(&self.device)?;
         self.device.clean_up_resources(&self.swap_chain);
+        Ok(())
     }
 
     /// Wait for the last submitted frame to complete to ensure safe shutdown once all in-flight resources are no longer needed
@@ -724,7 +733,12 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
                 self.imgui.image_window("main_dock", tex);
             }
 
-            self.present("main_colour");
+            if let Err(err) = self.present("main_colour") {
+                println!("hotline_rs::client:: {}", err.msg);
+                if self.device.is_device_removed() {
+                    break;
+                }
+            }
         }
 
         // save out values for next time
@@ -732,6 +746,9 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
         self.imgui.save_ini_settings();
 
         self.wait_for_last_frame();
+        if let Err(e) = self.pmfx.save_pipeline_libraries() {
+            println!("hotline_rs::client:: failed to save pipeline libraries: {}", e.msg);
+        }
     }
 
     /// Very simple run loop which can take control of your application, you could roll your own
@@ -742,7 +759,9 @@ impl<D, A> Client<D, A> where D: gfx::Device, A: os::App {
         
         self.pmfx.show_ui(&mut self.imgui, true);
         self = self.update_plugins();
-        self.present("main_colour");
+        if let Err(err) = self.present("main_colour") {
+            println!("hotline_rs::client:: {}", err.msg);
+        }
 
         self.wait_for_last_frame();
     }
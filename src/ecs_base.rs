@@ -119,9 +119,18 @@ pub struct MainCamera;
 #[derive(Component)]
 pub struct MeshComponent(pub pmfx::Mesh<gfx_platform::Device>);
 
+#[derive(Component)]
+pub struct InstanceBufferComponent(pub pmfx::InstanceBuffer<gfx_platform::Device>);
+
 #[derive(Component)]
 pub struct Name(pub String);
 
+/// Packs the matrices from `WorldMatrix` components into a contiguous vec which can be written into an
+/// `InstanceBuffer` with a stride of `size_of::<Mat4f>()`
+pub fn pack_world_matrices<'a>(world_matrices: impl Iterator<Item = &'a WorldMatrix>) -> Vec<Mat4f> {
+    world_matrices.map(|world_matrix| world_matrix.0).collect()
+}
+
 #[macro_export]
 macro_rules! system_func {
     ($func:expr) => {
@@ -133,18 +142,25 @@ macro_rules! system_func {
 macro_rules! render_func {
     ($func:expr, $view:expr) => {
         Some(render_func_closure![$func, $view].into_descriptor())
+    };
+    ($func:expr, $view:expr, $query:ty) => {
+        Some(render_func_closure![$func, $view, $query].into_descriptor())
     }
 }
 
 /// This macro can be used to export a system render function for bevy ecs. You can pass a compatible 
 /// system function with a `view` name which can be looked up when the function is called
-/// so that a single render function can have different views
+/// so that a single render function can have different views, the query defaults to `(&WorldMatrix, &MeshComponent)`
+/// and a custom query type can be passed as the 3rd argument
 #[macro_export]
 macro_rules! render_func_closure {
     ($func:expr, $view_name:expr) => {
+        render_func_closure![$func, $view_name, (&WorldMatrix, &MeshComponent)]
+    };
+    ($func:expr, $view_name:expr, $query:ty) => {
         move |
             pmfx: Res<PmfxRes>,
-            qmesh: Query::<(&WorldMatrix, &MeshComponent)>| {
+            qmesh: Query::<$query>| {
 
                 let view = pmfx.0.get_view(&$view_name);
 
@@ -2,6 +2,7 @@ use crate::os;
 use std::any::Any;
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash};
+use std::collections::HashMap;
 
 /// Implemets this interface with a Direct3D12 backend.
 #[cfg(target_os = "windows")]
@@ -21,6 +22,7 @@ macro_rules! data {
 }
 
 /// 3-Dimensional struct for compute shader thread count / thread group size.
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Size3 {
     pub x: u32,
     pub y: u32,
@@ -57,6 +59,18 @@ pub struct ScissorRect {
     pub bottom: i32,
 }
 
+/// Structure to specify a box within a texture subresource for copies, the `left`, `top` and `front` coordinates
+/// are inclusive and `right`, `bottom` and `back` are exclusive.
+#[derive(Copy, Clone)]
+pub struct TextureRegion {
+    pub left: u32,
+    pub top: u32,
+    pub front: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub back: u32,
+}
+
 /// Format for resource types (textures / buffers).
 /// n = normalised unsigned integer,
 /// u = unsigned integer,
@@ -65,6 +79,7 @@ pub struct ScissorRect {
 #[derive(Copy, Clone, Serialize, Deserialize, Hash)]
 pub enum Format {
     Unknown,
+    R8u,
     R16n,
     R16u,
     R16i,
@@ -79,9 +94,12 @@ pub enum Format {
     RGB32i,
     RGB32f,
     RGBA8n,
+    RGBA8nSRGB,
     RGBA8u,
     RGBA8i,
     BGRA8n,
+    BGRA8nSRGB,
+    RGB10A2n,
     RGBA16u,
     RGBA16i,
     RGBA16f,
@@ -92,6 +110,18 @@ pub enum Format {
     D32f,
     D24nS8u,
     D16n,
+    /// Block compressed 4x4 texels in 8 bytes, rgb with 1 bit alpha
+    BC1n,
+    /// Block compressed 4x4 texels in 16 bytes, rgba
+    BC3n,
+    /// Block compressed 4x4 texels in 8 bytes, single channel
+    BC4n,
+    /// Block compressed 4x4 texels in 16 bytes, two channels
+    BC5n,
+    /// Block compressed 4x4 texels in 16 bytes, unsigned half float rgb
+    BC6H,
+    /// Block compressed 4x4 texels in 16 bytes, high quality rgba
+    BC7n,
 }
 
 /// Information to create a device, it contains default heaps for resource views
@@ -107,6 +137,8 @@ pub struct DeviceInfo {
     pub render_target_heap_size: usize,
     /// space for depth stencil targets.
     pub depth_stencil_heap_size: usize,
+    /// space for samplers created with `Device::create_sampler`.
+    pub sampler_heap_size: usize,
 }
 
 /// Information returned from `Device::get_adapter_info`.
@@ -124,6 +156,51 @@ pub struct AdapterInfo {
     pub shared_system_memory: usize,
     /// List of available adapter descriptons.
     pub available: Vec<String>,
+    /// True if an output connected to the adapter is in HDR mode and can present HDR10 content.
+    pub hdr_supported: bool,
+    /// Maximum luminance in nits of the brightest connected output.
+    pub max_luminance: f32,
+    /// Minimum luminance in nits of the brightest connected output.
+    pub min_luminance: f32,
+    /// Maximum luminance in nits the brightest connected output can sustain over the full frame.
+    pub max_full_frame_luminance: f32,
+    /// Resource binding tier from 1 to 3, higher tiers allow more descriptors to be bound and accessed in shaders.
+    pub resource_binding_tier: u32,
+    /// Highest shader model supported by the device as (major, minor), compare with a tuple: `shader_model >= (6, 5)`.
+    pub shader_model: (u32, u32),
+    /// Mesh shader tier, 0 if not supported or 10 for tier 1.0.
+    pub mesh_shader_tier: u32,
+    /// Ray tracing tier, 0 if not supported, 10 for tier 1.0 or 11 for tier 1.1 which adds inline ray queries.
+    pub raytracing_tier: u32,
+    /// Variable rate shading tier, 0 if not supported, 1 for per draw rates or 2 which adds per primitive and
+    /// screen space image rates.
+    pub variable_shading_rate_tier: u32,
+    /// Size in pixels of the square tile covered by each texel of a shading rate image, 0 if not supported.
+    pub shading_rate_image_tile_size: u32,
+    /// Minimum number of lanes in a wave, wave intrinsics require shader model 6.
+    pub wave_lane_count_min: u32,
+    /// Maximum number of lanes in a wave.
+    pub wave_lane_count_max: u32,
+}
+
+/// Memory budget for a single memory segment returned as part of `VideoMemoryInfo`.
+#[derive(Copy, Clone, Default)]
+pub struct MemoryBudget {
+    /// Amount of memory in bytes the application can use before it may be penalised by the os.
+    pub budget: usize,
+    /// Memory in bytes currently in use by the application.
+    pub current_usage: usize,
+    /// Memory in bytes which can be reserved by the application.
+    pub available_for_reservation: usize,
+}
+
+/// Information returned from `Device::get_video_memory_info`, queried at the time of calling.
+#[derive(Copy, Clone, Default)]
+pub struct VideoMemoryInfo {
+    /// Memory local to the gpu (dedicated video memory on discrete adapters).
+    pub local: MemoryBudget,
+    /// Memory not local to the gpu (shared system memory).
+    pub non_local: MemoryBudget,
 }
 
 /// Information to create a desciptor heap... `Device` will contain default heaps, but you can create your own if required.
@@ -139,18 +216,75 @@ pub struct HeapInfo {
 pub enum HeapType {
     /// For shader resource view, constant buffer or unordered access.
     Shader,
+    /// Non shader visible heap for shader resource view, constant buffer or unordered access, these are the cpu
+    /// descriptors required by `CmdBuf::clear_unordered_access_view_uint` and `clear_unordered_access_view_float`
+    ShaderCpu,
     RenderTarget,
     DepthStencil,
     Sampler,
 }
 
+/// Command queues which command buffers are submitted to, work on the compute queue can overlap with work on the
+/// graphics queue. Use `Device::queue_signal` and `Device::queue_wait` to synchronise across queues
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum QueueType {
+    /// Direct queue for graphics, compute and copy commands, this is the queue swap chains present from
+    #[serde(alias = "graphics")]
+    Graphics,
+    /// Async compute queue for compute and copy commands only
+    #[serde(alias = "compute")]
+    Compute,
+}
+
 /// Information to pass to `Device::create_swap_chain`.
 pub struct SwapChainInfo {
     pub num_buffers: u32,
-    /// Must be BGRA8n, RGBA8n or RGBA16f.
+    /// Must be BGRA8n, RGBA8n, RGB10A2n or RGBA16f. RGB10A2n outputs HDR10 (ST.2084 with BT.2020 primaries) and
+    /// RGBA16f outputs linear scRGB, when supported by the display.
     pub format: Format,
     /// Colour for clearing the window when using the backbuffer pass, use None to not clear.
     pub clear_colour: Option<ClearColour>,
+    /// Maximum number of frames the CPU can queue ahead of the GPU (1-16), use None for the platform default (3).
+    /// `SwapChain::new_frame` blocks on the frame latency waitable object as well as the fence of the backbuffer
+    /// being reused, so a latency of 1 caps the CPU to a single frame ahead regardless of `num_buffers`.
+    pub max_frame_latency: Option<u32>,
+    /// Number of vertical blanks to wait before presenting (0-4), 0 presents immediately and allows tearing on
+    /// adapters and displays which support it.
+    pub sync_interval: u32,
+    /// Additional usage for the backbuffers, render target usage is always implied.
+    /// SHADER_RESOURCE allocates an srv for each backbuffer so it can be sampled, and preserves backbuffer contents
+    /// after present so the previous frame can be sampled through `get_previous_backbuffer_texture`.
+    pub usage: TextureUsage,
+}
+
+/// Arguments for a single draw issued through `CmdBuf::draw_indirect`, matches the layout of `draw_instanced`
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct DrawArguments {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub start_vertex: u32,
+    pub start_instance: u32,
+}
+
+/// Arguments for a single draw issued through `CmdBuf::draw_indexed_indirect`, matches the layout of `draw_indexed_instanced`
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct DrawIndexedArguments {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub start_index: u32,
+    pub base_vertex: i32,
+    pub start_instance: u32,
+}
+
+/// Arguments for a single compute dispatch issued through `CmdBuf::dispatch_indirect`, matches `dispatch` group count
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct DispatchArguments {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
 }
 
 /// Information to create a buffer through `Device::create_buffer`.
@@ -168,12 +302,46 @@ pub struct BufferInfo {
     pub num_elements: usize,
 }
 
+/// Information to create a ring buffer through `Device::create_ring_buffer`.
+#[derive(Copy, Clone)]
+pub struct RingBufferInfo {
+    /// Size in bytes available for transient allocations each frame.
+    pub frame_size: usize,
+    /// Number of frames to buffer, this should match the number of buffers in the swap chain.
+    pub num_buffers: u32,
+}
+
+/// A transient allocation made from a `RingBuffer`, valid until the ring buffer wraps back around to this frame.
+#[derive(Copy, Clone)]
+pub struct RingBufferAllocation {
+    /// CPU pointer to write data into.
+    pub cpu_ptr: *mut u8,
+    /// GPU virtual address to bind the data with.
+    pub gpu_address: u64,
+    /// Size of the allocation in bytes.
+    pub size: usize,
+}
+
 /// Describes how a buffer will be used on the GPU.
 #[derive(Copy, Clone)]
 pub enum BufferUsage {
     Vertex,
     Index,
+    /// Constant buffer with a cbv in the shader heap, the buffer is padded to a multiple of 256 bytes
     ConstantBuffer,
+    /// Contains `DrawArguments` or `DrawIndexedArguments` (or a u32 draw count) for indirect draws
+    IndirectArgument,
+    /// Read only `StructuredBuffer` of `num_elements` with `stride` bytes each, accessed through `get_srv_index`
+    Structured,
+    /// Read-write `RWStructuredBuffer`, accessed through `get_srv_index` and `get_uav_index`
+    RWStructured,
+    /// `RWStructured` with a hidden counter for `AppendStructuredBuffer`, `ConsumeStructuredBuffer` or
+    /// `IncrementCounter`, the counter is stored after the elements in the same buffer
+    AppendStructured,
+    /// Read only `ByteAddressBuffer` accessed through `get_srv_index`, the size must be a multiple of 4 bytes
+    Raw,
+    /// Read-write `RWByteAddressBuffer` accessed through `get_srv_index` and `get_uav_index`
+    RWRaw,
 }
 
 /// Information to create a shader through `Device::create_shader`.
@@ -187,10 +355,12 @@ pub struct ShaderInfo {
 
 /// Information required to compile a shader from source code.
 pub struct ShaderCompileInfo {
-    /// The name of the entry point function in the shader to compile.
+    /// The name of the entry point function in the shader to compile, ignored for library targets (lib_6_3) which
+    /// export all of their entry points.
     pub entry_point: String,
     /// The target you wish to compile for, this is paltform specific.
-    /// hlsl: (vs_5_0, ps_5_0, vs_6_0, ps_6_0).
+    /// hlsl: (vs_5_0, ps_5_0, vs_6_0, ps_6_0). Shader model 6 targets are compiled to DXIL with DXC, which
+    /// requires dxcompiler.dll at runtime, earlier targets are compiled to DXBC with FXC.
     pub target: String,
     /// Flags to pass to the compiler.
     pub flags: ShaderCompileFlags,
@@ -202,6 +372,18 @@ pub enum ShaderType {
     Vertex,
     Fragment,
     Compute,
+    /// Outputs meshlet vertices and primitives for `Device::create_mesh_pipeline`, requires shader model 6.5.
+    Mesh,
+    /// Optionally launches mesh shader groups in a mesh pipeline, requires shader model 6.5.
+    Amplification,
+    /// Outputs tessellation factors and control points for `Topology::PatchList` pipelines.
+    Hull,
+    /// Evaluates the vertices generated by the tessellator from hull shader control points.
+    Domain,
+    /// Emits or discards primitives per input primitive, ie. for single pass cubemap rendering.
+    Geometry,
+    /// A library of ray tracing entry points for `Device::create_raytracing_pipeline`, requires shader model 6.3.
+    Library,
 }
 
 bitflags! {
@@ -216,6 +398,7 @@ bitflags! {
     }
 
     /// Render target write mask flags.
+    #[derive(Serialize, Deserialize)]
     pub struct WriteMask : u8 {
         const RED = 1<<0;
         const GREEN = 1<<1;
@@ -240,6 +423,85 @@ pub struct DescriptorLayout {
     pub static_samplers: Option<Vec<SamplerBinding>>,
 }
 
+impl DescriptorLayout {
+    /// Append unbounded shader resource and unordered access ranges starting at `t0` and `u0` in `register_space`
+    /// visible to all stages, both ranges start at the beginning of the heap bound with `CmdBuf::set_bindless_heap`
+    pub fn with_bindless_table(mut self, register_space: u32) -> Self {
+        let bindings = self.bindings.get_or_insert_with(Vec::new);
+        for binding_type in [DescriptorType::ShaderResource, DescriptorType::UnorderedAccess] {
+            bindings.push(DescriptorBinding {
+                visibility: ShaderVisibility::All,
+                shader_register: 0,
+                register_space,
+                binding_type,
+                num_descriptors: None,
+            });
+        }
+        self
+    }
+
+    /// Create a layout from the reflection of each shader stage with the visibility of that stage, each resource is
+    /// given its own binding and resources used by more than one stage are visible to all stages
+    pub fn from_reflection(stages: &[(ShaderVisibility, &ShaderReflection)]) -> Self {
+        let mut bindings: Vec<DescriptorBinding> = Vec::new();
+        for (visibility, reflection) in stages {
+            for resource in &reflection.bindings {
+                let existing = bindings.iter_mut().find(|binding| {
+                    binding.binding_type == resource.binding_type &&
+                    binding.shader_register == resource.shader_register &&
+                    binding.register_space == resource.register_space
+                });
+                if let Some(existing) = existing {
+                    if existing.visibility != *visibility {
+                        existing.visibility = ShaderVisibility::All;
+                    }
+                    continue;
+                }
+                bindings.push(DescriptorBinding {
+                    visibility: *visibility,
+                    shader_register: resource.shader_register,
+                    register_space: resource.register_space,
+                    binding_type: resource.binding_type.clone(),
+                    num_descriptors: resource.num_descriptors,
+                });
+            }
+        }
+        DescriptorLayout {
+            bindings: if bindings.is_empty() { None } else { Some(bindings) },
+            ..Default::default()
+        }
+    }
+
+    /// Returns the resources in `reflection` which are not bound by any push constants, bindings or static samplers of
+    /// this layout
+    pub fn find_missing_bindings<'a>(&self, reflection: &'a ShaderReflection) -> Vec<&'a ShaderResourceBinding> {
+        reflection.bindings.iter().filter(|resource| {
+            let register = resource.shader_register;
+            let space = resource.register_space;
+            let push_constant = matches!(resource.binding_type, DescriptorType::ConstantBuffer) &&
+                self.push_constants.iter().flatten().any(|constants| {
+                    constants.shader_register == register && constants.register_space == space
+                });
+            let binding = self.bindings.iter().flatten().any(|binding| {
+                let binding_type = match binding.binding_type {
+                    DescriptorType::RootConstantBuffer => DescriptorType::ConstantBuffer,
+                    DescriptorType::RootShaderResource => DescriptorType::ShaderResource,
+                    DescriptorType::RootUnorderedAccess => DescriptorType::UnorderedAccess,
+                    _ => binding.binding_type.clone()
+                };
+                let end = binding.num_descriptors.map(|count| binding.shader_register.saturating_add(count));
+                binding_type == resource.binding_type && binding.register_space == space &&
+                    register >= binding.shader_register && end.map_or(true, |end| register < end)
+            });
+            let static_sampler = matches!(resource.binding_type, DescriptorType::Sampler) &&
+                self.static_samplers.iter().flatten().any(|sampler| {
+                    sampler.shader_register == register && sampler.register_space == space
+                });
+            !(push_constant || binding || static_sampler)
+        }).collect()
+    }
+}
+
 /// Describes a range of resources for access on the GPU.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DescriptorBinding {
@@ -256,7 +518,7 @@ pub struct DescriptorBinding {
 }
 
 /// Describes the type of descriptor binding to create.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum DescriptorType {
     /// Used for textures or structured buffers.
     ShaderResource,
@@ -266,6 +528,38 @@ pub enum DescriptorType {
     UnorderedAccess,
     /// Used for texture samplers.
     Sampler,
+    /// Used for a cbuffer bound inline in the root signature rather than through a heap, bind a buffer and byte offset
+    /// with `CmdBuf::set_constant_buffer`. Root descriptors take the slots after push constants in binding order and
+    /// `num_descriptors` is ignored
+    RootConstantBuffer,
+    /// Used for a structured or raw buffer bound inline in the root signature, bind a buffer and byte offset with
+    /// `CmdBuf::set_root_shader_resource`. Slots are assigned the same as `RootConstantBuffer`
+    RootShaderResource,
+    /// Used for a read-write structured or raw buffer bound inline in the root signature, bind a buffer and byte offset
+    /// with `CmdBuf::set_root_unordered_access`. Slots are assigned the same as `RootConstantBuffer`
+    RootUnorderedAccess,
+}
+
+/// Resources bound by a shader, returned from `Device::reflect_shader`.
+#[derive(Clone, Default)]
+pub struct ShaderReflection {
+    /// All resources the shader binds, in the order they are declared.
+    pub bindings: Vec<ShaderResourceBinding>,
+}
+
+/// A single resource or array of resources bound by a shader.
+#[derive(Clone)]
+pub struct ShaderResourceBinding {
+    /// Name of the resource in the shader source.
+    pub name: String,
+    /// Register index the resource is bound to.
+    pub shader_register: u32,
+    /// Register space the resource is bound to.
+    pub register_space: u32,
+    /// Type of the resource, cbuffers are reported as `ConstantBuffer` even if they are bound as push constants.
+    pub binding_type: DescriptorType,
+    /// Number of descriptors in an array of resources, `None` for unbounded arrays.
+    pub num_descriptors: Option<u32>,
 }
 
 /// Describes the visibility of which shader stages can access a descriptor.
@@ -275,6 +569,9 @@ pub enum ShaderVisibility {
     Vertex,
     Fragment,
     Compute,
+    Hull,
+    Domain,
+    Geometry,
 }
 
 /// Describes space in the shader to send data to via `CmdBuf::push_constants`.
@@ -332,7 +629,8 @@ pub struct SamplerBinding {
     pub sampler_info: SamplerInfo
 }
 
-/// Info to create a sampler state object to sample textures in shaders.
+/// Info to create a sampler state object to sample textures in shaders, either baked into a `DescriptorLayout`
+/// as a static sampler or created with `Device::create_sampler` to be selected dynamically from the sampler heap.
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct SamplerInfo {
     pub filter: SamplerFilter,
@@ -385,6 +683,12 @@ pub struct RenderPipelineInfo<'stack, D: Device> {
     pub vs: Option<&'stack D::Shader>,
     /// Fragment Shader
     pub fs: Option<&'stack D::Shader>,
+    /// Hull Shader, required along with `ds` to tessellate `Topology::PatchList` primitives
+    pub hs: Option<&'stack D::Shader>,
+    /// Domain Shader
+    pub ds: Option<&'stack D::Shader>,
+    /// Geometry Shader
+    pub gs: Option<&'stack D::Shader>,
     /// Vertex shader input layout
     pub input_layout: InputLayout,
     /// Layout of shader resources (constant buffers, structured buffers, textures, etc)
@@ -397,13 +701,35 @@ pub struct RenderPipelineInfo<'stack, D: Device> {
     pub blend_info: BlendInfo,
     /// Primitive topolgy oof the input assembler
     pub topology: Topology,
-    /// only required for Topology::PatchList use 0 as default
+    /// only required for Topology::PatchList, the number of patch control points - 1, use 0 as default
     pub patch_index: u32,
+    /// Mask of which msaa samples can be written, use `u32::max_value()` to write all samples
+    pub sample_mask: u32,
     /// A valid render pass, you can share pipelines across passes providing the render target
     /// formats and sample count are the same of the passes you wish to use the pipeline on
     pub pass: &'stack D::RenderPass,
 }
 
+/// Information to create a pipeline which generates geometry with mesh shaders through `Device::create_mesh_pipeline`
+pub struct MeshPipelineInfo<'stack, D: Device> {
+    /// Optional amplification shader which launches mesh shader groups
+    pub amp: Option<&'stack D::Shader>,
+    /// Mesh shader
+    pub ms: &'stack D::Shader,
+    /// Fragment Shader
+    pub fs: Option<&'stack D::Shader>,
+    /// Layout of shader resources (constant buffers, structured buffers, textures, etc)
+    pub descriptor_layout: DescriptorLayout,
+    /// Control rasterisation of primitives
+    pub raster_info: RasterInfo,
+    /// Control depth test and stencil oprations
+    pub depth_stencil_info: DepthStencilInfo,
+    /// Control blending settings for the output merge stage
+    pub blend_info: BlendInfo,
+    /// A valid render pass, the pipeline is compatible with passes of the same formats and sample count
+    pub pass: &'stack D::RenderPass,
+}
+
 /// Indicates how the pipeline interprets vertex data at the input assembler stage
 /// This will be also used to infer primitive topology types for geometry or hull shaders
 #[derive(Copy, Clone, Serialize, Deserialize)]
@@ -498,16 +824,20 @@ pub enum StencilOp {
 }
 
 /// Information to control blending operations on render targets
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BlendInfo {
+    /// Use the alpha output of render target 0 as msaa sample coverage, ie. for alpha tested foliage
+    #[serde(default)]
     pub alpha_to_coverage_enabled: bool,
-    /// Separate blending on colour and alpha channels
+    /// Separate blend operations for each render target, this is enabled automatically when `render_target`
+    /// contains entries which differ
     pub independent_blend_enabled: bool,
-    /// Separate blend operations for each bout render targets
+    /// Blend operations for each of the pass render targets, a single entry is shared by all render targets
     pub render_target: Vec<RenderTargetBlendInfo>,
 }
 
 /// Blending operations for a single render target
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct RenderTargetBlendInfo {
     pub blend_enabled: bool,
     pub logic_op_enabled: bool,
@@ -522,6 +852,7 @@ pub struct RenderTargetBlendInfo {
 }
 
 /// Controls how the source and destination terms in blend equation are derrived
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BlendFactor {
     Zero,
     One,
@@ -543,6 +874,7 @@ pub enum BlendFactor {
 }
 
 /// Controls how the source and destination terms are combined: final = src (op) dest
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BlendOp {
     Add,
     Subtract,
@@ -552,6 +884,7 @@ pub enum BlendOp {
 }
 
 /// The logical operation to configure for a render target blend with logic op enabled
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum LogicOp {
     Clear,
     Set,
@@ -571,6 +904,35 @@ pub enum LogicOp {
     OrInverted,
 }
 
+/// Number of pixels (width x height) shaded by a single fragment shader invocation with variable rate shading,
+/// 2x4, 4x2 and 4x4 require `AdapterInfo::variable_shading_rate_tier` 2 on some devices
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum ShadingRate {
+    Rate1x1,
+    Rate1x2,
+    Rate2x1,
+    Rate2x2,
+    Rate2x4,
+    Rate4x2,
+    Rate4x4,
+}
+
+/// Combines two shading rates, the first combiner passed to `CmdBuf::set_shading_rate` combines the draw rate with
+/// the per primitive rate and the second combines that result with the shading rate image
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum ShadingRateCombiner {
+    /// Keep the incoming rate and ignore the other
+    Passthrough,
+    /// Replace the incoming rate with the other
+    Override,
+    /// Use the finer of the two rates
+    Min,
+    /// Use the coarser of the two rates
+    Max,
+    /// Add the two rates together, clamped to the coarsest rate
+    Sum,
+}
+
 /// Information to create a compute pipeline through `Device::create_compute_pipeline`
 pub struct ComputePipelineInfo<'stack, D: Device> {
     /// Compute Shader
@@ -578,6 +940,100 @@ pub struct ComputePipelineInfo<'stack, D: Device> {
     pub descriptor_layout: DescriptorLayout,
 }
 
+bitflags! {
+    /// Flags to control how a ray tracing acceleration structure is built
+    pub struct AccelerationStructureBuildFlags: u8 {
+        /// No flags, the driver balances trace and build performance
+        const NONE = 0b00000000;
+        /// Favour faster ray traversal over build time, for static geometry
+        const PREFER_FAST_TRACE = 0b00000001;
+        /// Favour a faster build over ray traversal, for geometry which is rebuilt often
+        const PREFER_FAST_BUILD = 0b00000010;
+    }
+}
+
+/// Triangle geometry to build into a bottom level acceleration structure
+pub struct RaytracingGeometryInfo<'stack, D: Device> {
+    /// Vertex buffer, the position is read from the start of each vertex
+    pub vertex_buffer: &'stack D::Buffer,
+    /// Format of the vertex position, RGB32f or RG32f
+    pub vertex_format: Format,
+    /// Number of vertices in `vertex_buffer`
+    pub vertex_count: usize,
+    /// Stride of each vertex in bytes
+    pub vertex_stride: usize,
+    /// Optional index buffer, if None the vertices are a triangle list
+    pub index_buffer: Option<&'stack D::Buffer>,
+    /// Format of the indices R16u or R32u, ignored if there is no index buffer
+    pub index_format: Format,
+    /// Number of indices in `index_buffer`
+    pub index_count: usize,
+    /// Opaque geometry skips any hit shaders
+    pub opaque: bool,
+}
+
+/// Information to create a bottom level acceleration structure through `Device::create_raytracing_blas`
+pub struct RaytracingBLASInfo<'stack, D: Device> {
+    pub geometry: Vec<RaytracingGeometryInfo<'stack, D>>,
+    pub flags: AccelerationStructureBuildFlags,
+}
+
+/// An instance of a bottom level acceleration structure placed into a top level acceleration structure
+pub struct RaytracingInstanceInfo<'stack, D: Device> {
+    /// The bottom level acceleration structure to instance
+    pub blas: &'stack D::RaytracingBLAS,
+    /// Row major 3x4 transform from object space to world space
+    pub transform: [f32; 12],
+    /// 24-bit value returned from `InstanceID()` in shaders
+    pub instance_id: u32,
+    /// The instance is only hit by rays where `InstanceInclusionMask & mask` is non-zero
+    pub mask: u8,
+    /// 24-bit offset of the first hit group record used by this instance in the shader binding table
+    pub hit_group_index: u32,
+}
+
+/// Information to create a top level acceleration structure through `Device::create_raytracing_tlas`
+pub struct RaytracingTLASInfo<'stack, D: Device> {
+    pub instances: Vec<RaytracingInstanceInfo<'stack, D>>,
+    pub flags: AccelerationStructureBuildFlags,
+}
+
+/// A named hit group combining entry points exported from a ray tracing library, triangle hit groups have no
+/// intersection shader
+pub struct RaytracingHitGroup {
+    pub name: String,
+    pub closest_hit: Option<String>,
+    pub any_hit: Option<String>,
+    pub intersection: Option<String>,
+}
+
+/// Information to create a ray tracing pipeline through `Device::create_raytracing_pipeline`
+pub struct RaytracingPipelineInfo<'stack, D: Device> {
+    /// Library shader (lib_6_3 or higher) containing all entry points used by the pipeline
+    pub library: &'stack D::Shader,
+    /// Hit groups made from entry points in `library`
+    pub hit_groups: Vec<RaytracingHitGroup>,
+    /// Layout of shader resources shared by all entry points, bind with the compute binding functions of `CmdBuf`
+    pub descriptor_layout: DescriptorLayout,
+    /// Maximum size in bytes of the ray payload
+    pub max_payload_size: u32,
+    /// Maximum size in bytes of hit attributes, built-in triangle intersections use 8 bytes for barycentrics
+    pub max_attribute_size: u32,
+    /// Maximum depth of nested `TraceRay` calls, 1 if only ray generation shaders trace rays
+    pub max_recursion_depth: u32,
+}
+
+/// Information to create a shader binding table through `Device::create_raytracing_shader_binding_table`, shaders
+/// and hit groups are referenced by their exported names in the pipeline
+pub struct RaytracingShaderBindingTableInfo<'stack, D: Device> {
+    pub pipeline: &'stack D::RaytracingPipeline,
+    pub ray_generation_shader: String,
+    /// Miss shaders indexed by `MissShaderIndex` of `TraceRay`
+    pub miss_shaders: Vec<String>,
+    /// Hit groups indexed by the instance `hit_group_index` and `RayContributionToHitGroupIndex` of `TraceRay`
+    pub hit_groups: Vec<String>,
+}
+
 /// Information to create a pipeline through `Device::create_texture`.
 #[derive(Copy, Clone)]
 pub struct TextureInfo {
@@ -600,6 +1056,9 @@ pub enum TextureType {
     Texture1D,
     Texture2D,
     Texture3D,
+    /// 2D texture array with 6 faces per cube, `array_levels` is the number of faces and must be a multiple of 6,
+    /// more than 6 faces creates a cubemap array
+    TextureCube,
 }
 
 bitflags! {
@@ -618,6 +1077,12 @@ bitflags! {
         const DEPTH_STENCIL = (1 << 3);
         /// Used as a target for hardware assisted video decoding operations
         const VIDEO_DECODE_TARGET = (1 << 4);
+        /// Create the resource with a typeless format so additional views with compatible formats can be created
+        /// with `Device::create_texture_view`, ie. viewing `RGBA8n` as `RGBA8nSRGB` or `D32f` as `R32f`
+        const TYPELESS = (1 << 5);
+        /// Allows the mip chain to be generated from the top mip with `Device::generate_mips`, the format must
+        /// support typed unordered access stores, check with `format_supports_generate_mips`
+        const GENERATE_MIP_MAPS = (1 << 6);
     }
 }
 
@@ -644,8 +1109,15 @@ pub struct RenderPassInfo<'stack, D: Device> {
     pub render_targets: Vec<&'stack D::Texture>,
     /// Colour to clear render target when the pass starts, use None to preserve previous contents
     pub rt_clear: Option<ClearColour>,
+    /// Optional per-target clear colours which take precedence over `rt_clear`, the length must match `render_targets`
+    pub rt_clears: Option<Vec<ClearColour>>,
+    /// Optional render target heap indices from `Device::create_render_target_view` to render into a single
+    /// subresource of each target instead of mip 0, the length must match `render_targets`
+    pub rt_views: Option<Vec<usize>>,
     /// A texture which was created with depth stencil flags
     pub depth_stencil: Option<&'stack D::Texture>,
+    /// Optional depth stencil heap index from `Device::create_depth_stencil_view` to render into a single subresource
+    pub ds_view: Option<usize>,
     /// Depth value (in view) to clear depth stencil, use None to preserve previous contents
     pub ds_clear: Option<ClearDepthStencil>,
     /// Choose to resolve multi-sample AA targets,
@@ -655,6 +1127,7 @@ pub struct RenderPassInfo<'stack, D: Device> {
 }
 
 /// Transitions are required to be performed to switch resources from reading to writing or into different formats
+/// when the state of a resource is tracked you can use `CmdBuf::transition` instead to omit `state_before`
 pub struct TransitionBarrier<'stack, D: Device> {
     pub texture: Option<&'stack D::Texture>,
     pub buffer: Option<&'stack D::Buffer>,
@@ -663,7 +1136,7 @@ pub struct TransitionBarrier<'stack, D: Device> {
 }
 
 /// All possible resource states, some for buffers and some for textures
-#[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub enum ResourceState {
     /// Used for texture only to be written to from fragment shaders
     RenderTarget,
@@ -671,6 +1144,9 @@ pub enum ResourceState {
     DepthStencil,
     /// Used for when depth testing is enabled, but depth writes are disabled
     DepthStencilReadOnly,
+    /// Used for a depth stencil buffer bound with `Device::create_read_only_depth_stencil_view`, which is depth
+    /// tested against and sampled from fragment shaders at the same time
+    DepthStencilReadOnlyShaderResource,
     /// Used for swap chain textures only, required before calling swap
     Present,
     /// Access for read/write from shaders
@@ -684,7 +1160,18 @@ pub enum ResourceState {
     /// Used as a source msaa texture to resolve into a non-msaa resource
     ResolveSrc,
     /// Used as a destination sngle sample texture to be resolved into by an msaa resource
-    ResolveDst
+    ResolveDst,
+    /// Used as a source for copy operations such as reading back a texture
+    CopySrc,
+    /// Used as a destination for copy operations
+    CopyDst,
+    /// Used as an argument or count buffer for indirect draws
+    IndirectArgument,
+    /// Readable from shaders other than fragment shaders, vertex and index buffers must be in this state when they
+    /// are built into a ray tracing acceleration structure
+    NonPixelShaderResource,
+    /// Used for a texture bound with `CmdBuf::set_shading_rate_image`
+    ShadingRateSource,
 }
 
 /// ome resources may contain subresources for resolving
@@ -727,6 +1214,43 @@ pub trait RenderPass<D: Device>: Send + Sync  {
 /// An opaque compute pipeline type..
 pub trait ComputePipeline<D: Device>: Send + Sync  {}
 
+/// A fence which is signalled from the device command queue and can be waited on from the cpu
+pub trait Fence<D: Device>: Send + Sync {
+    /// Returns the last value the fence was signalled with on the gpu
+    fn get_completed_value(&self) -> u64;
+    /// Block the calling thread until the fence has reached `value`, equivalent to `Device::wait`
+    fn wait_cpu(&self, value: u64) -> Result<(), Error>;
+}
+
+/// A bottom level acceleration structure containing geometry, build it with `CmdBuf::build_raytracing_blas`
+/// before building any top level acceleration structure which instances it
+pub trait RaytracingBLAS<D: Device>: Send + Sync {}
+
+/// A top level acceleration structure containing instances of bottom level acceleration structures, build it with
+/// `CmdBuf::build_raytracing_tlas` after the instanced bottom levels
+pub trait RaytracingTLAS<D: Device>: Send + Sync {
+    /// Return the index of the `RaytracingAccelerationStructure` in the shader heap
+    fn get_srv_index(&self) -> usize;
+}
+
+/// An opaque ray tracing pipeline, bind with `CmdBuf::set_raytracing_pipeline`
+pub trait RaytracingPipeline<D: Device>: Send + Sync {}
+
+/// Shader records of a ray tracing pipeline used to select shaders in `CmdBuf::dispatch_rays`
+pub trait RaytracingShaderBindingTable<D: Device>: Send + Sync {}
+
+/// A sampler state object allocated in the device sampler heap, which can be selected dynamically in shaders
+pub trait Sampler<D: Device>: Send + Sync {
+    /// Return the index of the sampler in the sampler heap
+    fn get_index(&self) -> usize;
+}
+
+/// A collection of compiled pipelines keyed by name, which can be serialized to disk to skip compilation on load
+pub trait PipelineLibrary<D: Device>: Send + Sync {
+    /// Serialize all pipelines in the library into a blob to pass to `Device::create_pipeline_library`
+    fn serialize(&self) -> Result<Vec<u8>, Error>;
+}
+
 /// A GPU device is used to create GPU resources, the device also contains a single a single command queue
 /// to which all command buffers will submitted and executed each frame.
 pub trait Device: 'static + Send + Sync + Sized + Any + Clone {
@@ -740,6 +1264,14 @@ pub trait Device: 'static + Send + Sync + Sized + Any + Clone {
     type RenderPass: RenderPass<Self>;
     type Heap: Heap<Self>;
     type ComputePipeline: ComputePipeline<Self>;
+    type RingBuffer: RingBuffer<Self>;
+    type PipelineLibrary: PipelineLibrary<Self>;
+    type Fence: Fence<Self>;
+    type Sampler: Sampler<Self>;
+    type RaytracingBLAS: RaytracingBLAS<Self>;
+    type RaytracingTLAS: RaytracingTLAS<Self>;
+    type RaytracingPipeline: RaytracingPipeline<Self>;
+    type RaytracingShaderBindingTable: RaytracingShaderBindingTable<Self>;
     fn create(info: &DeviceInfo) -> Self;
     fn create_heap(&self, info: &HeapInfo) -> Self::Heap;
     fn create_swap_chain<A: os::App>(
@@ -748,40 +1280,199 @@ pub trait Device: 'static + Send + Sync + Sized + Any + Clone {
         window: &A::Window,
     ) -> Result<Self::SwapChain, Error>;
     fn create_cmd_buf(&self, num_buffers: u32) -> Self::CmdBuf;
+    /// create a command buffer which is submitted to `queue` when passed to `execute`, compute command buffers can
+    /// only record compute and copy commands
+    fn create_cmd_buf_for_queue(&self, num_buffers: u32, queue: QueueType) -> Self::CmdBuf;
+    /// create `count` independent graphics command buffers each with `num_buffers` (to match the swap chain), which
+    /// can be recorded in parallel on worker threads and submitted together with `execute_multiple`
+    fn create_cmd_buf_bundle(&self, count: u32, num_buffers: u32) -> Vec<Self::CmdBuf>;
     fn create_shader<T: Sized>(&self, info: &ShaderInfo, src: &[T]) -> Result<Self::Shader, Error>;
+    /// Returns the resources bound by `shader` so a `DescriptorLayout` can be validated against or built from it
+    fn reflect_shader(&self, shader: &Self::Shader) -> Result<ShaderReflection, Error>;
     fn create_buffer<T: Sized>(
         &mut self,
         info: &BufferInfo,
         data: Option<&[T]>,
     ) -> Result<Self::Buffer, Error>;
+    /// Create a texture with optional initial data, data can contain only the top mip of each array layer or
+    /// the full mip chain of each layer (see `mip_chain_size_for_format`), ordered layer by layer then mip by mip
     fn create_texture<T: Sized>(
         &mut self,
         info: &TextureInfo,
         data: Option<&[T]>,
     ) -> Result<Self::Texture, Error>;
+    /// Create an additional shader resource view of `texture` with a compatible `format` and `tex_type`, returning
+    /// the index in the shader heap. The texture must have been created with `TextureUsage::TYPELESS`
+    fn create_texture_view(
+        &mut self,
+        texture: &Self::Texture,
+        format: Format,
+        tex_type: TextureType,
+    ) -> Result<usize, Error>;
+    /// Create a render target view of a single `mip` and array `slice` of `texture`, for 3D textures `slice` is the
+    /// depth slice. Returns the index in the render target heap to use in `RenderPassInfo::rt_views`
+    fn create_render_target_view(
+        &mut self,
+        texture: &Self::Texture,
+        mip: u32,
+        slice: u32
+    ) -> Result<usize, Error>;
+    /// Create a depth stencil view of a single `mip` and array `slice` of `texture`. Returns the index in the depth
+    /// stencil heap to use in `RenderPassInfo::ds_view`
+    fn create_depth_stencil_view(
+        &mut self,
+        texture: &Self::Texture,
+        mip: u32,
+        slice: u32
+    ) -> Result<usize, Error>;
+    /// Create a read-only depth stencil view of a single `mip` and array `slice` of `texture`, so the texture can be
+    /// depth tested against while sampled as a shader resource in `ResourceState::DepthStencilReadOnlyShaderResource`.
+    /// Returns the index in the depth stencil heap to use in `RenderPassInfo::ds_view`, render passes using it must
+    /// not clear
+    fn create_read_only_depth_stencil_view(
+        &mut self,
+        texture: &Self::Texture,
+        mip: u32,
+        slice: u32
+    ) -> Result<usize, Error>;
+    /// Record commands into `cmd` to generate mips 1..n of `texture` by downsampling from the top mip with a box
+    /// filter, each mip of a non-power-of-two texture has its dimensions floored. The texture must have been created
+    /// with `TextureUsage::GENERATE_MIP_MAPS` and is returned to its current state at the end of the commands.
+    /// srgb textures are filtered without conversion through a linear view. The temporary views are released by
+    /// `clean_up_resources` so `cmd` must be executed within the same frame
+    fn generate_mips(&mut self, cmd: &mut Self::CmdBuf, texture: &Self::Texture) -> Result<(), Error>;
     fn create_render_pipeline(
         &self,
         info: &RenderPipelineInfo<Self>,
     ) -> Result<Self::RenderPipeline, Error>;
+    /// Create a render pipeline which generates geometry with mesh shaders, bind it with `CmdBuf::set_render_pipeline`
+    /// and draw with `CmdBuf::dispatch_mesh`. Returns an error when `AdapterInfo::mesh_shader_supported` returns false
+    fn create_mesh_pipeline(
+        &self,
+        info: &MeshPipelineInfo<Self>,
+    ) -> Result<Self::RenderPipeline, Error>;
     fn create_render_pass(&self, info: &RenderPassInfo<Self>) -> Result<Self::RenderPass, Error>;
     fn create_compute_pipeline(
         &self,
         info: &ComputePipelineInfo<Self>,
     ) -> Result<Self::ComputePipeline, Error>;
+    /// Create a bottom level acceleration structure sized for `info`, the geometry buffers must remain valid until the
+    /// structure is built with `CmdBuf::build_raytracing_blas`. Returns an error when
+    /// `AdapterInfo::raytracing_supported` returns false
+    fn create_raytracing_blas(
+        &self,
+        info: &RaytracingBLASInfo<Self>,
+    ) -> Result<Self::RaytracingBLAS, Error>;
+    /// Create a top level acceleration structure of `info.instances` and a view of it in the shader heap, build it
+    /// with `CmdBuf::build_raytracing_tlas`. Returns an error when `AdapterInfo::raytracing_supported` returns false
+    fn create_raytracing_tlas(
+        &mut self,
+        info: &RaytracingTLASInfo<Self>,
+    ) -> Result<Self::RaytracingTLAS, Error>;
+    /// Create a ray tracing pipeline from a library shader. Returns an error when
+    /// `AdapterInfo::raytracing_supported` returns false
+    fn create_raytracing_pipeline(
+        &self,
+        info: &RaytracingPipelineInfo<Self>,
+    ) -> Result<Self::RaytracingPipeline, Error>;
+    /// Create a shader binding table with a record for each named shader or hit group exported from `info.pipeline`
+    fn create_raytracing_shader_binding_table(
+        &self,
+        info: &RaytracingShaderBindingTableInfo<Self>,
+    ) -> Result<Self::RaytracingShaderBindingTable, Error>;
+    /// create a sampler in the device sampler heap, bind the heap returned from `get_sampler_heap` with
+    /// `CmdBuf::set_render_heap` or `CmdBuf::set_compute_heap` and index it in shaders with `Sampler::get_index`
+    fn create_sampler(&mut self, info: &SamplerInfo) -> Result<Self::Sampler, Error>;
+    /// create a ring buffer for per-frame transient data such as constants, n-buffered against the swap chain
+    fn create_ring_buffer(&mut self, info: &RingBufferInfo) -> Result<Self::RingBuffer, Error>;
+    /// Update a region of a gpu only buffer by staging `data` in a ring on the upload heap and copying it into
+    /// `dst` at byte `offset` on the upload queue, which is submitted on the next `execute` or `flush_uploads`.
+    /// The buffer must not be in use by the gpu when the copy executes
+    fn upload_buffer_region<T: Sized>(&mut self, dst: &Self::Buffer, offset: usize, data: &[T]) -> Result<(), Error>;
+    /// returns the most recent gpu time in milliseconds of each named timer recorded with `CmdBuf::begin_timer`
+    fn get_timer_results(&self) -> HashMap<String, f64>;
+    /// returns the result of each query slot written with `CmdBuf::begin_occlusion_query`, `true` if any samples
+    /// passed the depth and stencil tests. slots keep their previous result until the swap chain fence of the frame
+    /// which recorded the query has been signalled
+    fn get_occlusion_results(&self) -> Vec<bool>;
+    /// resources created with initial data are uploaded on a copy queue in batches, which are submitted on the next
+    /// `execute`. use this to submit pending uploads and block until they have completed
+    fn flush_uploads(&self) -> Result<(), Error>;
+    /// create a fence to synchronise the cpu with work submitted to the device command queue, the initial value is 0
+    fn create_fence(&self) -> Result<Self::Fence, Error>;
+    /// signal `fence` with `value` from the command queue once all previously executed work has completed
+    fn signal(&self, fence: &Self::Fence, value: u64) -> Result<(), Error>;
+    /// block the calling thread until `fence` has reached `value`
+    fn wait(&self, fence: &Self::Fence, value: u64) -> Result<(), Error>;
+    /// signal `fence` with `value` from `queue` once all work previously executed on that queue has completed
+    fn queue_signal(&self, queue: QueueType, fence: &Self::Fence, value: u64) -> Result<(), Error>;
+    /// make `queue` wait on the gpu until `fence` has reached `value` before executing any further work, use this
+    /// with `queue_signal` on another queue to consume the results of work from that queue
+    fn queue_wait(&self, queue: QueueType, fence: &Self::Fence, value: u64) -> Result<(), Error>;
+    /// create a pipeline library from a blob previously obtained with `PipelineLibrary::serialize`, supply None or
+    /// data from a different driver or adapter to start with an empty library. `data` is copied so the caller does not
+    /// need to keep it alive
+    fn create_pipeline_library(&self, data: Option<&[u8]>) -> Result<Self::PipelineLibrary, Error>;
+    /// load a render pipeline stored as `name` in `library`, if it does not exist it is created and stored
+    fn create_render_pipeline_from_library(
+        &self,
+        info: &RenderPipelineInfo<Self>,
+        library: &Self::PipelineLibrary,
+        name: &str
+    ) -> Result<Self::RenderPipeline, Error>;
+    /// load a compute pipeline stored as `name` in `library`, if it does not exist it is created and stored
+    fn create_compute_pipeline_from_library(
+        &self,
+        info: &ComputePipelineInfo<Self>,
+        library: &Self::PipelineLibrary,
+        name: &str
+    ) -> Result<Self::ComputePipeline, Error>;
     /// device will take ownership safely waiting for the resource to be no longer in use on the gpu before destroying
     fn destroy_texture(&mut self, texture: Self::Texture);
-    /// check if resources are finished on the gpu and de-allocate from shader heaps
+    /// device will take ownership safely waiting for the buffer to be no longer in use on the gpu before destroying
+    fn destroy_buffer(&mut self, buffer: Self::Buffer);
+    /// device will take ownership safely waiting for the pipeline to be no longer in use on the gpu before destroying
+    fn destroy_render_pipeline(&mut self, pipeline: Self::RenderPipeline);
+    /// device will take ownership safely waiting for the pipeline to be no longer in use on the gpu before destroying
+    fn destroy_compute_pipeline(&mut self, pipeline: Self::ComputePipeline);
+    /// device will take ownership safely waiting for the sampler to be no longer in use on the gpu before destroying
+    fn destroy_sampler(&mut self, sampler: Self::Sampler);
+    /// device will take ownership safely waiting for the view to be no longer in use on the gpu before destroying
+    fn destroy_render_target_view(&mut self, index: usize);
+    /// device will take ownership safely waiting for the view to be no longer in use on the gpu before destroying
+    fn destroy_depth_stencil_view(&mut self, index: usize);
+    /// check if resources are finished on the gpu and de-allocate from shader heaps. when presenting to multiple
+    /// swap chains call this once per frame with the main swap chain, resources are held for the largest number of
+    /// buffers of any swap chain created on the device
     fn clean_up_resources(&mut self, swap_chain: &Self::SwapChain);
-    fn execute(&self, cmd: &Self::CmdBuf);
+    /// submit `cmd` to the queue it was created for (see `CmdBuf::get_queue_type`). returns an error if the device
+    /// has been removed, `is_device_removed` will then be true and the device and its resources must be recreated
+    fn execute(&self, cmd: &Self::CmdBuf) -> Result<(), Error>;
+    /// submit all of `cmds` in order with a single submission, the command buffers must all be for the same queue
+    fn execute_multiple(&self, cmds: &[&Self::CmdBuf]) -> Result<(), Error>;
+    /// returns true if the device has been removed by a gpu hang, driver update or the adapter being disconnected
+    fn is_device_removed(&self) -> bool;
+    /// after the device has been removed, returns the breadcrumbs of command lists which did not complete and the
+    /// faulting gpu virtual address. Breadcrumbs are only recorded in debug builds
+    fn get_dred_breadcrumbs(&self) -> Result<String, Error>;
     fn report_live_objects(&self) -> Result<(), Error>;
     fn get_shader_heap(&self) -> &Self::Heap;
     fn get_shader_heap_mut(&mut self) -> &mut Self::Heap;
+    /// heap containing samplers created with `create_sampler`
+    fn get_sampler_heap(&self) -> &Self::Heap;
     fn get_adapter_info(&self) -> &AdapterInfo;
+    /// Query the current video memory budget and usage, use this to limit streaming and avoid running out of memory
+    fn get_video_memory_info(&self) -> Result<VideoMemoryInfo, Error>;
+    /// Lock the gpu to its base clock for reproducible profiling timings. This requires developer mode to be enabled
+    /// on the os and returns an error otherwise. For profiling only, it must be disabled for shipping
+    fn set_stable_power_state(&self, enabled: bool) -> Result<(), Error>;
     fn as_ptr(&self) -> *const Self;
     fn as_mut_ptr(&mut self) -> *mut Self;
 }
 
 /// A swap chain is connected to a window, controls fences and signals as we swap buffers.
+/// Multiple swap chains (one per window) can be created from the same `Device`, each swap chain has its own fence
+/// so `update` and `swap` can be called independently for each window within a frame.
 pub trait SwapChain<D: Device>: 'static + Sized + Any + Send + Sync + Clone {
     fn new_frame(&mut self);
     fn update<A: os::App>(&mut self, device: &mut D, window: &A::Window, cmd: &mut D::CmdBuf);
@@ -789,11 +1480,17 @@ pub trait SwapChain<D: Device>: 'static + Sized + Any + Send + Sync + Clone {
     fn get_num_buffers(&self) -> u32;
     fn get_backbuffer_index(&self) -> u32;
     fn get_backbuffer_texture(&self) -> &D::Texture;
+    /// Returns the backbuffer presented last frame, its contents are only preserved for swap chains created with
+    /// `TextureUsage::SHADER_RESOURCE`
+    fn get_previous_backbuffer_texture(&self) -> &D::Texture;
     fn get_backbuffer_pass(&self) -> &D::RenderPass;
     fn get_backbuffer_pass_mut(&mut self) -> &mut D::RenderPass;
     fn get_backbuffer_pass_no_clear(&self) -> &D::RenderPass;
     fn get_backbuffer_pass_no_clear_mut(&mut self) -> &mut D::RenderPass;
-    fn swap(&mut self, device: &D);
+    /// present the current backbuffer and signal the frame fence, returns an error if the device has been removed
+    fn swap(&mut self, device: &D) -> Result<(), Error>;
+    /// Enable or disable vsync for subsequent calls to `swap`, disabling sets the sync interval to 0
+    fn set_vsync(&mut self, vsync: bool);
     fn as_ptr(&self) -> *const Self;
     fn as_mut_ptr(&mut self) -> *mut Self;
 }
@@ -803,26 +1500,120 @@ pub trait SwapChain<D: Device>: 'static + Sized + Any + Send + Sync + Clone {
 /// At the start of each frame `reset` must be called with an associated swap chain to internally switch
 /// which buffer we are writing to. At the end of each frame `close` must be called
 /// and finally the `CmdBuf` can be passed to `Device::execute` to be processed on the GPU.
+/// Command buffers created with separate calls to `Device::create_cmd_buf` can be recorded concurrently on
+/// different threads. Clones share the same command lists, so a `CmdBuf` and its clones must not record
+/// concurrently. Submission via `Device::execute` must still happen from a single thread in order.
 pub trait CmdBuf<D: Device>: Send + Sync + Clone {
     fn reset(&mut self, swap_chain: &D::SwapChain);
     fn close(&mut self) -> Result<(), Error>;
     fn get_backbuffer_index(&self) -> u32;
+    /// Returns the queue the command buffer is submitted to by `Device::execute`
+    fn get_queue_type(&self) -> QueueType;
     fn begin_render_pass(&self, render_pass: &D::RenderPass);
     fn end_render_pass(&self);
     fn begin_event(&mut self, colour: u32, name: &str);
     fn end_event(&mut self);
+    /// Begin a gpu timer named `name`, timers can be nested and must be ended with `end_timer` before `close`.
+    /// Results are available from `Device::get_timer_results` once the frame has completed on the gpu
+    fn begin_timer(&mut self, name: &str);
+    /// End the most recent timer started with `begin_timer`
+    fn end_timer(&mut self);
+    /// Begin a binary occlusion query in slot `index` of the device query heap, draws recorded until
+    /// `end_occlusion_query` count towards the result returned from `Device::get_occlusion_results`
+    fn begin_occlusion_query(&mut self, index: u32);
+    /// End the occlusion query in slot `index` and resolve its result to be read back by the device, the result is
+    /// tracked with the swap chain fence passed to `reset` so queries must be recorded within a frame
+    fn end_occlusion_query(&mut self, index: u32);
     fn transition_barrier(&mut self, barrier: &TransitionBarrier<D>);
+    /// Wait for unordered access writes to `texture` or `buffer` to complete before subsequent unordered access
+    /// reads or writes, supply neither for a barrier on all unordered access resources
+    fn uav_barrier(&mut self, texture: Option<&D::Texture>, buffer: Option<&D::Buffer>);
+    /// Clear the unordered access view of `texture` or `buffer` to the integer `values`, `heap` must be the shader heap
+    /// the uav was allocated in. The resource must be in the `UnorderedAccess` state and can be cleared outside of
+    /// a render pass
+    fn clear_unordered_access_view_uint(
+        &self,
+        texture: Option<&D::Texture>,
+        buffer: Option<&D::Buffer>,
+        heap: &D::Heap,
+        values: [u32; 4]) -> Result<(), Error>;
+    /// Clear the unordered access view of `texture` or `buffer` to the float `values`, `heap` must be the shader heap
+    /// the uav was allocated in. The resource must be in the `UnorderedAccess` state and can be cleared outside of
+    /// a render pass
+    fn clear_unordered_access_view_float(
+        &self,
+        texture: Option<&D::Texture>,
+        buffer: Option<&D::Buffer>,
+        heap: &D::Heap,
+        values: [f32; 4]) -> Result<(), Error>;
     fn transition_barrier_subresource(&mut self, barrier: &TransitionBarrier<D>, subresource: Subresource);
+    /// Transition `num_subresources` individual subresources starting at `first_subresource`, where a subresource
+    /// index is `mip + array_slice * mip_levels` (see `subresource_index`). The tracked state of the texture is not updated as it
+    /// only describes the whole resource
+    fn transition_barrier_range(&mut self, barrier: &TransitionBarrier<D>, first_subresource: u32, num_subresources: u32);
+    /// Begin a split transition barrier, the resource must not be used until a matching `end_transition` is issued
+    /// with the same `barrier`. This allows the gpu to hide the cost of the transition behind other work
+    fn begin_transition(&mut self, barrier: &TransitionBarrier<D>);
+    /// End a split transition barrier previously started with `begin_transition`, the tracked state is updated here
+    fn end_transition(&mut self, barrier: &TransitionBarrier<D>);
+    /// Transition a texture from its currently tracked state into `state_after`, no barrier is issued
+    /// if the texture is already in `state_after`
+    fn transition(&mut self, texture: &D::Texture, state_after: ResourceState);
+    /// Transition a buffer from its currently tracked state into `state_after`, buffers which are
+    /// cpu writable cannot change state and are ignored
+    fn transition_buffer(&mut self, buffer: &D::Buffer, state_after: ResourceState);
+    /// Validate the tracked state of `texture` is `expected` before it is used, for example sampling a texture as a
+    /// `ShaderResource` which was last written as `UnorderedAccess` in a compute pass without a transition.
+    /// Validation only happens in debug builds and compiles out entirely in release
+    fn validate_texture_state(&self, texture: &D::Texture, expected: ResourceState) -> Result<(), Error>;
+    /// Validate the tracked state of `buffer` is `expected` before it is used, cpu writable buffers are not tracked
+    /// and always pass. Validation only happens in debug builds and compiles out entirely in release
+    fn validate_buffer_state(&self, buffer: &D::Buffer, expected: ResourceState) -> Result<(), Error>;
     fn set_viewport(&self, viewport: &Viewport);
     fn set_scissor_rect(&self, scissor_rect: &ScissorRect);
+    /// Set multiple viewports at once (up to 16), shaders select a viewport with `SV_ViewportArrayIndex`
+    fn set_viewports(&self, viewports: &[Viewport]);
+    /// Set multiple scissor rects at once (up to 16), the rect at the same index as the selected viewport is used
+    fn set_scissor_rects(&self, scissor_rects: &[ScissorRect]);
     fn set_index_buffer(&self, buffer: &D::Buffer);
     fn set_vertex_buffer(&self, buffer: &D::Buffer, slot: u32);
     fn set_render_pipeline(&self, pipeline: &D::RenderPipeline);
     fn set_compute_pipeline(&self, pipeline: &D::ComputePipeline);
+    /// Bind `heap` as the descriptor table at root `slot`, a shader heap and a sampler heap can be bound at
+    /// the same time in different slots
     fn set_compute_heap(&self, slot: u32, heap: &D::Heap);
+    /// Bind `heap` starting at `offset` as the descriptor table at root `slot`, a shader heap and a sampler heap
+    /// can be bound at the same time in different slots
     fn set_render_heap(&self, slot: u32, heap: &D::Heap, offset: usize);
+    /// Bind the shader `heap` once so shaders can index any resource by `get_srv_index` or `get_uav_index`. The heap
+    /// is bound to the first unbounded descriptor table (see `DescriptorLayout::with_bindless_table`) of the current
+    /// pipeline and rebound each time a pipeline is set until the command buffer is reset
+    fn set_bindless_heap(&self, heap: &D::Heap);
     fn set_marker(&self, colour: u32, name: &str);
     fn push_constants<T: Sized>(&self, slot: u32, num_values: u32, dest_offset: u32, data: &[T]);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootConstantBuffer` at root `slot` of the
+    /// current render pipeline, `offset` must be a multiple of 256 bytes
+    fn set_constant_buffer(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootConstantBuffer` at root `slot` of the
+    /// current compute pipeline, `offset` must be a multiple of 256 bytes
+    fn set_compute_constant_buffer(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootShaderResource` at root `slot` of the
+    /// current render pipeline
+    fn set_root_shader_resource(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootUnorderedAccess` at root `slot` of the
+    /// current render pipeline
+    fn set_root_unordered_access(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootShaderResource` at root `slot` of the
+    /// current compute pipeline
+    fn set_compute_root_shader_resource(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Bind `buffer` starting at byte `offset` to the `DescriptorType::RootUnorderedAccess` at root `slot` of the
+    /// current compute pipeline
+    fn set_compute_root_unordered_access(&self, slot: u32, buffer: &D::Buffer, offset: usize);
+    /// Push a single `value` as 32-bit constants, the number of values is derived from `size_of::<T>()`
+    /// and a `T` which is not a multiple of 4 bytes in size fails to compile
+    fn push_constants_typed<T: Sized>(&self, slot: u32, dest_offset: u32, value: &T) {
+        self.push_constants(slot, PushConstantsSize::<T>::NUM_VALUES, dest_offset, std::slice::from_ref(value));
+    }
     fn draw_instanced(
         &self,
         vertex_count: u32,
@@ -838,10 +1629,78 @@ pub trait CmdBuf<D: Device>: Send + Sync + Clone {
         base_vertex: i32,
         start_instance: u32,
     );
+    /// Issue up to `max_draw_count` draws with `DrawArguments` read from `args` at `args_offset` in bytes.
+    /// If `count_buffer` is supplied the number of draws is read as a u32 from it at `count_offset`, clamped to `max_draw_count`
+    fn draw_indirect(
+        &self,
+        args: &D::Buffer,
+        args_offset: usize,
+        max_draw_count: u32,
+        count_buffer: Option<&D::Buffer>,
+        count_offset: usize
+    );
+    /// Issue up to `max_draw_count` indexed draws with `DrawIndexedArguments` read from `args` at `args_offset` in bytes.
+    /// If `count_buffer` is supplied the number of draws is read as a u32 from it at `count_offset`, clamped to `max_draw_count`
+    fn draw_indexed_indirect(
+        &self,
+        args: &D::Buffer,
+        args_offset: usize,
+        max_draw_count: u32,
+        count_buffer: Option<&D::Buffer>,
+        count_offset: usize
+    );
     /// Thread count is required for metal, in hlsl it is specified in the shader
     fn dispatch(&self, group_count: Size3, thread_count: Size3);
+    /// Dispatch compute work with group counts read as `DispatchArguments` from `args` at `args_offset` in bytes
+    fn dispatch_indirect(&self, args: &D::Buffer, args_offset: usize);
+    /// Record the build of `blas`, followed by a barrier so it can be used in a top level acceleration structure build.
+    /// The geometry buffers must be in `ResourceState::NonPixelShaderResource`
+    fn build_raytracing_blas(&mut self, blas: &D::RaytracingBLAS);
+    /// Record the build of `tlas`, followed by a barrier so it can be traced against
+    fn build_raytracing_tlas(&mut self, tlas: &D::RaytracingTLAS);
+    /// Set the coarse shading rate of subsequent draws and how it combines with per primitive and image rates.
+    /// Ignored when `AdapterInfo::variable_shading_rate_tier` is 0, so draws fall back to full rate shading
+    fn set_shading_rate(&self, rate: ShadingRate, combiners: [ShadingRateCombiner; 2]);
+    /// Bind an R8u `texture` in `ResourceState::ShadingRateSource` where each texel holds the shading rate of a
+    /// screen space tile (see `AdapterInfo::shading_rate_image_tile_size`), supply None to unbind. Ignored when
+    /// `AdapterInfo::variable_shading_rate_tier` is lower than 2
+    fn set_shading_rate_image(&self, texture: Option<&D::Texture>);
+    /// Bind a ray tracing pipeline, resources are bound to its descriptor layout with the compute binding functions
+    fn set_raytracing_pipeline(&self, pipeline: &D::RaytracingPipeline);
+    /// Launch a `size` grid of ray generation shader invocations, using the shaders in `table` from the current
+    /// ray tracing pipeline
+    fn dispatch_rays(&self, table: &D::RaytracingShaderBindingTable, size: Size3);
+    /// Launch `group_count` amplification or mesh shader groups of the render pipeline created with
+    /// `Device::create_mesh_pipeline`, inside a render pass
+    fn dispatch_mesh(&self, group_count: Size3);
     fn resolve_texture_subresource(&self, texture: &D::Texture, subresource: u32) -> Result<(), Error>;
     fn read_back_backbuffer(&mut self, swap_chain: &D::SwapChain) -> D::ReadBackRequest;
+    /// Copy `subresource` (mip + array_layer * mip_levels) of `texture` into a readback buffer, the texture must be
+    /// in the `CopySrc` state. The returned request owns its readback buffer, which is only reused once the request
+    /// and all of its clones have been dropped
+    fn read_back_texture(
+        &mut self,
+        swap_chain: &D::SwapChain,
+        texture: &D::Texture,
+        subresource: u32
+    ) -> Result<D::ReadBackRequest, Error>;
+    /// Copy `src_region` of `src_subresource` in `src` to `dst_origin` (x, y, z) in `dst_subresource` of `dst`, supply
+    /// `None` to copy the whole source subresource. `src` must be in the `CopySrc` state and `dst` in the `CopyDst`
+    /// state, the caller is responsible for the transitions
+    fn copy_texture_region(
+        &self,
+        dst: &D::Texture,
+        dst_subresource: u32,
+        dst_origin: Size3,
+        src: &D::Texture,
+        src_subresource: u32,
+        src_region: Option<TextureRegion>);
+    /// Copy the whole of `src` into `dst`, the textures must have the same dimensions and compatible formats. `src` must
+    /// be in the `CopySrc` state and `dst` in the `CopyDst` state, the caller is responsible for the transitions
+    fn copy_texture(&self, dst: &D::Texture, src: &D::Texture);
+    /// Copy `size` bytes from `src_offset` in `src` to `dst_offset` in `dst`, `src` must be in the `CopySrc` state
+    /// and `dst` in the `CopyDst` state, the caller is responsible for the transitions
+    fn copy_buffer_region(&self, dst: &D::Buffer, dst_offset: usize, src: &D::Buffer, src_offset: usize, size: usize);
 }
 
 /// An opaque Buffer type used for vertex, index, constant or unordered access.
@@ -857,6 +1716,20 @@ pub trait Buffer<D: Device>: Send + Sync {
     fn get_srv_index(&self) -> Option<usize>;
     /// Return the index to unorder access view for read/write from shaders...
     fn get_uav_index(&self) -> Option<usize>;
+    /// Returns the tracked resource state, or `None` if the buffer is cpu writable and does not track state
+    fn get_state(&self) -> Option<ResourceState>;
+}
+
+/// A linear allocator which sub-allocates transient data from a persistently mapped upload buffer.
+/// Each frame gets its own region of the buffer, call `reset` at the start of each frame so the data written
+/// in previous frames is not overwritten while it is in flight on the GPU.
+pub trait RingBuffer<D: Device>: Send + Sync {
+    /// Switch to the region for the current backbuffer and reset the allocation offset
+    fn reset(&mut self, swap_chain: &D::SwapChain);
+    /// Allocate `size` bytes, allocations are aligned to 256 bytes so they can be bound as constant buffers
+    fn allocate(&mut self, size: usize) -> Result<RingBufferAllocation, Error>;
+    /// Allocate space for and copy `data` into the ring buffer
+    fn push<T: Sized>(&mut self, data: &[T]) -> Result<RingBufferAllocation, Error>;
 }
 
 /// An opaque Texture type
@@ -869,19 +1742,35 @@ pub trait Texture<D: Device>: Send + Sync {
     fn clone_inner(&self) -> Self;
     /// Returns true if this texture has a subresource which can be resolved into
     fn is_resolvable(&self) -> bool;
+    /// Returns the tracked resource state, which is updated by transitions recorded on a `CmdBuf`
+    fn get_state(&self) -> ResourceState;
 }
 
-/// An opaque shader heap type, use to create views of resources for binding and access in shaders
+/// An opaque shader heap type, use to create views of resources for binding and access in shaders.
+/// Heaps grow automatically when full, render target and depth stencil heaps chain on additional heaps instead
 pub trait Heap<D: Device>: Send + Sync {
     /// Deallocate a resource from the heap and mark space in free list for re-use
     fn deallocate(&mut self, index: usize);
+    /// Grow the heap to contain `num_descriptors`, existing descriptors are copied and keep the same indices.
+    /// Only shader and sampler heaps can grow and they are limited to the maximum shader visible heap size.
+    /// Growing moves the heap to a new gpu address, so gpu handles and heaps already bound on command buffers are
+    /// invalidated and the heap must be bound again with `CmdBuf::set_render_heap` or `set_compute_heap`.
+    /// The old heap is kept alive until in-flight frames complete
+    fn grow(&mut self, num_descriptors: usize) -> Result<(), Error>;
 }
 
 /// Used to readback data from the GPU, once the request is issued `is_complete` needs to be waited on for completion
 /// you must poll this every frame and not block so the GPU can flush the request. Once the result is ready the
-/// data can be obtained using `get_data`
-pub trait ReadBackRequest<D: Device> {
+/// data can be obtained using `get_data`. Completion is tracked with the swap chain fence which is signalled
+/// during `swap`, so the command buffer containing the request must be executed in the frame it was recorded
+pub trait ReadBackRequest<D: Device>: Send + Sync {
+    /// Returns true once the gpu has completed the copy, by default requests complete when the swap chain fence for
+    /// the frame they were recorded in is signalled
     fn is_complete(&self, swap_chain: &D::SwapChain) -> bool;
+    /// Track completion with `fence` reaching `value` instead of the swap chain fence, use this when the command
+    /// buffer containing the read back is submitted outside of the swap chain frame and signalled with
+    /// `Device::queue_signal`
+    fn set_fence(&mut self, fence: &D::Fence, value: u64);
     fn map(&self, info: &MapInfo) -> Result<ReadBackData, Error>;
     fn unmap(&self);
 }
@@ -900,6 +1789,15 @@ pub struct ReadBackData {
     pub slice_pitch: usize,
 }
 
+/// Compile time checked number of 32-bit values required to push a `T` as constants
+struct PushConstantsSize<T>(std::marker::PhantomData<T>);
+impl<T> PushConstantsSize<T> {
+    const NUM_VALUES: u32 = {
+        assert!(std::mem::size_of::<T>() % 4 == 0, "push constants must be a multiple of 4 bytes in size");
+        (std::mem::size_of::<T>() / 4) as u32
+    };
+}
+
 /// Take any sized type and return a u8 slice. This can be useful to pass `data` to `Device::create_buffer`.
 pub fn as_u8_slice<T: Sized>(p: &T) -> &[u8] {
     unsafe {
@@ -921,6 +1819,7 @@ pub fn slice_as_u8_slice<T: Sized>(p: &[T]) -> &[u8] {
 pub fn block_size_for_format(format: Format) -> u32 {
     match format {
         Format::Unknown => 0,
+        Format::R8u => 1,
         Format::R16n => 2,
         Format::R16u => 2,
         Format::R16i => 2,
@@ -932,9 +1831,12 @@ pub fn block_size_for_format(format: Format) -> u32 {
         Format::RG32i => 8,
         Format::RG32f => 8,
         Format::RGBA8n => 4,
+        Format::RGBA8nSRGB => 4,
         Format::RGBA8u => 4,
         Format::RGBA8i => 4,
         Format::BGRA8n => 4,
+        Format::BGRA8nSRGB => 4,
+        Format::RGB10A2n => 4,
         Format::RGB32u => 12,
         Format::RGB32i => 12,
         Format::RGB32f => 12,
@@ -948,23 +1850,60 @@ pub fn block_size_for_format(format: Format) -> u32 {
         Format::D32f => 16,
         Format::D24nS8u => 32,
         Format::D16n => 2,
+        Format::BC1n => 8,
+        Format::BC3n => 16,
+        Format::BC4n => 8,
+        Format::BC5n => 16,
+        Format::BC6H => 16,
+        Format::BC7n => 16,
     }
 }
 
-/// Returns the row pitch of an image in bytes: width * block size
+/// Returns true if the mip chain of `format` can be generated with `Device::generate_mips`, which requires typed
+/// unordered access stores of float data. srgb formats are written through a linear view of the same texture
+pub fn format_supports_generate_mips(format: Format) -> bool {
+    matches!(format,
+        Format::R16n | Format::R16f | Format::R32f | Format::RG32f | Format::RGBA8n | Format::RGBA8nSRGB |
+        Format::RGB10A2n | Format::RGBA16f | Format::RGBA32f)
+}
+
+/// Returns the width and height in texels of a single block, 4 for block compressed formats and 1 otherwise
+pub fn block_dimension_for_format(format: Format) -> u64 {
+    match format {
+        Format::BC1n | Format::BC3n | Format::BC4n | Format::BC5n | Format::BC6H | Format::BC7n => 4,
+        _ => 1
+    }
+}
+
+/// Returns the row pitch of an image in bytes: number of blocks in a row * block size
 pub fn row_pitch_for_format(format: Format, width: u64) -> u64 {
-    block_size_for_format(format) as u64 * width
+    let dim = block_dimension_for_format(format);
+    block_size_for_format(format) as u64 * ((width + dim - 1) / dim)
 }
 
-/// Returns the slice pitch of an image in bytes: width * height * block size, a slice is a single 2D image
+/// Returns the slice pitch of an image in bytes: row pitch * number of rows of blocks, a slice is a single 2D image
 /// or a single slice of a 3D texture or texture array
 pub fn slice_pitch_for_format(format: Format, width: u64, height: u64) -> u64 {
-    block_size_for_format(format) as u64 * width * height
+    let dim = block_dimension_for_format(format);
+    row_pitch_for_format(format, width) * ((height + dim - 1) / dim)
 }
 
-/// Return the size in bytes of a 3 dimensional resource: width * height * depth block size
+/// Return the size in bytes of a 3 dimensional resource: slice pitch * depth
 pub fn size_for_format(format: Format, width: u64, height: u64, depth: u32) -> u64 {
-    block_size_for_format(format) as u64 * width * height * depth as u64
+    slice_pitch_for_format(format, width, height) * depth as u64
+}
+
+/// Return the size in bytes of a full mip chain of a 3 dimensional resource, each level halves width, height and depth
+pub fn mip_chain_size_for_format(format: Format, width: u64, height: u64, depth: u32, mip_levels: u32) -> u64 {
+    (0..mip_levels.max(1)).map(|mip| {
+        size_for_format(format, (width >> mip).max(1), (height >> mip).max(1), (depth >> mip).max(1))
+    }).sum()
+}
+
+/// Return the subresource index of `mip` in `array_slice` of a texture with `mip_levels`, for use with
+/// `CmdBuf::transition_barrier_range` and other per-subresource operations
+pub fn subresource_index(mip: u32, array_slice: u32, mip_levels: u32) -> u32 {
+    mip + array_slice * mip_levels.max(1)
 }
 
 /// Aligns value to the alignment specified by align. value must be a power of 2
@@ -1021,6 +1960,19 @@ impl From<WriteMask> for u8 {
     }
 }
 
+impl AdapterInfo {
+    /// True if the device supports mesh and amplification shaders used by `Device::create_mesh_pipeline`.
+    pub fn mesh_shader_supported(&self) -> bool {
+        self.mesh_shader_tier > 0
+    }
+
+    /// True if the device supports acceleration structures and ray tracing pipelines used by
+    /// `Device::create_raytracing_pipeline`.
+    pub fn raytracing_supported(&self) -> bool {
+        self.raytracing_tier > 0
+    }
+}
+
 impl std::fmt::Display for AdapterInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut available = String::from("");
@@ -1036,6 +1988,13 @@ impl std::fmt::Display for AdapterInfo {
   Video Memory: {}(mb)
   System Memory: {}(mb)
   Shared System Memory: {}(mb)
+  HDR: {} (max luminance: {} nits)
+  Shader Model: {}.{}
+  Resource Binding Tier: {}
+  Mesh Shader Tier: {}
+  Ray Tracing Tier: {}
+  Variable Rate Shading Tier: {}
+  Wave Lanes: {}-{}
 Available Adapters:
 {}",
             self.name,
@@ -1043,6 +2002,16 @@ Available Adapters:
             self.dedicated_video_memory / 1024 / 1024,
             self.dedicated_system_memory / 1024 / 1024,
             self.shared_system_memory / 1024 / 1024,
+            self.hdr_supported,
+            self.max_luminance,
+            self.shader_model.0,
+            self.shader_model.1,
+            self.resource_binding_tier,
+            self.mesh_shader_tier,
+            self.raytracing_tier,
+            self.variable_shading_rate_tier,
+            self.wave_lane_count_min,
+            self.wave_lane_count_max,
             available
         )
     }
@@ -4,19 +4,24 @@ use crate::os::Window;
 use crate::os::NativeHandle;
 
 use super::Device as SuperDevice;
+use super::Fence as SuperFence;
 use super::*;
 
 use std::char::{decode_utf16, REPLACEMENT_CHARACTER};
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::result;
 use std::str;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
 
 use windows::{
-    core::*, Win32::Foundation::*, Win32::Graphics::Direct3D::Fxc::*, Win32::Graphics::Direct3D::*,
+    core::*, Win32::Foundation::*, Win32::Graphics::Direct3D::Dxc::*, Win32::Graphics::Direct3D::Fxc::*,
+    Win32::Graphics::Direct3D::*,
     Win32::Graphics::Direct3D12::*, Win32::Graphics::Dxgi::Common::*, Win32::Graphics::Dxgi::*,
     Win32::System::LibraryLoader::*, Win32::System::Threading::*,
     Win32::System::WindowsProgramming::*,
@@ -26,6 +31,13 @@ use windows::{
 type BeginEventOnCommandList = extern "stdcall" fn(*const core::ffi::c_void, u64, PSTR) -> i32;
 type EndEventOnCommandList = extern "stdcall" fn(*const core::ffi::c_void) -> i32;
 type SetMarkerOnCommandList = extern "stdcall" fn(*const core::ffi::c_void, u64, PSTR) -> i32;
+type DxcCreateInstanceProc = extern "system" fn(*const GUID, *const GUID, *mut *mut core::ffi::c_void) -> HRESULT;
+
+/// {73e22d93-e6ce-47f3-b5bf-f0664f39c1b0}
+const DXC_COMPILER_CLSID: GUID = GUID::from_u128(0x73e22d93_e6ce_47f3_b5bf_f0664f39c1b0);
+/// {6245d6af-66e0-48fd-80b4-4d271796748c}
+const DXC_UTILS_CLSID: GUID = GUID::from_u128(0x6245d6af_66e0_48fd_80b4_4d271796748c);
+const DXC_CP_UTF8: u32 = 65001;
 
 #[derive(Copy, Clone)]
 struct WinPixEventRuntime {
@@ -109,16 +121,38 @@ impl WinPixEventRuntime {
 #[derive(Clone)]
 pub struct Device {
     adapter_info: super::AdapterInfo,
+    adapter: IDXGIAdapter3,
     dxgi_factory: IDXGIFactory4,
     device: ID3D12Device,
-    command_allocator: ID3D12CommandAllocator,
-    command_list: ID3D12GraphicsCommandList,
     command_queue: ID3D12CommandQueue,
+    compute_queue: ID3D12CommandQueue,
+    upload_queue: Arc<Mutex<UploadQueue>>,
     pix: Option<WinPixEventRuntime>,
     shader_heap: Heap,
+    /// non shader visible copies of unordered access views, required as the cpu descriptor for uav clears
+    shader_cpu_heap: Heap,
     rtv_heap: Heap,
     dsv_heap: Heap,
-    cleanup_textures: Vec<(u32, Texture)>
+    sampler_heap: Heap,
+    cleanup_textures: Vec<(u32, Texture)>,
+    cleanup_buffers: Vec<(u32, Buffer)>,
+    cleanup_render_pipelines: Vec<(u32, RenderPipeline)>,
+    cleanup_compute_pipelines: Vec<(u32, ComputePipeline)>,
+    cleanup_samplers: Vec<(u32, Sampler)>,
+    cleanup_rtvs: Vec<(u32, usize)>,
+    cleanup_dsvs: Vec<(u32, usize)>,
+    /// shader heap indices of the temporary views recorded by `generate_mips`
+    cleanup_mip_views: Vec<(u32, usize)>,
+    draw_indirect_signature: ID3D12CommandSignature,
+    draw_indexed_indirect_signature: ID3D12CommandSignature,
+    dispatch_indirect_signature: ID3D12CommandSignature,
+    /// compute pipeline used by `generate_mips`, created on first use
+    mip_pipeline: Option<ComputePipeline>,
+    /// largest number of buffers of any swap chain created on the device, resources are kept alive in
+    /// `clean_up_resources` for this many frames so they are not in flight on any swap chain when released
+    max_swap_chain_buffers: u32,
+    timers: Arc<Mutex<Timers>>,
+    occlusion_queries: Arc<Mutex<OcclusionQueries>>
 }
 
 unsafe impl Send for Device {}
@@ -141,6 +175,32 @@ unsafe impl Send for Texture {}
 unsafe impl Sync for Texture {}
 unsafe impl Send for Heap {}
 unsafe impl Sync for Heap {}
+unsafe impl Send for RingBuffer {}
+unsafe impl Sync for RingBuffer {}
+unsafe impl Send for PipelineLibrary {}
+unsafe impl Sync for PipelineLibrary {}
+unsafe impl Send for Fence {}
+unsafe impl Sync for Fence {}
+unsafe impl Send for RaytracingBLAS {}
+unsafe impl Sync for RaytracingBLAS {}
+unsafe impl Send for RaytracingTLAS {}
+unsafe impl Sync for RaytracingTLAS {}
+unsafe impl Send for RaytracingPipeline {}
+unsafe impl Sync for RaytracingPipeline {}
+unsafe impl Send for RaytracingShaderBindingTable {}
+unsafe impl Sync for RaytracingShaderBindingTable {}
+unsafe impl Send for ReadBackRequest {}
+unsafe impl Sync for ReadBackRequest {}
+unsafe impl Send for UploadQueue {}
+unsafe impl Send for Timers {}
+
+// `pmfx::Pmfx::execute_parallel` shares the pmfx and records views from rayon worker threads
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Device>();
+    assert_send_sync::<CmdBuf>();
+    assert_send_sync::<crate::pmfx::Pmfx<Device>>();
+};
 
 #[derive(Clone)]
 pub struct SwapChain {
@@ -160,6 +220,11 @@ pub struct SwapChain {
     fence_event: HANDLE,
     frame_fence_value: Vec<u64>,
     readback_buffer: Option<ID3D12Resource>,
+    readback_footprints: CopyableFootprints,
+    usage: super::TextureUsage,
+    /// vertical blanks to wait on present, tearing is allowed with an interval of 0 if supported
+    sync_interval: u32,
+    allow_tearing: bool,
     require_wait: Vec<bool>,
     clear_col: Option<ClearColour>,
 }
@@ -169,17 +234,78 @@ pub struct RenderPipeline {
     pso: ID3D12PipelineState,
     root_signature: ID3D12RootSignature,
     topology: D3D_PRIMITIVE_TOPOLOGY,
+    /// root slot of the first descriptor table with an unbounded range
+    bindless_slot: Option<u32>,
+}
+
+/// A single subobject in a pipeline state stream, the type is followed by its data and each subobject is aligned to the
+/// size of a pointer to match the layout d3d12 expects
+#[repr(C, align(8))]
+struct PipelineStateSubobject<T> {
+    subobject_type: D3D12_PIPELINE_STATE_SUBOBJECT_TYPE,
+    data: T,
+}
+
+impl<T> PipelineStateSubobject<T> {
+    fn new(subobject_type: D3D12_PIPELINE_STATE_SUBOBJECT_TYPE, data: T) -> Self {
+        PipelineStateSubobject {
+            subobject_type,
+            data
+        }
+    }
+}
+
+/// Pipeline state stream used by `create_mesh_pipeline`, mesh pipelines can only be created from a stream
+#[repr(C)]
+struct MeshPipelineStateStream {
+    root_signature: PipelineStateSubobject<Option<ID3D12RootSignature>>,
+    amplification_shader: PipelineStateSubobject<D3D12_SHADER_BYTECODE>,
+    mesh_shader: PipelineStateSubobject<D3D12_SHADER_BYTECODE>,
+    pixel_shader: PipelineStateSubobject<D3D12_SHADER_BYTECODE>,
+    rasterizer: PipelineStateSubobject<D3D12_RASTERIZER_DESC>,
+    blend: PipelineStateSubobject<D3D12_BLEND_DESC>,
+    depth_stencil: PipelineStateSubobject<D3D12_DEPTH_STENCIL_DESC>,
+    rt_formats: PipelineStateSubobject<D3D12_RT_FORMAT_ARRAY>,
+    ds_format: PipelineStateSubobject<DXGI_FORMAT>,
+    sample_desc: PipelineStateSubobject<DXGI_SAMPLE_DESC>,
+    sample_mask: PipelineStateSubobject<u32>,
 }
 
 #[derive(Clone)]
 pub struct CmdBuf {
     bb_index: usize,
+    queue_type: super::QueueType,
     command_allocator: Vec<ID3D12CommandAllocator>,
     command_list: Vec<ID3D12GraphicsCommandList>,
     needs_reset: Vec<bool>,
     pix: Option<WinPixEventRuntime>,
     in_flight_barriers: Vec<Vec<D3D12_RESOURCE_BARRIER>>,
-    event_stack_count: u32
+    event_stack_count: u32,
+    draw_indirect_signature: ID3D12CommandSignature,
+    draw_indexed_indirect_signature: ID3D12CommandSignature,
+    dispatch_indirect_signature: ID3D12CommandSignature,
+    /// readback buffers used by `read_back_texture` which are no longer owned by any `ReadBackRequest`
+    free_read_back_buffers: Arc<Mutex<Vec<FreeReadBackBuffer>>>,
+    timers: Arc<Mutex<Timers>>,
+    /// name and begin query index of timers which have not ended yet
+    timer_stack: Vec<(String, u32)>,
+    occlusion_queries: Arc<Mutex<OcclusionQueries>>,
+    /// variable rate shading commands are skipped on devices which do not support them
+    variable_shading_rate_tier: u32,
+    /// swap chain fence and the value it will be signalled with at the end of the frame being recorded
+    frame_fence: Option<(ID3D12Fence, u64)>,
+    /// shader and sampler heaps bound on the command list, `SetDescriptorHeaps` replaces both so we keep track
+    bound_heaps: Arc<Mutex<[Option<ID3D12DescriptorHeap>; 2]>>,
+    /// heap bound with `set_bindless_heap` which is rebound each time a pipeline sets a new root signature
+    bindless: Arc<Mutex<BindlessBinding>>
+}
+
+/// Start of the heap bound with `CmdBuf::set_bindless_heap` and the bindless table slot of the bound pipeline as
+/// (slot, compute), root signature changes reset root tables so the heap is rebound when pipelines are set
+#[derive(Default)]
+struct BindlessBinding {
+    heap_start: Option<D3D12_GPU_DESCRIPTOR_HANDLE>,
+    slot: Option<(u32, bool)>
 }
 
 #[derive(Clone)]
@@ -189,6 +315,10 @@ pub struct Buffer {
     ibv: Option<D3D12_INDEX_BUFFER_VIEW>,
     srv_index: Option<usize>,
     uav_index: Option<usize>,
+    /// non shader visible copy of the uav in the device `shader_cpu_heap`
+    uav_cpu: Option<D3D12_CPU_DESCRIPTOR_HANDLE>,
+    /// tracked resource state shared between clones, `None` for upload buffers which cannot transition
+    state: Option<Arc<Mutex<super::ResourceState>>>,
 }
 
 #[derive(Clone)]
@@ -200,6 +330,8 @@ pub struct Shader {
 #[derive(Clone)]
 pub struct Texture {
     resource: ID3D12Resource,
+    /// typed format of the texture, the resource itself may be typeless
+    format: DXGI_FORMAT,
     resolved_resource: Option<ID3D12Resource>,
     resolved_format: DXGI_FORMAT,
     rtv: Option<D3D12_CPU_DESCRIPTOR_HANDLE>,
@@ -207,16 +339,54 @@ pub struct Texture {
     srv_index: Option<usize>,
     resolved_srv_index: Option<usize>,
     uav_index: Option<usize>,
+    /// non shader visible copy of the uav in the device `shader_cpu_heap`
+    uav_cpu: Option<D3D12_CPU_DESCRIPTOR_HANDLE>,
     shared_handle: Option<HANDLE>,
+    /// tracked resource state shared between clones
+    state: Arc<Mutex<super::ResourceState>>,
 }
 
 #[derive(Clone)]
 pub struct ReadBackRequest {
     pub resource: Option<ID3D12Resource>,
+    /// fence signalled by the swap chain at the end of the frame the request was submitted in
+    pub fence: ID3D12Fence,
     pub fence_value: u64,
     pub size: usize,
     pub row_pitch: usize,
     pub slice_pitch: usize,
+    /// pooled buffer from `CmdBuf::read_back_texture`, shared by clones and returned to the pool when all are dropped
+    buffer: Option<Arc<ReadBackBuffer>>,
+}
+
+/// A readback buffer owned by a `ReadBackRequest` and its clones, it is returned to the free list of the command buffer
+/// which created it when the last request is dropped
+struct ReadBackBuffer {
+    size: u64,
+    resource: ID3D12Resource,
+    /// fence and value the copy into the buffer completes at, updated by `ReadBackRequest::set_fence`
+    fence: Mutex<(ID3D12Fence, u64)>,
+    free_list: Arc<Mutex<Vec<FreeReadBackBuffer>>>,
+}
+
+/// A readback buffer no longer owned by any request, it can be reused once `fence` has reached `fence_value`
+struct FreeReadBackBuffer {
+    size: u64,
+    resource: ID3D12Resource,
+    fence: ID3D12Fence,
+    fence_value: u64,
+}
+
+impl Drop for ReadBackBuffer {
+    fn drop(&mut self) {
+        let (fence, fence_value) = self.fence.lock().unwrap().clone();
+        self.free_list.lock().unwrap().push(FreeReadBackBuffer {
+            size: self.size,
+            resource: self.resource.clone(),
+            fence,
+            fence_value,
+        });
+    }
 }
 
 
@@ -232,23 +402,226 @@ pub struct RenderPass {
 
 #[derive(Clone)]
 pub struct Heap {
+    device: ID3D12Device,
     heap: ID3D12DescriptorHeap,
+    heap_type: super::HeapType,
     base_address: usize,
     increment_size: usize,
     capacity: usize,
     offset: usize,
     free_list: Vec<usize>,
+    /// non shader visible copy of shader visible heaps, descriptors are written here and then copied into `heap`
+    /// with `commit`, because descriptors can only be copied from non shader visible heaps when growing
+    shadow: Option<ID3D12DescriptorHeap>,
+    /// heaps replaced by `grow` tuple (frames waited, heap), released in `clean_up_resources` once no longer in use
+    retired_heaps: Vec<(u32, ID3D12DescriptorHeap)>,
+    /// additional heaps for render target and depth stencil heaps which are full, their cpu handles are held
+    /// directly by textures so they cannot move and grow by chaining instead
+    chained_heaps: Vec<ChainedHeap>,
+    /// bitset of allocated descriptor indices, used to validate frees
+    allocated: Vec<u64>,
+}
+
+/// A heap chained onto a full heap, indices continue on from the heap before it
+#[derive(Clone)]
+struct ChainedHeap {
+    heap: ID3D12DescriptorHeap,
+    base_address: usize,
+    first_index: usize,
+    num_descriptors: usize,
 }
 
 #[derive(Clone)]
 pub struct ComputePipeline {
     pso: ID3D12PipelineState,
     root_signature: ID3D12RootSignature,
+    /// root slot of the first descriptor table with an unbounded range
+    bindless_slot: Option<u32>,
+}
+
+/// Maximum number of timestamp queries in flight, each timer uses a begin and end query
+const MAX_TIMESTAMP_QUERIES: u32 = 1024;
+
+/// Timestamp queries shared between the device and command buffers, written by `CmdBuf::begin_timer` and
+/// `CmdBuf::end_timer` and resolved into a readback buffer at the query offset
+struct Timers {
+    heap: ID3D12QueryHeap,
+    readback: ID3D12Resource,
+    /// ticks per second of the direct queue timestamps
+    frequency: u64,
+    next_query: u32,
+    pending: Vec<PendingTimer>,
+    results: HashMap<String, f64>,
+}
+
+/// Number of binary occlusion query slots available to `CmdBuf::begin_occlusion_query`
+const MAX_OCCLUSION_QUERIES: u32 = 1024;
+
+/// Binary occlusion queries shared between the device and command buffers, each query is resolved into the readback
+/// buffer at its own offset by `CmdBuf::end_occlusion_query`
+struct OcclusionQueries {
+    heap: ID3D12QueryHeap,
+    readback: ID3D12Resource,
+    pending: Vec<PendingOcclusionQuery>,
+    results: Vec<bool>,
+}
+
+/// An occlusion query which has been resolved but its frame may not have completed on the gpu yet
+struct PendingOcclusionQuery {
+    index: u32,
+    fence: ID3D12Fence,
+    fence_value: u64,
+}
+
+/// A timer which has been recorded but its frame may not have completed on the gpu yet
+struct PendingTimer {
+    name: String,
+    query: u32,
+    fence: ID3D12Fence,
+    fence_value: u64,
+}
+
+/// Copy queue used to batch initial data uploads of buffers and textures, uploads are submitted together on
+/// `Device::execute` or `Device::flush_uploads`
+struct UploadQueue {
+    queue: ID3D12CommandQueue,
+    allocator: ID3D12CommandAllocator,
+    list: ID3D12GraphicsCommandList,
+    /// direct list to transition uploaded resources from the common state they decay to after copy queue access
+    transition_allocator: ID3D12CommandAllocator,
+    transition_list: ID3D12GraphicsCommandList,
+    transitions: Vec<D3D12_RESOURCE_BARRIER>,
+    /// signalled by the copy queue when copies complete and by the direct queue once transitions complete
+    fence: Fence,
+    fence_value: u64,
+    /// true if the lists are open for recording
+    open: bool,
+    /// true if copies have been recorded which are not yet submitted
+    pending: bool,
+    /// upload buffers for the pending batch, and for submitted batches tagged with the fence value of the batch
+    upload_buffers: Vec<ID3D12Resource>,
+    in_flight_upload_buffers: Vec<(u64, ID3D12Resource)>,
+    /// persistently mapped staging ring for `Device::upload_buffer_region`
+    ring: ID3D12Resource,
+    ring_data: *mut u8,
+    ring_head: u64,
+    /// (fence value, start, end) of ring regions in allocation order, the fence value is `None` until submitted
+    ring_regions: VecDeque<(Option<u64>, u64, u64)>,
+}
+
+/// Size in bytes of the staging ring used by `Device::upload_buffer_region`
+const UPLOAD_RING_SIZE: u64 = 8 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct Sampler {
+    index: usize,
+}
+
+pub struct RaytracingBLAS {
+    resource: ID3D12Resource,
+    scratch: ID3D12Resource,
+    geometry: Vec<D3D12_RAYTRACING_GEOMETRY_DESC>,
+    flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS,
+}
+
+pub struct RaytracingTLAS {
+    resource: ID3D12Resource,
+    scratch: ID3D12Resource,
+    /// upload buffer of `RaytracingInstanceDesc` read during the build
+    instances: ID3D12Resource,
+    num_instances: u32,
+    flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS,
+    srv_index: usize,
+    /// instanced bottom levels are kept alive as long as the top level references them
+    _blas: Vec<ID3D12Resource>,
+}
+
+/// matches the layout of `D3D12_RAYTRACING_INSTANCE_DESC`, the id and mask, and the hit group index and flags are
+/// packed into 24 and 8 bit fields
+#[repr(C)]
+struct RaytracingInstanceDesc {
+    transform: [f32; 12],
+    instance_id_and_mask: u32,
+    hit_group_index_and_flags: u32,
+    acceleration_structure: u64,
+}
+
+#[derive(Clone)]
+pub struct RaytracingPipeline {
+    state_object: ID3D12StateObject,
+    root_signature: ID3D12RootSignature,
+    /// root slot of the first descriptor table with an unbounded range
+    bindless_slot: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct RaytracingShaderBindingTable {
+    resource: ID3D12Resource,
+    record_size: u64,
+    miss_offset: u64,
+    num_miss_shaders: u64,
+    hit_group_offset: u64,
+    num_hit_groups: u64,
+}
+
+#[derive(Clone)]
+pub struct Fence {
+    fence: ID3D12Fence,
+}
+
+#[derive(Clone)]
+pub struct PipelineLibrary {
+    library: ID3D12PipelineLibrary,
+    /// serialized blob the library was created from, d3d12 reads pipelines from it in place so it must outlive
+    /// the library
+    _data: Option<Arc<[u8]>>,
+}
+
+#[derive(Clone)]
+pub struct RingBuffer {
+    resource: ID3D12Resource,
+    cpu_address: *mut u8,
+    gpu_address: u64,
+    frame_size: usize,
+    bb_index: usize,
+    offset: usize,
 }
 
+/// downsamples each layer of `src_size` mip `src_index` into the next mip `dst_index` with a box filter, the last
+/// row and column of odd sized mips also take the remaining source texels, so mip sizes are floored
+const MIP_DOWNSAMPLE_SRC: &str = "
+    cbuffer mip_constants : register(b0) {
+        uint src_index;
+        uint dst_index;
+        uint2 src_size;
+    };
+    Texture2DArray src_textures[] : register(t0, space0);
+    RWTexture2DArray<float4> dst_textures[] : register(u0, space1);
+
+    [numthreads(8, 8, 1)]
+    void cs_main(uint3 did : SV_DispatchThreadID) {
+        uint2 dst_size = max(src_size >> 1, uint2(1, 1));
+        if (any(did.xy >= dst_size)) {
+            return;
+        }
+        uint2 first = did.xy * 2;
+        uint2 last = min(first + 1 + uint2(did.xy == dst_size - 1) * (src_size & 1), src_size - 1);
+        float4 sum = float4(0.0, 0.0, 0.0, 0.0);
+        float count = 0.0;
+        for (uint y = first.y; y <= last.y; ++y) {
+            for (uint x = first.x; x <= last.x; ++x) {
+                sum += src_textures[src_index].Load(int4(x, y, did.z, 0));
+                count += 1.0;
+            }
+        }
+        dst_textures[dst_index][did] = sum / count;
+    }
+";
+
 const fn to_dxgi_format(format: super::Format) -> DXGI_FORMAT {
     match format {
         super::Format::Unknown => DXGI_FORMAT_UNKNOWN,
+        super::Format::R8u => DXGI_FORMAT_R8_UINT,
         super::Format::R16n => DXGI_FORMAT_R16_UNORM,
         super::Format::R16u => DXGI_FORMAT_R16_UINT,
         super::Format::R16i => DXGI_FORMAT_R16_SINT,
@@ -263,9 +636,12 @@ const fn to_dxgi_format(format: super::Format) -> DXGI_FORMAT {
         super::Format::RGB32i => DXGI_FORMAT_R32G32B32_SINT,
         super::Format::RGB32f => DXGI_FORMAT_R32G32B32_FLOAT,
         super::Format::RGBA8n => DXGI_FORMAT_R8G8B8A8_UNORM,
+        super::Format::RGBA8nSRGB => DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
         super::Format::RGBA8u => DXGI_FORMAT_R8G8B8A8_UINT,
         super::Format::RGBA8i => DXGI_FORMAT_R8G8B8A8_SINT,
         super::Format::BGRA8n => DXGI_FORMAT_B8G8R8A8_UNORM,
+        super::Format::BGRA8nSRGB => DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+        super::Format::RGB10A2n => DXGI_FORMAT_R10G10B10A2_UNORM,
         super::Format::RGBA16u => DXGI_FORMAT_R16G16B16A16_UINT,
         super::Format::RGBA16i => DXGI_FORMAT_R16G16B16A16_SINT,
         super::Format::RGBA16f => DXGI_FORMAT_R16G16B16A16_FLOAT,
@@ -276,6 +652,40 @@ const fn to_dxgi_format(format: super::Format) -> DXGI_FORMAT {
         super::Format::D32f => DXGI_FORMAT_D32_FLOAT,
         super::Format::D24nS8u => DXGI_FORMAT_D24_UNORM_S8_UINT,
         super::Format::D16n => DXGI_FORMAT_D16_UNORM,
+        super::Format::BC1n => DXGI_FORMAT_BC1_UNORM,
+        super::Format::BC3n => DXGI_FORMAT_BC3_UNORM,
+        super::Format::BC4n => DXGI_FORMAT_BC4_UNORM,
+        super::Format::BC5n => DXGI_FORMAT_BC5_UNORM,
+        super::Format::BC6H => DXGI_FORMAT_BC6H_UF16,
+        super::Format::BC7n => DXGI_FORMAT_BC7_UNORM,
+    }
+}
+
+const fn to_dxgi_format_typeless(format: super::Format) -> DXGI_FORMAT {
+    match format {
+        super::Format::Unknown => DXGI_FORMAT_UNKNOWN,
+        super::Format::R8u => DXGI_FORMAT_R8_TYPELESS,
+        super::Format::R16n | super::Format::R16u | super::Format::R16i | super::Format::R16f => DXGI_FORMAT_R16_TYPELESS,
+        super::Format::R32u | super::Format::R32i | super::Format::R32f => DXGI_FORMAT_R32_TYPELESS,
+        super::Format::RG32u | super::Format::RG32i | super::Format::RG32f => DXGI_FORMAT_R32G32_TYPELESS,
+        super::Format::RGB32u | super::Format::RGB32i | super::Format::RGB32f => DXGI_FORMAT_R32G32B32_TYPELESS,
+        super::Format::RGBA8n | super::Format::RGBA8nSRGB | super::Format::RGBA8u | super::Format::RGBA8i => {
+            DXGI_FORMAT_R8G8B8A8_TYPELESS
+        }
+        super::Format::BGRA8n | super::Format::BGRA8nSRGB => DXGI_FORMAT_B8G8R8A8_TYPELESS,
+        super::Format::RGB10A2n => DXGI_FORMAT_R10G10B10A2_TYPELESS,
+        super::Format::RGBA16u | super::Format::RGBA16i | super::Format::RGBA16f => DXGI_FORMAT_R16G16B16A16_TYPELESS,
+        super::Format::RGBA32u | super::Format::RGBA32i | super::Format::RGBA32f => DXGI_FORMAT_R32G32B32A32_TYPELESS,
+        super::Format::D32fS8X24u => DXGI_FORMAT_R32G8X24_TYPELESS,
+        super::Format::D32f => DXGI_FORMAT_R32_TYPELESS,
+        super::Format::D24nS8u => DXGI_FORMAT_R24G8_TYPELESS,
+        super::Format::D16n => DXGI_FORMAT_R16_TYPELESS,
+        super::Format::BC1n => DXGI_FORMAT_BC1_TYPELESS,
+        super::Format::BC3n => DXGI_FORMAT_BC3_TYPELESS,
+        super::Format::BC4n => DXGI_FORMAT_BC4_TYPELESS,
+        super::Format::BC5n => DXGI_FORMAT_BC5_TYPELESS,
+        super::Format::BC6H => DXGI_FORMAT_BC6H_TYPELESS,
+        super::Format::BC7n => DXGI_FORMAT_BC7_TYPELESS,
     }
 }
 
@@ -289,6 +699,95 @@ const fn to_dxgi_format_srv(format: super::Format) -> DXGI_FORMAT {
     }
 }
 
+/// returns the non srgb equivalent of a typed format, generated mips are filtered and written through a linear view
+fn to_dxgi_format_linear(format: DXGI_FORMAT) -> DXGI_FORMAT {
+    match format {
+        DXGI_FORMAT_R8G8B8A8_UNORM_SRGB => DXGI_FORMAT_R8G8B8A8_UNORM,
+        DXGI_FORMAT_B8G8R8A8_UNORM_SRGB => DXGI_FORMAT_B8G8R8A8_UNORM,
+        _ => format
+    }
+}
+
+/// returns the major shader model of a compile target such as `cs_6_5`, or 0 if it cannot be parsed
+fn get_shader_model(target: &str) -> u32 {
+    target.split('_').nth(1).and_then(|major| major.parse().ok()).unwrap_or(0)
+}
+
+/// returns true if `bytecode` is a shader container holding dxil compiled with dxc, rather than dxbc compiled with
+/// fxc. both use the same container with the DXBC fourcc, dxil is stored in a part with the DXIL fourcc
+fn is_dxil_container(bytecode: &[u8]) -> bool {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        bytecode.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    // header: fourcc, 16 byte digest, version, container size, part count then an offset for each part
+    if bytecode.get(0..4) != Some(b"DXBC".as_slice()) {
+        return false;
+    }
+    let part_count = read_u32(28).unwrap_or(0) as usize;
+    (0..part_count).any(|part| {
+        read_u32(32 + part * 4)
+            .and_then(|offset| bytecode.get(offset as usize..offset as usize + 4))
+            .map_or(false, |fourcc| fourcc == b"DXIL")
+    })
+}
+
+/// creates a dxc com object, dxcompiler.dll is loaded on demand so it is only required by shader model 6 shaders
+fn create_dxc_instance<T: Interface>(clsid: &GUID) -> result::Result<T, super::Error> {
+    unsafe {
+        let module = LoadLibraryA("dxcompiler.dll\0");
+        let create = GetProcAddress(module, "DxcCreateInstance\0").ok_or_else(|| super::Error {
+            msg: "hotline_rs::gfx::d3d12: shader model 6 requires dxcompiler.dll".to_string()
+        })?;
+        let create = std::mem::transmute::<*const usize, DxcCreateInstanceProc>(create as *const usize);
+        let mut instance: Option<T> = None;
+        create(clsid, &T::IID, &mut instance as *mut _ as *mut *mut core::ffi::c_void).ok()?;
+        instance.ok_or_else(|| super::Error {
+            msg: "hotline_rs::gfx::d3d12: failed to create dxc instance".to_string()
+        })
+    }
+}
+
+/// compiles `src` to a dxil shader container with dxc for shader model 6 targets
+fn compile_dxil(compile_info: &super::ShaderCompileInfo, src: &[u8]) -> result::Result<Vec<u8>, super::Error> {
+    let compiler: IDxcCompiler3 = create_dxc_instance(&DXC_COMPILER_CLSID)?;
+    let mut args = vec![
+        "-T".to_string(),
+        compile_info.target.to_string()
+    ];
+    // libraries export all of their entry points
+    if !compile_info.target.starts_with("lib_") {
+        args.push("-E".to_string());
+        args.push(compile_info.entry_point.to_string());
+    }
+    if compile_info.flags.contains(super::ShaderCompileFlags::SKIP_OPTIMIZATION) {
+        args.push("-Od".to_string());
+    }
+    if compile_info.flags.contains(super::ShaderCompileFlags::DEBUG) {
+        args.push("-Zi".to_string());
+        args.push("-Qembed_debug".to_string());
+    }
+    let wide_args = args.iter()
+        .map(|arg| arg.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>())
+        .collect::<Vec<Vec<u16>>>();
+    let arg_ptrs = wide_args.iter().map(|arg| PCWSTR(arg.as_ptr())).collect::<Vec<PCWSTR>>();
+    unsafe {
+        let result = compiler.Compile::<_, IDxcResult>(&DxcBuffer {
+            Ptr: src.as_ptr() as *const core::ffi::c_void,
+            Size: src.len(),
+            Encoding: DXC_CP_UTF8
+        }, &arg_ptrs, None)?;
+        if result.GetStatus()?.is_err() {
+            let errors = result.GetErrorBuffer()?;
+            let errors = std::slice::from_raw_parts(errors.GetBufferPointer() as *const u8, errors.GetBufferSize());
+            return Err(super::Error {
+                msg: String::from_utf8_lossy(errors).trim_end_matches('\0').to_string()
+            });
+        }
+        let blob = result.GetResult()?;
+        Ok(std::slice::from_raw_parts(blob.GetBufferPointer() as *const u8, blob.GetBufferSize()).to_vec())
+    }
+}
+
 const fn to_d3d12_compile_flags(flags: &super::ShaderCompileFlags) -> u32 {
     let mut d3d12_flags = 0;
     if flags.contains(super::ShaderCompileFlags::SKIP_OPTIMIZATION) {
@@ -306,6 +805,9 @@ const fn to_d3d12_shader_visibility(visibility: &super::ShaderVisibility) -> D3D
         super::ShaderVisibility::Vertex => D3D12_SHADER_VISIBILITY_VERTEX,
         super::ShaderVisibility::Fragment => D3D12_SHADER_VISIBILITY_PIXEL,
         super::ShaderVisibility::Compute => D3D12_SHADER_VISIBILITY_ALL,
+        super::ShaderVisibility::Hull => D3D12_SHADER_VISIBILITY_HULL,
+        super::ShaderVisibility::Domain => D3D12_SHADER_VISIBILITY_DOMAIN,
+        super::ShaderVisibility::Geometry => D3D12_SHADER_VISIBILITY_GEOMETRY,
     }
 }
 
@@ -348,6 +850,17 @@ const fn to_d3d12_comparison_func(func: super::ComparisonFunc) -> D3D12_COMPARIS
     }
 }
 
+/// unpacks an rgba8 colour packed into a u32 with red in the most significant byte
+fn to_d3d12_sampler_border_colour_rgba(col: Option<u32>) -> [f32; 4] {
+    let col = col.unwrap_or(0);
+    [
+        ((col >> 24) & 0xff) as f32 / 255.0,
+        ((col >> 16) & 0xff) as f32 / 255.0,
+        ((col >> 8) & 0xff) as f32 / 255.0,
+        (col & 0xff) as f32 / 255.0,
+    ]
+}
+
 fn to_d3d12_address_comparison_func(func: Option<super::ComparisonFunc>) -> D3D12_COMPARISON_FUNC {
     if let Some(func) = func {
         to_d3d12_comparison_func(func)
@@ -357,6 +870,11 @@ fn to_d3d12_address_comparison_func(func: Option<super::ComparisonFunc>) -> D3D1
     }
 }
 
+/// returns true if a typed depth stencil `format` has a stencil plane
+fn has_stencil_plane(format: DXGI_FORMAT) -> bool {
+    format == DXGI_FORMAT_D24_UNORM_S8_UINT || format == DXGI_FORMAT_D32_FLOAT_S8X24_UINT
+}
+
 const fn to_d3d12_resource_state(state: super::ResourceState) -> D3D12_RESOURCE_STATES {
     match state {
         super::ResourceState::RenderTarget => D3D12_RESOURCE_STATE_RENDER_TARGET,
@@ -369,23 +887,74 @@ const fn to_d3d12_resource_state(state: super::ResourceState) -> D3D12_RESOURCE_
         super::ResourceState::IndexBuffer => D3D12_RESOURCE_STATE_INDEX_BUFFER,
         super::ResourceState::DepthStencil => D3D12_RESOURCE_STATE_DEPTH_WRITE,
         super::ResourceState::DepthStencilReadOnly => D3D12_RESOURCE_STATE_DEPTH_READ,
+        super::ResourceState::DepthStencilReadOnlyShaderResource => {
+            D3D12_RESOURCE_STATES(D3D12_RESOURCE_STATE_DEPTH_READ.0 | D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE.0)
+        }
         super::ResourceState::ResolveSrc => D3D12_RESOURCE_STATE_RESOLVE_SOURCE,
         super::ResourceState::ResolveDst => D3D12_RESOURCE_STATE_RESOLVE_DEST,
+        super::ResourceState::CopySrc => D3D12_RESOURCE_STATE_COPY_SOURCE,
+        super::ResourceState::CopyDst => D3D12_RESOURCE_STATE_COPY_DEST,
+        super::ResourceState::IndirectArgument => D3D12_RESOURCE_STATE_INDIRECT_ARGUMENT,
+        super::ResourceState::NonPixelShaderResource => D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
+        super::ResourceState::ShadingRateSource => D3D12_RESOURCE_STATE_SHADING_RATE_SOURCE,
+    }
+}
+
+const fn to_d3d12_shading_rate(rate: super::ShadingRate) -> D3D12_SHADING_RATE {
+    match rate {
+        super::ShadingRate::Rate1x1 => D3D12_SHADING_RATE_1X1,
+        super::ShadingRate::Rate1x2 => D3D12_SHADING_RATE_1X2,
+        super::ShadingRate::Rate2x1 => D3D12_SHADING_RATE_2X1,
+        super::ShadingRate::Rate2x2 => D3D12_SHADING_RATE_2X2,
+        super::ShadingRate::Rate2x4 => D3D12_SHADING_RATE_2X4,
+        super::ShadingRate::Rate4x2 => D3D12_SHADING_RATE_4X2,
+        super::ShadingRate::Rate4x4 => D3D12_SHADING_RATE_4X4,
+    }
+}
+
+const fn to_d3d12_shading_rate_combiner(combiner: super::ShadingRateCombiner) -> D3D12_SHADING_RATE_COMBINER {
+    match combiner {
+        super::ShadingRateCombiner::Passthrough => D3D12_SHADING_RATE_COMBINER_PASSTHROUGH,
+        super::ShadingRateCombiner::Override => D3D12_SHADING_RATE_COMBINER_OVERRIDE,
+        super::ShadingRateCombiner::Min => D3D12_SHADING_RATE_COMBINER_MIN,
+        super::ShadingRateCombiner::Max => D3D12_SHADING_RATE_COMBINER_MAX,
+        super::ShadingRateCombiner::Sum => D3D12_SHADING_RATE_COMBINER_SUM,
+    }
+}
+
+const fn to_d3d12_command_list_type(queue: super::QueueType) -> D3D12_COMMAND_LIST_TYPE {
+    match queue {
+        super::QueueType::Graphics => D3D12_COMMAND_LIST_TYPE_DIRECT,
+        super::QueueType::Compute => D3D12_COMMAND_LIST_TYPE_COMPUTE,
     }
 }
 
 const fn to_d3d12_descriptor_heap_type(heap_type: super::HeapType) -> D3D12_DESCRIPTOR_HEAP_TYPE {
     match heap_type {
         super::HeapType::Shader => D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
+        super::HeapType::ShaderCpu => D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV,
         super::HeapType::RenderTarget => D3D12_DESCRIPTOR_HEAP_TYPE_RTV,
         super::HeapType::DepthStencil => D3D12_DESCRIPTOR_HEAP_TYPE_DSV,
         super::HeapType::Sampler => D3D12_DESCRIPTOR_HEAP_TYPE_SAMPLER,
     }
 }
 
+const fn max_descriptors_for_heap_type(heap_type: super::HeapType) -> usize {
+    match heap_type {
+        // D3D12_MAX_SHADER_VISIBLE_DESCRIPTOR_HEAP_SIZE_TIER_1
+        super::HeapType::Shader => 1000000,
+        // D3D12_MAX_SHADER_VISIBLE_SAMPLER_HEAP_SIZE
+        super::HeapType::Sampler => 2048,
+        super::HeapType::ShaderCpu => usize::MAX,
+        super::HeapType::RenderTarget => usize::MAX,
+        super::HeapType::DepthStencil => usize::MAX,
+    }
+}
+
 const fn to_d3d12_descriptor_heap_flags(heap_type: super::HeapType) -> D3D12_DESCRIPTOR_HEAP_FLAGS {
     match heap_type {
         super::HeapType::Shader => D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE,
+        super::HeapType::ShaderCpu => D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
         super::HeapType::RenderTarget => D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
         super::HeapType::DepthStencil => D3D12_DESCRIPTOR_HEAP_FLAG_NONE,
         super::HeapType::Sampler => D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE,
@@ -400,7 +969,7 @@ fn to_d3d12_texture_usage_flags(usage: super::TextureUsage) -> D3D12_RESOURCE_FL
     if usage.contains(super::TextureUsage::DEPTH_STENCIL) {
         flags |= D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL;
     }
-    if usage.contains(super::TextureUsage::UNORDERED_ACCESS) {
+    if usage.intersects(super::TextureUsage::UNORDERED_ACCESS | super::TextureUsage::GENERATE_MIP_MAPS) {
         flags |= D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS;
     }
     if usage.contains(super::TextureUsage::VIDEO_DECODE_TARGET) {
@@ -489,12 +1058,119 @@ const fn to_d3d12_stencil_op(op: &super::StencilOp) -> D3D12_STENCIL_OP {
     }
 }
 
+fn to_d3d12_rasterizer_desc(raster: &super::RasterInfo, msaa_format: bool) -> D3D12_RASTERIZER_DESC {
+    D3D12_RASTERIZER_DESC {
+        FillMode: to_d3d12_fill_mode(&raster.fill_mode),
+        CullMode: to_d3d12_cull_mode(&raster.cull_mode),
+        FrontCounterClockwise: BOOL::from(raster.front_ccw),
+        DepthBias: raster.depth_bias,
+        DepthBiasClamp: raster.depth_bias_clamp,
+        SlopeScaledDepthBias: raster.slope_scaled_depth_bias,
+        DepthClipEnable: BOOL::from(raster.front_ccw),
+        MultisampleEnable: BOOL::from(msaa_format),
+        AntialiasedLineEnable: BOOL::from(msaa_format),
+        ForcedSampleCount: raster.forced_sample_count,
+        ConservativeRaster: if raster.conservative_raster_mode {
+            D3D12_CONSERVATIVE_RASTERIZATION_MODE_ON
+        } else {
+            D3D12_CONSERVATIVE_RASTERIZATION_MODE_OFF
+        },
+    }
+}
+
+/// a single blend info applies to all targets, otherwise there must be one for each of the `num_targets` in the pass
+fn to_d3d12_blend_desc(blend: &super::BlendInfo, num_targets: usize) -> result::Result<D3D12_BLEND_DESC, super::Error> {
+    let num_blends = blend.render_target.len();
+    if num_blends > 1 && num_blends != num_targets {
+        return Err(super::Error {
+            msg: format!(
+                "hotline_rs::gfx::d3d12: number of render target blend infos ({}) does not match number of render targets in the pass ({})",
+                num_blends, num_targets
+            ),
+        });
+    }
+    let independent_blend = blend.independent_blend_enabled ||
+        blend.render_target.windows(2).any(|pair| pair[0] != pair[1]);
+    Ok(D3D12_BLEND_DESC {
+        AlphaToCoverageEnable: BOOL::from(blend.alpha_to_coverage_enabled),
+        IndependentBlendEnable: BOOL::from(independent_blend),
+        RenderTarget: to_d3d12_render_target_blend(&blend.render_target),
+    })
+}
+
+fn to_d3d12_depth_stencil_desc(depth_stencil: &super::DepthStencilInfo) -> D3D12_DEPTH_STENCIL_DESC {
+    D3D12_DEPTH_STENCIL_DESC {
+        DepthEnable: BOOL::from(depth_stencil.depth_enabled),
+        DepthWriteMask: to_d3d12_write_mask(&depth_stencil.depth_write_mask),
+        DepthFunc: to_d3d12_comparison_func(depth_stencil.depth_func),
+        StencilEnable: BOOL::from(depth_stencil.stencil_enabled),
+        StencilReadMask: depth_stencil.stencil_read_mask,
+        StencilWriteMask: depth_stencil.stencil_write_mask,
+        FrontFace: D3D12_DEPTH_STENCILOP_DESC {
+            StencilFailOp: to_d3d12_stencil_op(&depth_stencil.front_face.fail),
+            StencilDepthFailOp: to_d3d12_stencil_op(&depth_stencil.front_face.depth_fail),
+            StencilPassOp: to_d3d12_stencil_op(&depth_stencil.front_face.pass),
+            StencilFunc: to_d3d12_comparison_func(depth_stencil.front_face.func),
+        },
+        BackFace: D3D12_DEPTH_STENCILOP_DESC {
+            StencilFailOp: to_d3d12_stencil_op(&depth_stencil.back_face.fail),
+            StencilDepthFailOp: to_d3d12_stencil_op(&depth_stencil.back_face.depth_fail),
+            StencilPassOp: to_d3d12_stencil_op(&depth_stencil.back_face.pass),
+            StencilFunc: to_d3d12_comparison_func(depth_stencil.back_face.func),
+        },
+    }
+}
+
+fn to_d3d12_acceleration_structure_build_flags(
+    flags: super::AccelerationStructureBuildFlags
+) -> D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS {
+    let mut d3d12_flags = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_NONE.0;
+    if flags.contains(super::AccelerationStructureBuildFlags::PREFER_FAST_TRACE) {
+        d3d12_flags |= D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_TRACE.0;
+    }
+    if flags.contains(super::AccelerationStructureBuildFlags::PREFER_FAST_BUILD) {
+        d3d12_flags |= D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAG_PREFER_FAST_BUILD.0;
+    }
+    D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS(d3d12_flags)
+}
+
+fn to_d3d12_blas_inputs(
+    geometry: &[D3D12_RAYTRACING_GEOMETRY_DESC],
+    flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS
+) -> D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+    D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+        Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL,
+        Flags: flags,
+        NumDescs: geometry.len() as u32,
+        DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+        Anonymous: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_0 {
+            pGeometryDescs: geometry.as_ptr()
+        }
+    }
+}
+
+fn to_d3d12_tlas_inputs(
+    instances: u64,
+    num_instances: u32,
+    flags: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BUILD_FLAGS
+) -> D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+    D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS {
+        Type: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL,
+        Flags: flags,
+        NumDescs: num_instances,
+        DescsLayout: D3D12_ELEMENTS_LAYOUT_ARRAY,
+        Anonymous: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS_0 {
+            InstanceDescs: instances
+        }
+    }
+}
+
 fn to_d3d12_render_target_blend(
     blend_info: &[super::RenderTargetBlendInfo],
 ) -> [D3D12_RENDER_TARGET_BLEND_DESC; 8] {
     let mut rtb: [D3D12_RENDER_TARGET_BLEND_DESC; 8] =
         [D3D12_RENDER_TARGET_BLEND_DESC::default(); 8];
-    for (i, b) in blend_info.iter().enumerate() {
+    for (i, b) in blend_info.iter().enumerate().take(8) {
         rtb[i] = D3D12_RENDER_TARGET_BLEND_DESC {
             BlendEnable: BOOL::from(b.blend_enabled),
             LogicOpEnable: BOOL::from(b.logic_op_enabled),
@@ -508,6 +1184,10 @@ fn to_d3d12_render_target_blend(
             RenderTargetWriteMask: u8::from(b.write_mask),
         };
     }
+    // a single blend info is shared by all targets
+    if blend_info.len() == 1 {
+        rtb = [rtb[0]; 8];
+    }
     rtb
 }
 
@@ -564,30 +1244,121 @@ const fn to_d3d12_logic_op(op: &super::LogicOp) -> D3D12_LOGIC_OP {
     }
 }
 
-fn to_d3d12_texture_srv_dimension(tex_type: super::TextureType, samples: u32) -> D3D12_SRV_DIMENSION {
-    if samples > 1 {
-        match tex_type {
-            super::TextureType::Texture1D => panic!(),
-            super::TextureType::Texture2D => D3D12_SRV_DIMENSION_TEXTURE2DMS,
-            super::TextureType::Texture3D => D3D12_SRV_DIMENSION_TEXTURE2DMSARRAY,
-        }
-    }
-    else {
-        match tex_type {
-            super::TextureType::Texture1D => D3D12_SRV_DIMENSION_TEXTURE1D,
-            super::TextureType::Texture2D => D3D12_SRV_DIMENSION_TEXTURE2D,
-            super::TextureType::Texture3D => D3D12_SRV_DIMENSION_TEXTURE3D,
-        }
+/// Returns a shader resource view desc covering all mips and array layers of a texture, `array_size` is the number
+/// of layers for 1D and 2D textures or the number of faces for cubemaps
+fn to_d3d12_texture_srv_desc(
+    tex_type: super::TextureType,
+    format: DXGI_FORMAT,
+    samples: u32,
+    mip_levels: u32,
+    array_size: u32
+) -> D3D12_SHADER_RESOURCE_VIEW_DESC {
+    let (view_dimension, view) = match tex_type {
+        super::TextureType::Texture1D if array_size > 1 => (
+            D3D12_SRV_DIMENSION_TEXTURE1DARRAY,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture1DArray: D3D12_TEX1D_ARRAY_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    FirstArraySlice: 0,
+                    ArraySize: array_size,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::Texture1D => (
+            D3D12_SRV_DIMENSION_TEXTURE1D,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture1D: D3D12_TEX1D_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::Texture2D if samples > 1 && array_size > 1 => (
+            D3D12_SRV_DIMENSION_TEXTURE2DMSARRAY,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2DMSArray: D3D12_TEX2DMS_ARRAY_SRV {
+                    FirstArraySlice: 0,
+                    ArraySize: array_size,
+                }
+            }
+        ),
+        super::TextureType::Texture2D if samples > 1 => (
+            D3D12_SRV_DIMENSION_TEXTURE2DMS,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2DMS: D3D12_TEX2DMS_SRV::default()
+            }
+        ),
+        super::TextureType::Texture2D if array_size > 1 => (
+            D3D12_SRV_DIMENSION_TEXTURE2DARRAY,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2DArray: D3D12_TEX2D_ARRAY_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    FirstArraySlice: 0,
+                    ArraySize: array_size,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::Texture2D => (
+            D3D12_SRV_DIMENSION_TEXTURE2D,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture2D: D3D12_TEX2D_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::Texture3D => (
+            D3D12_SRV_DIMENSION_TEXTURE3D,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                Texture3D: D3D12_TEX3D_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::TextureCube if array_size > 6 => (
+            D3D12_SRV_DIMENSION_TEXTURECUBEARRAY,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                TextureCubeArray: D3D12_TEXCUBE_ARRAY_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    First2DArrayFace: 0,
+                    NumCubes: array_size / 6,
+                    ..Default::default()
+                }
+            }
+        ),
+        super::TextureType::TextureCube => (
+            D3D12_SRV_DIMENSION_TEXTURECUBE,
+            D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                TextureCube: D3D12_TEXCUBE_SRV {
+                    MostDetailedMip: 0,
+                    MipLevels: mip_levels,
+                    ..Default::default()
+                }
+            }
+        )
+    };
+    D3D12_SHADER_RESOURCE_VIEW_DESC {
+        Format: format,
+        ViewDimension: view_dimension,
+        Anonymous: view,
+        Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
     }
 }
 
+/// copies the null terminated message out of an error blob, the blob owns its buffer so we must not take ownership
 fn get_d3d12_error_blob_string(blob: &ID3DBlob) -> String {
     unsafe {
-        String::from_raw_parts(
-            blob.GetBufferPointer() as *mut _,
-            blob.GetBufferSize(),
-            blob.GetBufferSize(),
-        )
+        let bytes = std::slice::from_raw_parts(blob.GetBufferPointer() as *const u8, blob.GetBufferSize());
+        String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string()
     }
 }
 
@@ -595,12 +1366,22 @@ fn transition_barrier(
     resource: &ID3D12Resource,
     state_before: D3D12_RESOURCE_STATES,
     state_after: D3D12_RESOURCE_STATES,
+) -> D3D12_RESOURCE_BARRIER {
+    transition_barrier_subresource_index(
+        resource, state_before, state_after, D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES)
+}
+
+fn transition_barrier_subresource_index(
+    resource: &ID3D12Resource,
+    state_before: D3D12_RESOURCE_STATES,
+    state_after: D3D12_RESOURCE_STATES,
+    subresource: u32,
 ) -> D3D12_RESOURCE_BARRIER {
     let trans = std::mem::ManuallyDrop::new(D3D12_RESOURCE_TRANSITION_BARRIER {
         pResource: Some(resource.clone()),
         StateBefore: state_before,
         StateAfter: state_after,
-        Subresource: D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES,
+        Subresource: subresource,
     });
     D3D12_RESOURCE_BARRIER {
         Type: D3D12_RESOURCE_BARRIER_TYPE_TRANSITION,
@@ -609,6 +1390,172 @@ fn transition_barrier(
     }
 }
 
+/// returns true if `err` was caused by the device being removed or reset, after which it must be recreated
+fn is_device_removed_error(err: &windows::core::Error) -> bool {
+    let code = err.code();
+    code == DXGI_ERROR_DEVICE_REMOVED || code == DXGI_ERROR_DEVICE_RESET || code == DXGI_ERROR_DEVICE_HUNG
+}
+
+/// readable name of a DRED breadcrumb op for the commands this engine records
+fn breadcrumb_op_name(op: D3D12_AUTO_BREADCRUMB_OP) -> String {
+    match op {
+        D3D12_AUTO_BREADCRUMB_OP_SETMARKER => "SetMarker".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_BEGINEVENT => "BeginEvent".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_ENDEVENT => "EndEvent".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_DRAWINSTANCED => "DrawInstanced".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_DRAWINDEXEDINSTANCED => "DrawIndexedInstanced".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_EXECUTEINDIRECT => "ExecuteIndirect".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_DISPATCH => "Dispatch".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_COPYBUFFERREGION => "CopyBufferRegion".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_COPYTEXTUREREGION => "CopyTextureRegion".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_COPYRESOURCE => "CopyResource".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_RESOLVESUBRESOURCE => "ResolveSubresource".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_CLEARRENDERTARGETVIEW => "ClearRenderTargetView".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_CLEARDEPTHSTENCILVIEW => "ClearDepthStencilView".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_RESOURCEBARRIER => "ResourceBarrier".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_PRESENT => "Present".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_RESOLVEQUERYDATA => "ResolveQueryData".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_BEGINSUBMISSION => "BeginSubmission".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_ENDSUBMISSION => "EndSubmission".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_BEGINRENDERPASS => "BeginRenderPass".to_string(),
+        D3D12_AUTO_BREADCRUMB_OP_ENDRENDERPASS => "EndRenderPass".to_string(),
+        _ => format!("op({})", op.0)
+    }
+}
+
+/// formats the auto breadcrumbs of command lists which did not complete and the faulting gpu virtual address
+/// recorded by DRED when the device was removed. DRED is only enabled in debug builds
+fn get_dred_info(device: &ID3D12Device) -> result::Result<String, super::Error> {
+    unsafe {
+        let dred: ID3D12DeviceRemovedExtendedData = device.cast()?;
+        let mut info = String::new();
+        if let Ok(breadcrumbs) = dred.GetAutoBreadcrumbsOutput() {
+            let mut node = breadcrumbs.pHeadAutoBreadcrumbNode;
+            while !node.is_null() {
+                let breadcrumb = &*node;
+                let completed = if breadcrumb.pLastBreadcrumbValue.is_null() {
+                    0
+                }
+                else {
+                    *breadcrumb.pLastBreadcrumbValue
+                };
+                // only command lists which did not complete are of interest
+                if completed < breadcrumb.BreadcrumbCount {
+                    info += &format!("command list breadcrumbs: {} of {} ops completed\n",
+                        completed, breadcrumb.BreadcrumbCount);
+                    for i in 0..breadcrumb.BreadcrumbCount {
+                        let op = *breadcrumb.pCommandHistory.offset(i as isize);
+                        let marker = if i == completed { " <- failed" } else { "" };
+                        info += &format!("    [{}] {}{}\n", i, breadcrumb_op_name(op), marker);
+                    }
+                }
+                node = breadcrumb.pNext;
+            }
+        }
+        if let Ok(page_fault) = dred.GetPageFaultAllocationOutput() {
+            if page_fault.PageFaultVA != 0 {
+                info += &format!("page fault gpu va: 0x{:x}\n", page_fault.PageFaultVA);
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// converts an error from the command queue or swap chain, if the device was removed the error contains the removed
+/// reason and any DRED info
+fn to_device_error(device: &ID3D12Device, err: windows::core::Error, context: &str) -> super::Error {
+    if is_device_removed_error(&err) {
+        let reason = unsafe { device.GetDeviceRemovedReason() }
+            .err()
+            .unwrap_or(err);
+        let mut msg = format!("hotline_rs::gfx::d3d12: device removed during {}: {}",
+            context, reason.message().to_string_lossy());
+        if let Ok(dred) = get_dred_info(device) {
+            msg += "\n";
+            msg += &dred;
+        }
+        super::Error {
+            msg
+        }
+    }
+    else {
+        super::Error {
+            msg: format!("hotline_rs::gfx::d3d12: {} failed: {}", context, err.message().to_string_lossy())
+        }
+    }
+}
+
+/// queries `feature` into a `T`, returns None if the runtime or driver does not recognise the feature
+fn check_feature_support<T: Default>(device: &ID3D12Device, feature: D3D12_FEATURE) -> Option<T> {
+    let mut data = T::default();
+    unsafe {
+        device.CheckFeatureSupport(
+            feature,
+            &mut data as *mut _ as *mut core::ffi::c_void,
+            std::mem::size_of::<T>() as u32
+        ).ok().map(|_| data)
+    }
+}
+
+/// fills out the capabilities of `device`, features newer than the runtime or driver are reported as unsupported
+fn get_device_feature_info(device: &ID3D12Device, adapter_info: &mut super::AdapterInfo) {
+    if let Some(options) = check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS>(device, D3D12_FEATURE_D3D12_OPTIONS) {
+        adapter_info.resource_binding_tier = options.ResourceBindingTier.0 as u32;
+    }
+    if let Some(options1) = check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS1>(device, D3D12_FEATURE_D3D12_OPTIONS1) {
+        adapter_info.wave_lane_count_min = options1.WaveLaneCountMin;
+        adapter_info.wave_lane_count_max = options1.WaveLaneCountMax;
+    }
+    if let Some(options5) = check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS5>(device, D3D12_FEATURE_D3D12_OPTIONS5) {
+        adapter_info.raytracing_tier = options5.RaytracingTier.0 as u32;
+    }
+    if let Some(options6) = check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS6>(device, D3D12_FEATURE_D3D12_OPTIONS6) {
+        adapter_info.variable_shading_rate_tier = options6.VariableShadingRateTier.0 as u32;
+        adapter_info.shading_rate_image_tile_size = options6.ShadingRateImageTileSize;
+    }
+    if let Some(options7) = check_feature_support::<D3D12_FEATURE_DATA_D3D12_OPTIONS7>(device, D3D12_FEATURE_D3D12_OPTIONS7) {
+        adapter_info.mesh_shader_tier = options7.MeshShaderTier.0 as u32;
+    }
+
+    // the highest shader model is queried by passing the highest model known, which fails on runtimes that do not
+    // recognise it, so step down until one is accepted. 5.1 is supported by all d3d12 devices
+    let shader_model = [0x67, 0x66, 0x65, 0x64, 0x63, 0x62, 0x61, 0x60].iter().find_map(|model| {
+        let mut data = D3D12_FEATURE_DATA_SHADER_MODEL {
+            HighestShaderModel: D3D_SHADER_MODEL(*model)
+        };
+        unsafe {
+            device.CheckFeatureSupport(
+                D3D12_FEATURE_SHADER_MODEL,
+                &mut data as *mut _ as *mut core::ffi::c_void,
+                std::mem::size_of::<D3D12_FEATURE_DATA_SHADER_MODEL>() as u32
+            ).ok().map(|_| data.HighestShaderModel.0)
+        }
+    }).unwrap_or(0x51);
+    adapter_info.shader_model = ((shader_model >> 4) as u32, (shader_model & 0xf) as u32);
+}
+
+/// fills out hdr support and luminance of the brightest output connected to `adapter`
+fn get_output_hdr_info(adapter: &IDXGIAdapter1, adapter_info: &mut super::AdapterInfo) {
+    unsafe {
+        for i in 0.. {
+            let output = adapter.EnumOutputs(i);
+            if output.is_err() {
+                break;
+            }
+            if let Ok(desc) = output.unwrap().cast::<IDXGIOutput6>().and_then(|output6| output6.GetDesc1()) {
+                if desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020 {
+                    adapter_info.hdr_supported = true;
+                }
+                if desc.MaxLuminance > adapter_info.max_luminance {
+                    adapter_info.max_luminance = desc.MaxLuminance;
+                    adapter_info.min_luminance = desc.MinLuminance;
+                    adapter_info.max_full_frame_luminance = desc.MaxFullFrameLuminance;
+                }
+            }
+        }
+    }
+}
+
 pub fn get_hardware_adapter(
     factory: &IDXGIFactory4,
     adapter_name: &Option<String>,
@@ -621,6 +1568,18 @@ pub fn get_hardware_adapter(
             dedicated_system_memory: 0,
             shared_system_memory: 0,
             available: vec![],
+            hdr_supported: false,
+            max_luminance: 0.0,
+            min_luminance: 0.0,
+            max_full_frame_luminance: 0.0,
+            resource_binding_tier: 0,
+            shader_model: (0, 0),
+            mesh_shader_tier: 0,
+            raytracing_tier: 0,
+            variable_shading_rate_tier: 0,
+            shading_rate_image_tile_size: 0,
+            wave_lane_count_min: 0,
+            wave_lane_count_max: 0,
         };
 
         // enumerate info
@@ -678,18 +1637,64 @@ pub fn get_hardware_adapter(
             adapter_info.dedicated_video_memory = desc.DedicatedVideoMemory;
             adapter_info.dedicated_system_memory = desc.DedicatedSystemMemory;
             adapter_info.shared_system_memory = desc.SharedSystemMemory;
+            get_output_hdr_info(&adapter, &mut adapter_info);
             return Ok((adapter, adapter_info));
         }
     }
     unreachable!()
 }
 
-fn create_read_back_buffer(device: &Device, size: u64) -> Option<ID3D12Resource> {
+/// Increments the frames waited for each resource pending destruction and removes the ones which have waited longer
+/// than the swap chain length, which are no longer in use on the gpu
+fn take_complete_cleanups<T>(cleanups: &mut Vec<(u32, T)>, num_bb: u32) -> Vec<T> {
+    for cleanup in cleanups.iter_mut() {
+        cleanup.0 += 1;
+    }
+    let (complete, pending) = cleanups.drain(..).partition(|(frames, _)| *frames > num_bb);
+    *cleanups = pending;
+    complete.into_iter().map(|(_, resource)| resource).collect()
+}
+
+/// Placed footprints, row counts and row sizes for a range of subresources and the total size in bytes required to copy them
+struct CopyableFootprints {
+    layouts: Vec<D3D12_PLACED_SUBRESOURCE_FOOTPRINT>,
+    num_rows: Vec<u32>,
+    row_sizes: Vec<u64>,
+    total_size: u64
+}
+
+fn get_copyable_footprints(
+    device: &ID3D12Device,
+    desc: &D3D12_RESOURCE_DESC,
+    first_subresource: u32,
+    num_subresources: u32
+) -> CopyableFootprints {
+    let mut footprints = CopyableFootprints {
+        layouts: vec![D3D12_PLACED_SUBRESOURCE_FOOTPRINT::default(); num_subresources as usize],
+        num_rows: vec![0; num_subresources as usize],
+        row_sizes: vec![0; num_subresources as usize],
+        total_size: 0
+    };
+    unsafe {
+        device.GetCopyableFootprints(
+            desc,
+            first_subresource,
+            num_subresources,
+            0,
+            footprints.layouts.as_mut_ptr(),
+            footprints.num_rows.as_mut_ptr(),
+            footprints.row_sizes.as_mut_ptr(),
+            &mut footprints.total_size,
+        );
+    }
+    footprints
+}
+
+fn create_read_back_buffer(device: &ID3D12Device, size: u64) -> result::Result<ID3D12Resource, super::Error> {
     let mut readback_buffer: Option<ID3D12Resource> = None;
     unsafe {
         // readback buffer
         device
-            .device
             .CreateCommittedResource(
                 &D3D12_HEAP_PROPERTIES {
                     Type: D3D12_HEAP_TYPE_READBACK,
@@ -713,32 +1718,88 @@ fn create_read_back_buffer(device: &Device, size: u64) -> Option<ID3D12Resource>
                 D3D12_RESOURCE_STATE_COPY_DEST,
                 std::ptr::null(),
                 &mut readback_buffer,
+            )?;
+    }
+    readback_buffer.ok_or(super::Error {
+        msg: "hotline_rs::gfx::d3d12: failed to create readback buffer".to_string()
+    })
+}
+
+fn create_command_signature(
+    device: &ID3D12Device,
+    arg_type: D3D12_INDIRECT_ARGUMENT_TYPE,
+    stride: usize
+) -> ID3D12CommandSignature {
+    let arg = D3D12_INDIRECT_ARGUMENT_DESC {
+        Type: arg_type,
+        ..Default::default()
+    };
+    let mut signature: Option<ID3D12CommandSignature> = None;
+    unsafe {
+        device
+            .CreateCommandSignature(
+                &D3D12_COMMAND_SIGNATURE_DESC {
+                    ByteStride: stride as u32,
+                    NumArgumentDescs: 1,
+                    pArgumentDescs: &arg,
+                    NodeMask: 0,
+                },
+                None,
+                &mut signature,
             )
-            .expect("hotline_rs::gfx::d3d12: failed to create readback buffer");
+            .expect("hotline_rs::gfx::d3d12: failed to create command signature");
+    }
+    signature.unwrap()
+}
+
+/// creates a descriptor heap of `heap_type`, shader visible heaps also create a non shader visible shadow heap of the
+/// same size, returns tuple (heap, shadow)
+fn create_descriptor_heaps(
+    device: &ID3D12Device,
+    heap_type: super::HeapType,
+    num_descriptors: usize
+) -> result::Result<(ID3D12DescriptorHeap, Option<ID3D12DescriptorHeap>), super::Error> {
+    unsafe {
+        let flags = to_d3d12_descriptor_heap_flags(heap_type);
+        let mut desc = D3D12_DESCRIPTOR_HEAP_DESC {
+            Type: to_d3d12_descriptor_heap_type(heap_type),
+            NumDescriptors: std::cmp::max(num_descriptors, 1) as u32,
+            Flags: flags,
+            ..Default::default()
+        };
+        let heap: ID3D12DescriptorHeap = device.CreateDescriptorHeap(&desc)?;
+        let shadow = if flags == D3D12_DESCRIPTOR_HEAP_FLAG_SHADER_VISIBLE {
+            desc.Flags = D3D12_DESCRIPTOR_HEAP_FLAG_NONE;
+            Some(device.CreateDescriptorHeap(&desc)?)
+        }
+        else {
+            None
+        };
+        Ok((heap, shadow))
     }
-    readback_buffer
 }
 
 fn create_heap(device: &ID3D12Device, info: &HeapInfo) -> Heap {
     unsafe {
         let d3d12_type = to_d3d12_descriptor_heap_type(info.heap_type);
-        let heap: ID3D12DescriptorHeap = device
-            .CreateDescriptorHeap(&D3D12_DESCRIPTOR_HEAP_DESC {
-                Type: d3d12_type,
-                NumDescriptors: std::cmp::max(info.num_descriptors, 1) as u32,
-                Flags: to_d3d12_descriptor_heap_flags(info.heap_type),
-                ..Default::default()
-            })
+        let (heap, shadow) = create_descriptor_heaps(device, info.heap_type, info.num_descriptors)
             .expect("hotline_rs::gfx::d3d12: failed to create heap");
-        let base_address = heap.GetCPUDescriptorHandleForHeapStart().ptr;
+        // handles are allocated from the shadow heap if we have one, they are copied into `heap` on commit
+        let base_address = shadow.as_ref().unwrap_or(&heap).GetCPUDescriptorHandleForHeapStart().ptr;
         let incr = device.GetDescriptorHandleIncrementSize(d3d12_type) as usize;
         Heap {
+            device: device.clone(),
             heap,
+            heap_type: info.heap_type,
             base_address,
             increment_size: device.GetDescriptorHandleIncrementSize(d3d12_type) as usize,
             capacity: info.num_descriptors * incr,
             offset: 0,
             free_list: Vec::new(),
+            shadow,
+            retired_heaps: Vec::new(),
+            chained_heaps: Vec::new(),
+            allocated: Vec::new(),
         }
     }
 }
@@ -747,28 +1808,80 @@ fn create_swap_chain_rtv(
     swap_chain: &IDXGISwapChain3,
     device: &mut Device,
     num_bb: u32,
-) -> Vec<Texture> {
+    usage: super::TextureUsage,
+) -> result::Result<Vec<Texture>, super::Error> {
     unsafe {
         // render targets for the swap chain
         let mut textures: Vec<Texture> = Vec::new();
         for i in 0..num_bb {
-            let render_target: ID3D12Resource = swap_chain.GetBuffer(i).unwrap();
-            let h = device.rtv_heap.allocate();
+            let render_target: ID3D12Resource = swap_chain.GetBuffer(i)?;
+            let h = device.rtv_heap.allocate()?;
             device.device.CreateRenderTargetView(&render_target, std::ptr::null_mut(), h);
+
+            // optional srv so the backbuffer can be sampled
+            let mut srv_index = None;
+            if usage.contains(super::TextureUsage::SHADER_RESOURCE) {
+                let srv = device.shader_heap.allocate()?;
+                device.device.CreateShaderResourceView(&render_target, std::ptr::null(), srv);
+                device.shader_heap.commit(srv);
+                srv_index = Some(device.shader_heap.get_handle_index(&srv));
+            }
+
             textures.push(Texture {
                 resource: render_target.clone(),
+                format: render_target.GetDesc().Format,
                 resolved_resource: None,
                 resolved_format: DXGI_FORMAT_UNKNOWN,
                 rtv: Some(h),
                 dsv: None,
-                srv_index: None,
+                srv_index,
                 resolved_srv_index: None,
                 uav_index: None,
-                shared_handle: None
+                uav_cpu: None,
+                shared_handle: None,
+                state: Arc::new(Mutex::new(super::ResourceState::Present)),
             });
         }
-        textures
+        Ok(textures)
+    }
+}
+
+/// returns an error if a tracked resource state does not match the state it is about to be used in
+#[cfg(debug_assertions)]
+fn validate_resource_state(
+    resource_type: &str,
+    tracked: super::ResourceState,
+    expected: super::ResourceState
+) -> result::Result<(), super::Error> {
+    if tracked != expected {
+        let hint = match (tracked, expected) {
+            (super::ResourceState::UnorderedAccess, super::ResourceState::ShaderResource) =>
+                ", it was last written in UnorderedAccess, is a transition missing after a compute pass?",
+            _ => ""
+        };
+        return Err(super::Error {
+            msg: format!("hotline_rs::gfx::d3d12: {} is in state {:?} but is being used as {:?}{}",
+                resource_type, tracked, expected, hint)
+        });
+    }
+    Ok(())
+}
+
+/// blocks the calling thread until `fence` reaches `value`
+fn wait_for_fence(fence: &ID3D12Fence, value: u64) -> result::Result<(), super::Error> {
+    unsafe {
+        if fence.GetCompletedValue() < value {
+            let event = CreateEventA(std::ptr::null_mut(), false, false, None)?;
+            fence.SetEventOnCompletion(value, event)?;
+            WaitForSingleObject(event, INFINITE);
+            CloseHandle(event);
+        }
     }
+    Ok(())
+}
+
+fn to_wide_null_terminated(string: &str) -> Vec<u16> {
+    string.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
 fn null_terminate_semantics(layout: &super::InputLayout) -> Vec<CString> {
@@ -779,6 +1892,59 @@ fn null_terminate_semantics(layout: &super::InputLayout) -> Vec<CString> {
     c_strs
 }
 
+/// validates index buffers use a 16 or 32 bit format with a matching stride
+fn validate_index_format(info: &super::BufferInfo) -> result::Result<(), super::Error> {
+    if matches!(info.usage, super::BufferUsage::Index) {
+        let expected_stride = match info.format {
+            super::Format::R16u => 2,
+            super::Format::R32u => 4,
+            _ => {
+                return Err(super::Error {
+                    msg: "hotline_rs::gfx::d3d12: index buffers must use Format::R16u or Format::R32u".to_string()
+                });
+            }
+        };
+        if info.stride != expected_stride {
+            return Err(super::Error {
+                msg: format!(
+                    "hotline_rs::gfx::d3d12: index buffer stride ({}) does not match format size ({})",
+                    info.stride, expected_stride
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks `mip` and `slice` are in range of a texture resource to create a single subresource view, for 3D textures
+/// `slice` is a depth slice of `mip`
+fn validate_view_subresource(
+    desc: &D3D12_RESOURCE_DESC,
+    mip: u32,
+    slice: u32
+) -> result::Result<(), super::Error> {
+    let num_slices = if desc.Dimension == D3D12_RESOURCE_DIMENSION_TEXTURE3D {
+        (desc.DepthOrArraySize as u32 >> mip).max(1)
+    }
+    else {
+        desc.DepthOrArraySize as u32
+    };
+    if mip >= desc.MipLevels as u32 || slice >= num_slices {
+        return Err(super::Error {
+            msg: format!(
+                "hotline_rs::gfx::d3d12: view subresource mip: ({}) slice: ({}) is out of range, texture has ({}) mips and ({}) slices",
+                mip, slice, desc.MipLevels, num_slices
+            ),
+        });
+    }
+    if desc.SampleDesc.Count > 1 && mip > 0 {
+        return Err(super::Error {
+            msg: "hotline_rs::gfx::d3d12: msaa textures only have a single mip".to_string()
+        });
+    }
+    Ok(())
+}
+
 /// validates the length of data is consistent with a known size_bytes of a buffer or texture
 fn validate_data_size<T: Sized>(
     size_bytes: usize,
@@ -809,43 +1975,361 @@ impl super::RenderPass<Device> for RenderPass {
 }
 
 impl Heap {
-    fn allocate(&mut self) -> D3D12_CPU_DESCRIPTOR_HANDLE {
-        unsafe {
-            if self.free_list.is_empty() {
-                // allocates a new handle
-                if self.offset >= self.capacity {
-                    panic!("hotline_rs::gfx::d3d12: heap is full!");
+    fn allocate(&mut self) -> result::Result<D3D12_CPU_DESCRIPTOR_HANDLE, super::Error> {
+        let handle = self.allocate_handle()?;
+        let index = self.get_handle_index(&handle);
+        let (word, bit) = (index / 64, 1u64 << (index % 64));
+        if word >= self.allocated.len() {
+            self.allocated.resize(word + 1, 0);
+        }
+        self.allocated[word] |= bit;
+        Ok(handle)
+    }
+
+    fn is_allocated(&self, index: usize) -> bool {
+        self.allocated.get(index / 64).map(|word| word & (1u64 << (index % 64)) != 0).unwrap_or(false)
+    }
+
+    fn allocate_handle(&mut self) -> result::Result<D3D12_CPU_DESCRIPTOR_HANDLE, super::Error> {
+        // pulls new handle from the free list
+        if let Some(ptr) = self.free_list.pop() {
+            return Ok(D3D12_CPU_DESCRIPTOR_HANDLE { ptr });
+        }
+
+        // double the size when full, shader and sampler heaps are limited to the shader visible maximum
+        let num_descriptors = self.get_num_descriptors();
+        if self.offset / self.increment_size >= num_descriptors {
+            if self.holds_cpu_handles() {
+                self.chain(num_descriptors.max(1))?;
+            }
+            else {
+                let max_descriptors = max_descriptors_for_heap_type(self.heap_type);
+                if num_descriptors >= max_descriptors {
+                    return Err(super::Error {
+                        msg: format!(
+                            "hotline_rs::gfx::d3d12: heap is full! capacity: ({}) descriptors",
+                            num_descriptors
+                        ),
+                    });
                 }
-                let ptr = self.heap.GetCPUDescriptorHandleForHeapStart().ptr + self.offset;
-                self.offset += self.increment_size;
-                return D3D12_CPU_DESCRIPTOR_HANDLE { ptr };
+                <Self as super::Heap<Device>>::grow(self, (num_descriptors * 2).clamp(1, max_descriptors))?;
             }
-            // pulls new handle from the free list
-            D3D12_CPU_DESCRIPTOR_HANDLE {
-                ptr: self.free_list.pop().unwrap(),
+        }
+
+        // allocates a new handle
+        let handle = self.get_handle_for_index(self.offset / self.increment_size);
+        self.offset += self.increment_size;
+        Ok(handle)
+    }
+
+    /// copies a descriptor written to `handle` in the shadow heap into the shader visible heap, must be called after
+    /// writing a descriptor into a handle from `allocate` before it is used on the gpu
+    fn commit(&self, handle: D3D12_CPU_DESCRIPTOR_HANDLE) {
+        if self.shadow.is_some() {
+            let index = self.get_handle_index(&handle);
+            unsafe {
+                let dst = D3D12_CPU_DESCRIPTOR_HANDLE {
+                    ptr: self.heap.GetCPUDescriptorHandleForHeapStart().ptr + index * self.increment_size
+                };
+                self.device.CopyDescriptorsSimple(1, dst, handle, to_d3d12_descriptor_heap_type(self.heap_type));
             }
         }
     }
 
+    /// releases heaps retired by `grow` which have waited longer than `num_bb` frames and are no longer in use
+    fn release_retired_heaps(&mut self, num_bb: u32) {
+        take_complete_cleanups(&mut self.retired_heaps, num_bb);
+    }
+
+    /// returns true for heaps which have their cpu handles held by resources, these chain rather than grow
+    fn holds_cpu_handles(&self) -> bool {
+        self.heap_type == super::HeapType::RenderTarget ||
+        self.heap_type == super::HeapType::DepthStencil ||
+        self.heap_type == super::HeapType::ShaderCpu
+    }
+
+    /// total number of descriptors in the heap and any chained heaps
+    fn get_num_descriptors(&self) -> usize {
+        self.capacity / self.increment_size + self.chained_heaps.iter().map(|chain| chain.num_descriptors).sum::<usize>()
+    }
+
+    /// chain a new heap with `num_descriptors` onto the end of the heap
+    fn chain(&mut self, num_descriptors: usize) -> result::Result<(), super::Error> {
+        unsafe {
+            let heap: ID3D12DescriptorHeap = self.device.CreateDescriptorHeap(&D3D12_DESCRIPTOR_HEAP_DESC {
+                Type: to_d3d12_descriptor_heap_type(self.heap_type),
+                NumDescriptors: num_descriptors as u32,
+                Flags: to_d3d12_descriptor_heap_flags(self.heap_type),
+                ..Default::default()
+            })?;
+            self.chained_heaps.push(ChainedHeap {
+                base_address: heap.GetCPUDescriptorHandleForHeapStart().ptr,
+                heap,
+                first_index: self.get_num_descriptors(),
+                num_descriptors
+            });
+        }
+        Ok(())
+    }
+
     fn get_handle_index(&self, handle: &D3D12_CPU_DESCRIPTOR_HANDLE) -> usize {
-        let ptr = handle.ptr - self.base_address;
+        for chain in &self.chained_heaps {
+            let end = chain.base_address + chain.num_descriptors * self.increment_size;
+            if handle.ptr >= chain.base_address && handle.ptr < end {
+                return chain.first_index + (handle.ptr - chain.base_address) / self.increment_size;
+            }
+        }
+        let ptr = handle.ptr.wrapping_sub(self.base_address);
         ptr / self.increment_size
     }
 
+    fn get_handle_for_index(&self, index: usize) -> D3D12_CPU_DESCRIPTOR_HANDLE {
+        for chain in &self.chained_heaps {
+            if index >= chain.first_index && index < chain.first_index + chain.num_descriptors {
+                return D3D12_CPU_DESCRIPTOR_HANDLE {
+                    ptr: chain.base_address + self.increment_size * (index - chain.first_index)
+                };
+            }
+        }
+        D3D12_CPU_DESCRIPTOR_HANDLE {
+            ptr: self.base_address + self.increment_size * index
+        }
+    }
+
     fn deallocate_internal(&mut self, handle: &D3D12_CPU_DESCRIPTOR_HANDLE) {
+        let index = self.get_handle_index(handle);
+        let in_range = index < self.get_num_descriptors();
+        debug_assert!(in_range, "hotline_rs::gfx::d3d12: freeing out of range heap index: ({})", index);
+        let allocated = self.is_allocated(index);
+        debug_assert!(!in_range || allocated, "hotline_rs::gfx::d3d12: double free of heap index: ({})", index);
+        if !allocated {
+            return;
+        }
+        self.allocated[index / 64] &= !(1u64 << (index % 64));
         self.free_list.push(handle.ptr);
     }
 }
 
 impl super::Heap<Device> for Heap {
     fn deallocate(&mut self, index: usize) {
-        let ptr = self.base_address + self.increment_size * index;
-        let handle = D3D12_CPU_DESCRIPTOR_HANDLE { ptr };
+        let handle = self.get_handle_for_index(index);
         self.deallocate_internal(&handle);
     }
+
+    fn grow(&mut self, num_descriptors: usize) -> result::Result<(), super::Error> {
+        // cpu handles to rtv, dsv and cpu uavs are held directly by textures and render passes, so they cannot move
+        if self.holds_cpu_handles() {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: only shader and sampler heaps can grow".to_string(),
+            });
+        }
+
+        let max_descriptors = max_descriptors_for_heap_type(self.heap_type);
+        if num_descriptors > max_descriptors {
+            return Err(super::Error {
+                msg: format!(
+                    "hotline_rs::gfx::d3d12: heap size: ({}) exceeds the maximum: ({}) descriptors",
+                    num_descriptors, max_descriptors
+                ),
+            });
+        }
+
+        // nothing to do if we already have the space
+        if num_descriptors * self.increment_size <= self.capacity {
+            return Ok(());
+        }
+
+        unsafe {
+            let d3d12_type = to_d3d12_descriptor_heap_type(self.heap_type);
+            let (heap, shadow) = create_descriptor_heaps(&self.device, self.heap_type, num_descriptors)?;
+            let shadow = shadow.ok_or_else(|| super::Error {
+                msg: "hotline_rs::gfx::d3d12: growing heap has no shadow heap to copy from".to_string(),
+            })?;
+
+            // copy the allocated descriptors from the old shadow heap into the new heaps, so indices remain the same.
+            // only non shader visible heaps can be copied from
+            let num_allocated = self.offset / self.increment_size;
+            if num_allocated > 0 {
+                let src = D3D12_CPU_DESCRIPTOR_HANDLE { ptr: self.base_address };
+                self.device.CopyDescriptorsSimple(
+                    num_allocated as u32, shadow.GetCPUDescriptorHandleForHeapStart(), src, d3d12_type);
+                self.device.CopyDescriptorsSimple(
+                    num_allocated as u32, heap.GetCPUDescriptorHandleForHeapStart(), src, d3d12_type);
+            }
+
+            // rebase the free list handles into the new shadow heap
+            let base_address = shadow.GetCPUDescriptorHandleForHeapStart().ptr;
+            for ptr in &mut self.free_list {
+                *ptr = *ptr - self.base_address + base_address;
+            }
+
+            // the old heap may still be bound on in-flight command lists so we keep it alive until
+            // `clean_up_resources` has waited for the swap chain, the old shadow heap is only used on the cpu
+            let old_heap = std::mem::replace(&mut self.heap, heap);
+            self.retired_heaps.push((0, old_heap));
+            self.shadow = Some(shadow);
+            self.base_address = base_address;
+            self.capacity = num_descriptors * self.increment_size;
+        }
+        Ok(())
+    }
 }
 
 impl Device {
+    /// copies the non shader visible uav `cpu_handle` into a newly allocated descriptor in the shader heap, returning
+    /// its index
+    fn copy_cpu_uav_to_shader_heap(&mut self, cpu_handle: D3D12_CPU_DESCRIPTOR_HANDLE) -> result::Result<usize, super::Error> {
+        let h = self.shader_heap.allocate()?;
+        unsafe {
+            self.device.CopyDescriptorsSimple(1, h, cpu_handle, D3D12_DESCRIPTOR_HEAP_TYPE_CBV_SRV_UAV);
+        }
+        self.shader_heap.commit(h);
+        Ok(self.shader_heap.get_handle_index(&h))
+    }
+
+    /// returns the command queue to submit work for `queue` to
+    fn get_queue(&self, queue: super::QueueType) -> &ID3D12CommandQueue {
+        match queue {
+            super::QueueType::Graphics => &self.command_queue,
+            super::QueueType::Compute => &self.compute_queue,
+        }
+    }
+
+    /// creates a render pipeline, optionally loading it from or storing it into a pipeline library by name
+    fn create_render_pipeline_with_library(
+        &self,
+        info: &super::RenderPipelineInfo<Device>,
+        library: Option<(&PipelineLibrary, &str)>
+    ) -> result::Result<RenderPipeline, super::Error> {
+        let (root_signature, bindless_slot) = self.create_root_signature(&info.descriptor_layout)?;
+
+        let semantics = null_terminate_semantics(&info.input_layout);
+        let mut elems = Device::create_d3d12_input_element_desc(&info.input_layout, &semantics);
+        let input_layout = D3D12_INPUT_LAYOUT_DESC {
+            pInputElementDescs: elems.as_mut_ptr(),
+            NumElements: elems.len() as u32,
+        };
+
+        let msaa_format = info.pass.sample_count > 1;
+        let blend_state = to_d3d12_blend_desc(&info.blend_info, info.pass.rt_formats.len())?;
+
+        let null_bytecode = D3D12_SHADER_BYTECODE {
+            pShaderBytecode: std::ptr::null_mut(),
+            BytecodeLength: 0,
+        };
+
+        let mut desc = D3D12_GRAPHICS_PIPELINE_STATE_DESC {
+            InputLayout: input_layout,
+            pRootSignature: Some(root_signature.clone()),
+            VS: if let Some(vs) = &info.vs {
+                D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: vs.get_buffer_pointer(),
+                    BytecodeLength: vs.get_buffer_size(),
+                }
+            } else {
+                null_bytecode
+            },
+            PS: if let Some(ps) = &info.fs {
+                D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: ps.get_buffer_pointer(),
+                    BytecodeLength: ps.get_buffer_size(),
+                }
+            } else {
+                null_bytecode
+            },
+            HS: if let Some(hs) = &info.hs {
+                D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: hs.get_buffer_pointer(),
+                    BytecodeLength: hs.get_buffer_size(),
+                }
+            } else {
+                null_bytecode
+            },
+            DS: if let Some(ds) = &info.ds {
+                D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: ds.get_buffer_pointer(),
+                    BytecodeLength: ds.get_buffer_size(),
+                }
+            } else {
+                null_bytecode
+            },
+            GS: if let Some(gs) = &info.gs {
+                D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: gs.get_buffer_pointer(),
+                    BytecodeLength: gs.get_buffer_size(),
+                }
+            } else {
+                null_bytecode
+            },
+            RasterizerState: to_d3d12_rasterizer_desc(&info.raster_info, msaa_format),
+            BlendState: blend_state,
+            DepthStencilState: to_d3d12_depth_stencil_desc(&info.depth_stencil_info),
+            SampleMask: info.sample_mask,
+            PrimitiveTopologyType: to_d3d12_primitive_topology_type(info.topology),
+            NumRenderTargets: info.pass.rt_formats.len() as u32,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: info.pass.sample_count,
+                Quality: 0,
+            },
+            ..Default::default()
+        };
+
+        // Set formats from pass
+        for i in 0..info.pass.rt_formats.len() {
+            desc.RTVFormats[i] = info.pass.rt_formats[i];
+        }
+        desc.DSVFormat = info.pass.ds_format;
+
+        let pso = unsafe {
+            if let Some((library, name)) = library {
+                library.load_graphics_pipeline(&self.device, name, &desc)?
+            }
+            else {
+                self.device.CreateGraphicsPipelineState(&desc)?
+            }
+        };
+
+        Ok(RenderPipeline {
+            pso,
+            root_signature,
+            topology: to_d3d12_primitive_topology(info.topology, info.patch_index),
+            bindless_slot,
+        })
+    }
+
+    /// creates a compute pipeline, optionally loading it from or storing it into a pipeline library by name
+    fn create_compute_pipeline_with_library(
+        &self,
+        info: &super::ComputePipelineInfo<Device>,
+        library: Option<(&PipelineLibrary, &str)>
+    ) -> result::Result<ComputePipeline, super::Error> {
+        let cs = &info.cs;
+        let (root_signature, bindless_slot) = self.create_root_signature(&info.descriptor_layout)?;
+
+        let desc = D3D12_COMPUTE_PIPELINE_STATE_DESC {
+            CS: D3D12_SHADER_BYTECODE {
+                pShaderBytecode: cs.get_buffer_pointer(),
+                BytecodeLength: cs.get_buffer_size(),
+            },
+            pRootSignature: Some(root_signature.clone()),
+            ..Default::default()
+        };
+
+        unsafe {
+            let pso = if let Some((library, name)) = library {
+                library.load_compute_pipeline(&self.device, name, &desc)?
+            }
+            else {
+                self.device.CreateComputePipelineState(&desc)?
+            };
+            Ok(ComputePipeline {
+                pso,
+                root_signature,
+                bindless_slot,
+            })
+        }
+    }
+
     fn create_d3d12_input_element_desc(
         layout: &super::InputLayout,
         null_terminated_semantics: &[CString],
@@ -870,10 +2354,12 @@ impl Device {
         d3d12_elems
     }
 
+    /// creates a root signature for `layout` and returns it along with the root slot of the first descriptor table
+    /// which contains an unbounded range, this is the slot `CmdBuf::set_bindless_heap` binds to
     fn create_root_signature(
         &self,
         layout: &super::DescriptorLayout,
-    ) -> result::Result<ID3D12RootSignature, super::Error> {
+    ) -> result::Result<(ID3D12RootSignature, Option<u32>), super::Error> {
         let mut root_params: Vec<D3D12_ROOT_PARAMETER> = Vec::new();
 
         // push constants
@@ -893,11 +2379,36 @@ impl Device {
             }
         }
 
-        // bindings for (SRV, UAV, CBV an Samplers)
+        // bindings for (SRV, UAV, CBV an Samplers), samplers cannot share a descriptor table with other ranges so
+        // they are placed in their own tables after the shader resource tables
         let mut visibility_map: HashMap<super::ShaderVisibility, Vec<D3D12_DESCRIPTOR_RANGE>> =
             HashMap::new();
+        let mut sampler_visibility_map: HashMap<super::ShaderVisibility, Vec<D3D12_DESCRIPTOR_RANGE>> =
+            HashMap::new();
+        let mut bindless_slot = None;
         if let Some(bindings) = &layout.bindings {
             for binding in bindings {
+                // root descriptors are inline, they go in the slots before any tables
+                let root_type = match binding.binding_type {
+                    super::DescriptorType::RootConstantBuffer => Some(D3D12_ROOT_PARAMETER_TYPE_CBV),
+                    super::DescriptorType::RootShaderResource => Some(D3D12_ROOT_PARAMETER_TYPE_SRV),
+                    super::DescriptorType::RootUnorderedAccess => Some(D3D12_ROOT_PARAMETER_TYPE_UAV),
+                    _ => None
+                };
+                if let Some(root_type) = root_type {
+                    root_params.push(D3D12_ROOT_PARAMETER {
+                        ParameterType: root_type,
+                        Anonymous: D3D12_ROOT_PARAMETER_0 {
+                            Descriptor: D3D12_ROOT_DESCRIPTOR {
+                                ShaderRegister: binding.shader_register,
+                                RegisterSpace: binding.register_space,
+                            },
+                        },
+                        ShaderVisibility: to_d3d12_shader_visibility(&binding.visibility),
+                    });
+                    continue;
+                }
+
                 let count = if binding.num_descriptors.is_some() {
                     binding.num_descriptors.unwrap()
                 } else {
@@ -909,6 +2420,9 @@ impl Device {
                         super::DescriptorType::UnorderedAccess => D3D12_DESCRIPTOR_RANGE_TYPE_UAV,
                         super::DescriptorType::ConstantBuffer => D3D12_DESCRIPTOR_RANGE_TYPE_CBV,
                         super::DescriptorType::Sampler => D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER,
+                        super::DescriptorType::RootConstantBuffer |
+                        super::DescriptorType::RootShaderResource |
+                        super::DescriptorType::RootUnorderedAccess => unreachable!(),
                     },
                     NumDescriptors: count,
                     BaseShaderRegister: binding.shader_register,
@@ -916,6 +2430,12 @@ impl Device {
                     OffsetInDescriptorsFromTableStart: 0,
                 };
 
+                let visibility_map = if matches!(binding.binding_type, super::DescriptorType::Sampler) {
+                    &mut sampler_visibility_map
+                }
+                else {
+                    &mut visibility_map
+                };
                 let map = visibility_map.get_mut(&binding.visibility);
                 if let Some(map) = map {
                     map.push(range);
@@ -924,7 +2444,11 @@ impl Device {
                 }
             }
 
-            for (visibility, ranges) in visibility_map.iter() {
+            for (visibility, ranges) in visibility_map.iter().chain(sampler_visibility_map.iter()) {
+                let unbounded = ranges.iter().any(|range| range.NumDescriptors == u32::MAX);
+                if unbounded && bindless_slot.is_none() && ranges[0].RangeType != D3D12_DESCRIPTOR_RANGE_TYPE_SAMPLER {
+                    bindless_slot = Some(root_params.len() as u32);
+                }
                 root_params.push(D3D12_ROOT_PARAMETER {
                     ParameterType: D3D12_ROOT_PARAMETER_TYPE_DESCRIPTOR_TABLE,
                     Anonymous: D3D12_ROOT_PARAMETER_0 {
@@ -991,23 +2515,241 @@ impl Device {
             let sig = signature.unwrap();
             let slice : &[u8] = std::slice::from_raw_parts(sig.GetBufferPointer() as *mut u8, sig.GetBufferSize());
             let sig = self.device.CreateRootSignature(0, slice)?;
-            Ok(sig)
+            Ok((sig, bindless_slot))
         }
     }
 
-    fn create_render_passes_for_swap_chain(
+    fn validate_raytracing_supported(&self) -> result::Result<(), super::Error> {
+        if !self.adapter_info.raytracing_supported() {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: ray tracing is not supported on this device".to_string()
+            });
+        }
+        Ok(())
+    }
+
+    /// creates a gpu only buffer with unordered access for acceleration structure data or build scratch memory
+    fn create_raytracing_buffer(
         &self,
-        num_buffers: u32,
-        textures: &[Texture],
-        clear_col: Option<ClearColour>,
-    ) -> Vec<RenderPass> {
-        let mut passes = Vec::new();
-        for texture in textures.iter().take(num_buffers as usize) {
-            passes.push(
-                self.create_render_pass(&super::RenderPassInfo {
-                    render_targets: vec![texture],
-                    rt_clear: clear_col,
+        size: u64,
+        state: D3D12_RESOURCE_STATES
+    ) -> result::Result<ID3D12Resource, super::Error> {
+        let mut resource: Option<ID3D12Resource> = None;
+        unsafe {
+            self.device.CreateCommittedResource(
+                &D3D12_HEAP_PROPERTIES {
+                    Type: D3D12_HEAP_TYPE_DEFAULT,
+                    ..Default::default()
+                },
+                D3D12_HEAP_FLAG_NONE,
+                &D3D12_RESOURCE_DESC {
+                    Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
+                    Width: super::align_pow2(size.max(1), D3D12_RAYTRACING_ACCELERATION_STRUCTURE_BYTE_ALIGNMENT as u64),
+                    Height: 1,
+                    DepthOrArraySize: 1,
+                    MipLevels: 1,
+                    SampleDesc: DXGI_SAMPLE_DESC {
+                        Count: 1,
+                        Quality: 0,
+                    },
+                    Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+                    Flags: D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS,
+                    ..Default::default()
+                },
+                state,
+                std::ptr::null(),
+                &mut resource,
+            )?;
+        }
+        Ok(resource.unwrap())
+    }
+
+    /// creates a persistent upload buffer containing `data`, for data the gpu reads directly such as ray tracing
+    /// instances and shader records
+    fn create_upload_buffer_with_data(&self, data: &[u8]) -> result::Result<ID3D12Resource, super::Error> {
+        let mut resource: Option<ID3D12Resource> = None;
+        unsafe {
+            self.device.CreateCommittedResource(
+                &D3D12_HEAP_PROPERTIES {
+                    Type: D3D12_HEAP_TYPE_UPLOAD,
+                    ..Default::default()
+                },
+                D3D12_HEAP_FLAG_NONE,
+                &D3D12_RESOURCE_DESC {
+                    Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
+                    Width: data.len().max(1) as u64,
+                    Height: 1,
+                    DepthOrArraySize: 1,
+                    MipLevels: 1,
+                    SampleDesc: DXGI_SAMPLE_DESC {
+                        Count: 1,
+                        Quality: 0,
+                    },
+                    Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+                    Flags: D3D12_RESOURCE_FLAG_NONE,
+                    ..Default::default()
+                },
+                D3D12_RESOURCE_STATE_GENERIC_READ,
+                std::ptr::null(),
+                &mut resource,
+            )?;
+            let resource = resource.unwrap();
+            let mut map_data = std::ptr::null_mut();
+            resource.Map(0, &D3D12_RANGE { Begin: 0, End: 0 }, &mut map_data)?;
+            std::ptr::copy_nonoverlapping(data.as_ptr(), map_data as *mut u8, data.len());
+            resource.Unmap(0, std::ptr::null());
+            Ok(resource)
+        }
+    }
+
+    /// creates the result and scratch buffers sized to build an acceleration structure from `inputs`
+    fn create_acceleration_structure_buffers(
+        &self,
+        inputs: &D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS
+    ) -> result::Result<(ID3D12Resource, ID3D12Resource), super::Error> {
+        let mut prebuild = D3D12_RAYTRACING_ACCELERATION_STRUCTURE_PREBUILD_INFO::default();
+        unsafe {
+            let device: ID3D12Device5 = self.device.cast()?;
+            device.GetRaytracingAccelerationStructurePrebuildInfo(inputs, &mut prebuild);
+        }
+        let resource = self.create_raytracing_buffer(
+            prebuild.ResultDataMaxSizeInBytes, D3D12_RESOURCE_STATE_RAYTRACING_ACCELERATION_STRUCTURE)?;
+        let scratch = self.create_raytracing_buffer(
+            prebuild.ScratchDataSizeInBytes, D3D12_RESOURCE_STATE_UNORDERED_ACCESS)?;
+        Ok((resource, scratch))
+    }
+
+    /// creates a dsv of a single subresource of `texture` with `flags` to make depth and or stencil read only
+    fn create_depth_stencil_view_with_flags(
+        &mut self,
+        texture: &Texture,
+        mip: u32,
+        slice: u32,
+        flags: D3D12_DSV_FLAGS
+    ) -> result::Result<usize, super::Error> {
+        let desc = unsafe { texture.resource.GetDesc() };
+        if (desc.Flags.0 & D3D12_RESOURCE_FLAG_ALLOW_DEPTH_STENCIL.0) == 0 {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: depth stencil views require a texture created with TextureUsage::DEPTH_STENCIL".to_string()
+            });
+        }
+        validate_view_subresource(&desc, mip, slice)?;
+        let (view_dimension, view) = match desc.Dimension {
+            D3D12_RESOURCE_DIMENSION_TEXTURE1D => (
+                D3D12_DSV_DIMENSION_TEXTURE1DARRAY,
+                D3D12_DEPTH_STENCIL_VIEW_DESC_0 {
+                    Texture1DArray: D3D12_TEX1D_ARRAY_DSV {
+                        MipSlice: mip,
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                    }
+                }
+            ),
+            _ if desc.SampleDesc.Count > 1 => (
+                D3D12_DSV_DIMENSION_TEXTURE2DMSARRAY,
+                D3D12_DEPTH_STENCIL_VIEW_DESC_0 {
+                    Texture2DMSArray: D3D12_TEX2DMS_ARRAY_DSV {
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                    }
+                }
+            ),
+            _ => (
+                D3D12_DSV_DIMENSION_TEXTURE2DARRAY,
+                D3D12_DEPTH_STENCIL_VIEW_DESC_0 {
+                    Texture2DArray: D3D12_TEX2D_ARRAY_DSV {
+                        MipSlice: mip,
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                    }
+                }
+            )
+        };
+        let h = self.dsv_heap.allocate()?;
+        unsafe {
+            self.device.CreateDepthStencilView(
+                &texture.resource,
+                &D3D12_DEPTH_STENCIL_VIEW_DESC {
+                    Format: texture.format,
+                    ViewDimension: view_dimension,
+                    Flags: flags,
+                    Anonymous: view,
+                },
+                h,
+            );
+        }
+        Ok(self.dsv_heap.get_handle_index(&h))
+    }
+
+    /// returns true if the adapter and display support presenting with tearing for uncapped frame rates
+    fn check_tearing_support(&self) -> bool {
+        let mut allow_tearing = BOOL(0);
+        let supported = self.dxgi_factory.cast::<IDXGIFactory5>().and_then(|factory| unsafe {
+            factory.CheckFeatureSupport(
+                DXGI_FEATURE_PRESENT_ALLOW_TEARING,
+                &mut allow_tearing as *mut _ as *mut core::ffi::c_void,
+                std::mem::size_of::<BOOL>() as u32
+            )
+        });
+        supported.is_ok() && allow_tearing.as_bool()
+    }
+
+    /// compiles the compute pipeline used to downsample mips in `generate_mips`
+    fn create_mip_pipeline(&self) -> result::Result<ComputePipeline, super::Error> {
+        let cs = SuperDevice::create_shader(self, &super::ShaderInfo {
+            shader_type: super::ShaderType::Compute,
+            compile_info: Some(super::ShaderCompileInfo {
+                entry_point: "cs_main".to_string(),
+                target: "cs_5_1".to_string(),
+                flags: super::ShaderCompileFlags::NONE,
+            }),
+        }, MIP_DOWNSAMPLE_SRC.as_bytes())?;
+        self.create_compute_pipeline_with_library(&super::ComputePipelineInfo {
+            cs: &cs,
+            descriptor_layout: super::DescriptorLayout {
+                push_constants: Some(vec![super::PushConstantInfo {
+                    visibility: super::ShaderVisibility::Compute,
+                    shader_register: 0,
+                    register_space: 0,
+                    num_values: 4,
+                }]),
+                bindings: Some(vec![
+                    super::DescriptorBinding {
+                        visibility: super::ShaderVisibility::Compute,
+                        shader_register: 0,
+                        register_space: 0,
+                        binding_type: super::DescriptorType::ShaderResource,
+                        num_descriptors: None,
+                    },
+                    super::DescriptorBinding {
+                        visibility: super::ShaderVisibility::Compute,
+                        shader_register: 0,
+                        register_space: 1,
+                        binding_type: super::DescriptorType::UnorderedAccess,
+                        num_descriptors: None,
+                    },
+                ]),
+                static_samplers: None,
+            },
+        }, None)
+    }
+
+    fn create_render_passes_for_swap_chain(
+        &self,
+        num_buffers: u32,
+        textures: &[Texture],
+        clear_col: Option<ClearColour>,
+    ) -> Vec<RenderPass> {
+        let mut passes = Vec::new();
+        for texture in textures.iter().take(num_buffers as usize) {
+            passes.push(
+                self.create_render_pass(&super::RenderPassInfo {
+                    render_targets: vec![texture],
+                    rt_clear: clear_col,
+                    rt_clears: None,
+                    rt_views: None,
                     depth_stencil: None,
+                    ds_view: None,
                     ds_clear: None,
                     resolve: false,
                     discard: false,
@@ -1061,6 +2803,14 @@ impl super::Device for Device {
     type RenderPass = RenderPass;
     type Heap = Heap;
     type ComputePipeline = ComputePipeline;
+    type RingBuffer = RingBuffer;
+    type PipelineLibrary = PipelineLibrary;
+    type Fence = Fence;
+    type Sampler = Sampler;
+    type RaytracingBLAS = RaytracingBLAS;
+    type RaytracingTLAS = RaytracingTLAS;
+    type RaytracingPipeline = RaytracingPipeline;
+    type RaytracingShaderBindingTable = RaytracingShaderBindingTable;
     fn create(info: &super::DeviceInfo) -> Device {
         unsafe {
             // enable debug layer
@@ -1072,6 +2822,14 @@ impl super::Device for Device {
                     println!("hotline_rs::gfx::d3d12: enabling debug layer");
                 }
                 dxgi_factory_flags = DXGI_CREATE_FACTORY_DEBUG;
+
+                // enable device removed extended data, to get breadcrumbs and page faults after a device removal
+                let mut dred_settings: Option<ID3D12DeviceRemovedExtendedDataSettings> = None;
+                if let Some(dred_settings) = D3D12GetDebugInterface(&mut dred_settings).ok().and(dred_settings) {
+                    dred_settings.SetAutoBreadcrumbsEnablement(D3D12_DRED_ENABLEMENT_FORCED_ON);
+                    dred_settings.SetPageFaultEnablement(D3D12_DRED_ENABLEMENT_FORCED_ON);
+                    println!("hotline_rs::gfx::d3d12: enabling dred");
+                }
             }
 
             // create dxgi factory
@@ -1079,24 +2837,20 @@ impl super::Device for Device {
                 .expect("hotline_rs::gfx::d3d12: failed to create dxgi factory");
 
             // create adapter
-            let (adapter, adapter_info) = get_hardware_adapter(&dxgi_factory, &info.adapter_name)
+            let (adapter, mut adapter_info) = get_hardware_adapter(&dxgi_factory, &info.adapter_name)
                 .expect("hotline_rs::gfx::d3d12: failed to get hardware adapter");
 
             // create device
             let mut d3d12_device: Option<ID3D12Device> = None;
-            D3D12CreateDevice(adapter, D3D_FEATURE_LEVEL_11_0, &mut d3d12_device)
+            D3D12CreateDevice(&adapter, D3D_FEATURE_LEVEL_11_0, &mut d3d12_device)
                 .expect("hotline_rs::gfx::d3d12: failed to create d3d12 device");
             let device = d3d12_device.unwrap();
 
-            // create command allocator
-            let command_allocator = device
-                .CreateCommandAllocator(D3D12_COMMAND_LIST_TYPE_DIRECT)
-                .expect("hotline_rs::gfx::d3d12: failed to create command allocator");
+            get_device_feature_info(&device, &mut adapter_info);
 
-            // create command list
-            let command_list = device
-                .CreateCommandList(0, D3D12_COMMAND_LIST_TYPE_DIRECT, &command_allocator, None)
-                .expect("hotline_rs::gfx::d3d12: failed to create command list");
+            // adapter 3 is required to query memory budgets
+            let adapter: IDXGIAdapter3 = adapter.cast()
+                .expect("hotline_rs::gfx::d3d12: failed to get IDXGIAdapter3");
 
             // create queue
             let desc = D3D12_COMMAND_QUEUE_DESC {
@@ -1108,6 +2862,27 @@ impl super::Device for Device {
                 .CreateCommandQueue(&desc)
                 .expect("hotline_rs::gfx::d3d12: failed to create command queue");
 
+            // async compute queue
+            let compute_queue = device
+                .CreateCommandQueue(&D3D12_COMMAND_QUEUE_DESC {
+                    Type: D3D12_COMMAND_LIST_TYPE_COMPUTE,
+                    NodeMask: 1,
+                    ..Default::default()
+                })
+                .expect("hotline_rs::gfx::d3d12: failed to create compute command queue");
+
+            // copy queue for uploads
+            let upload_queue = UploadQueue::create(&device)
+                .expect("hotline_rs::gfx::d3d12: failed to create upload queue");
+
+            // timestamp queries
+            let timers = Timers::create(&device, &command_queue)
+                .expect("hotline_rs::gfx::d3d12: failed to create timestamp query heap");
+
+            // occlusion queries
+            let occlusion_queries = OcclusionQueries::create(&device)
+                .expect("hotline_rs::gfx::d3d12: failed to create occlusion query heap");
+
             // default heaps
 
             // shader (srv, cbv, uav)
@@ -1119,6 +2894,15 @@ impl super::Device for Device {
                 },
             );
 
+            // non shader visible uavs for clears
+            let shader_cpu_heap = create_heap(
+                &device,
+                &HeapInfo {
+                    heap_type: super::HeapType::ShaderCpu,
+                    num_descriptors: info.shader_heap_size,
+                },
+            );
+
             // rtv
             let rtv_heap = create_heap(
                 &device,
@@ -1137,19 +2921,62 @@ impl super::Device for Device {
                 },
             );
 
+            // samplers
+            let sampler_heap = create_heap(
+                &device,
+                &HeapInfo {
+                    heap_type: super::HeapType::Sampler,
+                    num_descriptors: info.sampler_heap_size,
+                },
+            );
+
+            // command signatures for indirect draws and dispatches, shared by all command buffers
+            let draw_indirect_signature = create_command_signature(
+                &device,
+                D3D12_INDIRECT_ARGUMENT_TYPE_DRAW,
+                std::mem::size_of::<super::DrawArguments>()
+            );
+            let draw_indexed_indirect_signature = create_command_signature(
+                &device,
+                D3D12_INDIRECT_ARGUMENT_TYPE_DRAW_INDEXED,
+                std::mem::size_of::<super::DrawIndexedArguments>()
+            );
+            let dispatch_indirect_signature = create_command_signature(
+                &device,
+                D3D12_INDIRECT_ARGUMENT_TYPE_DISPATCH,
+                std::mem::size_of::<super::DispatchArguments>()
+            );
+
             // initialise struct
             Device {
                 adapter_info,
+                adapter,
                 device,
                 dxgi_factory,
-                command_allocator,
-                command_list,
                 command_queue,
+                compute_queue,
+                upload_queue: Arc::new(Mutex::new(upload_queue)),
                 pix: WinPixEventRuntime::create(),
                 shader_heap,
+                shader_cpu_heap,
                 rtv_heap,
                 dsv_heap,
-                cleanup_textures: Vec::new()
+                sampler_heap,
+                cleanup_textures: Vec::new(),
+                cleanup_buffers: Vec::new(),
+                cleanup_render_pipelines: Vec::new(),
+                cleanup_compute_pipelines: Vec::new(),
+                cleanup_samplers: Vec::new(),
+                cleanup_rtvs: Vec::new(),
+                cleanup_dsvs: Vec::new(),
+                cleanup_mip_views: Vec::new(),
+                draw_indirect_signature,
+                draw_indexed_indirect_signature,
+                dispatch_indirect_signature,
+                mip_pipeline: None,
+                max_swap_chain_buffers: 0,
+                timers: Arc::new(Mutex::new(timers)),
+                occlusion_queries: Arc::new(Mutex::new(occlusion_queries))
             }
         }
     }
@@ -1164,8 +2991,25 @@ impl super::Device for Device {
         win: &A::Window,
     ) -> result::Result<SwapChain, super::Error> {
         unsafe {
-            // set flags, these could be passed in
-            let flags = DXGI_SWAP_CHAIN_FLAG_FRAME_LATENCY_WAITABLE_OBJECT.0;
+            if info.sync_interval > 4 {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::gfx::d3d12: sync_interval ({}) must be in the range 0-4", info.sync_interval)
+                });
+            }
+
+            // set flags, tearing is required to present with a sync interval of 0 on variable refresh displays
+            let allow_tearing = self.check_tearing_support();
+            let mut flags = DXGI_SWAP_CHAIN_FLAG_FRAME_LATENCY_WAITABLE_OBJECT.0;
+            if allow_tearing {
+                flags |= DXGI_SWAP_CHAIN_FLAG_ALLOW_TEARING.0;
+            }
+            let mut buffer_usage = DXGI_USAGE_RENDER_TARGET_OUTPUT;
+            let mut swap_effect = DXGI_SWAP_EFFECT_FLIP_DISCARD;
+            if info.usage.contains(super::TextureUsage::SHADER_RESOURCE) {
+                // flip sequential preserves backbuffer contents after present so previous frames can be sampled
+                buffer_usage |= DXGI_USAGE_SHADER_INPUT;
+                swap_effect = DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL;
+            }
             let format = info.format;
             let dxgi_format = to_dxgi_format(format);
 
@@ -1176,8 +3020,8 @@ impl super::Device for Device {
                 Width: size.x as u32,
                 Height: size.y as u32,
                 Format: dxgi_format,
-                BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
-                SwapEffect: DXGI_SWAP_EFFECT_FLIP_DISCARD,
+                BufferUsage: buffer_usage,
+                SwapEffect: swap_effect,
                 Flags: flags as u32,
                 SampleDesc: DXGI_SAMPLE_DESC {
                     Count: 1,
@@ -1200,10 +3044,36 @@ impl super::Device for Device {
                 )?;
             let swap_chain: IDXGISwapChain3 = swap_chain1.cast()?;
 
+            // hdr formats present in their matching colour space, other formats use the default srgb colour space
+            let colour_space = match format {
+                super::Format::RGB10A2n => Some(DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020),
+                super::Format::RGBA16f => Some(DXGI_COLOR_SPACE_RGB_FULL_G10_NONE_P709),
+                _ => None
+            };
+            if let Some(colour_space) = colour_space {
+                let support = swap_chain.CheckColorSpaceSupport(colour_space)?;
+                if (support & DXGI_SWAP_CHAIN_COLOR_SPACE_SUPPORT_FLAG_PRESENT.0 as u32) != 0 {
+                    swap_chain.SetColorSpace1(colour_space)?;
+                }
+                else {
+                    println!("hotline_rs::gfx::d3d12: hdr colour space is not supported by the output, presenting as srgb");
+                }
+            }
+
+            // the frame latency waitable object in `new_frame` will block until the queue is within the latency
+            if let Some(latency) = info.max_frame_latency {
+                if !(1..=16).contains(&latency) {
+                    return Err(super::Error {
+                        msg: format!("hotline_rs::gfx::d3d12: max_frame_latency ({}) must be in the range 1-16", latency)
+                    });
+                }
+                swap_chain.SetMaximumFrameLatency(latency)?;
+            }
+
             // create rtv heap and handles
-            let textures = create_swap_chain_rtv(&swap_chain, self, info.num_buffers);
+            let textures = create_swap_chain_rtv(&swap_chain, self, info.num_buffers, info.usage)?;
 
-            let data_size = size_for_format(format, size.x as u64, size.y as u64, 1);
+            let readback_footprints = get_copyable_footprints(&self.device, &textures[0].resource.GetDesc(), 0, 1);
             let passes = self.create_render_passes_for_swap_chain(
                 info.num_buffers,
                 &textures,
@@ -1216,6 +3086,8 @@ impl super::Device for Device {
                 None,
             );
 
+            self.max_swap_chain_buffers = self.max_swap_chain_buffers.max(info.num_buffers);
+
             Ok(SwapChain {
                 width: size.x,
                 height: size.y,
@@ -1232,7 +3104,11 @@ impl super::Device for Device {
                 backbuffer_passes_no_clear: passes_no_clear,
                 frame_index: 0,
                 frame_fence_value: vec![0; info.num_buffers as usize],
-                readback_buffer: create_read_back_buffer(self, data_size),
+                readback_buffer: Some(create_read_back_buffer(&self.device, readback_footprints.total_size)?),
+                readback_footprints,
+                usage: info.usage,
+                sync_interval: info.sync_interval,
+                allow_tearing,
                 require_wait: vec![false; info.num_buffers as usize],
                 clear_col: info.clear_colour,
             })
@@ -1240,6 +3116,15 @@ impl super::Device for Device {
     }
 
     fn create_cmd_buf(&self, num_buffers: u32) -> CmdBuf {
+        self.create_cmd_buf_for_queue(num_buffers, super::QueueType::Graphics)
+    }
+
+    fn create_cmd_buf_bundle(&self, count: u32, num_buffers: u32) -> Vec<CmdBuf> {
+        (0..count).map(|_| self.create_cmd_buf(num_buffers)).collect()
+    }
+
+    fn create_cmd_buf_for_queue(&self, num_buffers: u32, queue: super::QueueType) -> CmdBuf {
+        let list_type = to_d3d12_command_list_type(queue);
         unsafe {
             let mut command_allocators: Vec<ID3D12CommandAllocator> = Vec::new();
             let mut command_lists: Vec<ID3D12GraphicsCommandList> = Vec::new();
@@ -1250,13 +3135,13 @@ impl super::Device for Device {
                 // create command allocator
                 let command_allocator = self
                     .device
-                    .CreateCommandAllocator(D3D12_COMMAND_LIST_TYPE_DIRECT)
+                    .CreateCommandAllocator(list_type)
                     .expect("hotline_rs::gfx::d3d12: failed to create command allocator");
 
                 // create command list
                 let command_list = self
                     .device
-                    .CreateCommandList(0, D3D12_COMMAND_LIST_TYPE_DIRECT, &command_allocator, None)
+                    .CreateCommandList(0, list_type, &command_allocator, None)
                     .expect("hotline_rs::gfx::d3d12: failed to create command list");
 
                 command_allocators.push(command_allocator);
@@ -1268,12 +3153,24 @@ impl super::Device for Device {
 
             CmdBuf {
                 bb_index: 0,
+                queue_type: queue,
                 command_allocator: command_allocators,
                 command_list: command_lists,
                 pix: self.pix,
                 in_flight_barriers: barriers,
                 event_stack_count: 0,
-                needs_reset
+                needs_reset,
+                draw_indirect_signature: self.draw_indirect_signature.clone(),
+                draw_indexed_indirect_signature: self.draw_indexed_indirect_signature.clone(),
+                dispatch_indirect_signature: self.dispatch_indirect_signature.clone(),
+                free_read_back_buffers: Arc::new(Mutex::new(Vec::new())),
+                timers: self.timers.clone(),
+                timer_stack: Vec::new(),
+                occlusion_queries: self.occlusion_queries.clone(),
+                variable_shading_rate_tier: self.adapter_info.variable_shading_rate_tier,
+                frame_fence: None,
+                bound_heaps: Arc::new(Mutex::new([None, None])),
+                bindless: Arc::new(Mutex::new(BindlessBinding::default()))
             }
         }
     }
@@ -1282,108 +3179,7 @@ impl super::Device for Device {
         &self,
         info: &super::RenderPipelineInfo<Device>,
     ) -> result::Result<RenderPipeline, super::Error> {
-        let root_signature = self.create_root_signature(&info.descriptor_layout)?;
-
-        let semantics = null_terminate_semantics(&info.input_layout);
-        let mut elems = Device::create_d3d12_input_element_desc(&info.input_layout, &semantics);
-        let input_layout = D3D12_INPUT_LAYOUT_DESC {
-            pInputElementDescs: elems.as_mut_ptr(),
-            NumElements: elems.len() as u32,
-        };
-
-        let raster = &info.raster_info;
-        let depth_stencil = &info.depth_stencil_info;
-        let blend = &info.blend_info;
-
-        let null_bytecode = D3D12_SHADER_BYTECODE {
-            pShaderBytecode: std::ptr::null_mut(),
-            BytecodeLength: 0,
-        };
-
-        let msaa_format = info.pass.sample_count > 1;
-
-        let mut desc = D3D12_GRAPHICS_PIPELINE_STATE_DESC {
-            InputLayout: input_layout,
-            pRootSignature: Some(root_signature.clone()),
-            VS: if let Some(vs) = &info.vs {
-                D3D12_SHADER_BYTECODE {
-                    pShaderBytecode: vs.get_buffer_pointer(),
-                    BytecodeLength: vs.get_buffer_size(),
-                }
-            } else {
-                null_bytecode
-            },
-            PS: if let Some(ps) = &info.fs {
-                D3D12_SHADER_BYTECODE {
-                    pShaderBytecode: ps.get_buffer_pointer(),
-                    BytecodeLength: ps.get_buffer_size(),
-                }
-            } else {
-                null_bytecode
-            },
-            RasterizerState: D3D12_RASTERIZER_DESC {
-                FillMode: to_d3d12_fill_mode(&raster.fill_mode),
-                CullMode: to_d3d12_cull_mode(&raster.cull_mode),
-                FrontCounterClockwise: BOOL::from(raster.front_ccw),
-                DepthBias: raster.depth_bias,
-                DepthBiasClamp: raster.depth_bias_clamp,
-                SlopeScaledDepthBias: raster.slope_scaled_depth_bias,
-                DepthClipEnable: BOOL::from(raster.front_ccw),
-                MultisampleEnable: BOOL::from(msaa_format),
-                AntialiasedLineEnable: BOOL::from(msaa_format),
-                ForcedSampleCount: raster.forced_sample_count,
-                ConservativeRaster: if raster.conservative_raster_mode {
-                    D3D12_CONSERVATIVE_RASTERIZATION_MODE_ON
-                } else {
-                    D3D12_CONSERVATIVE_RASTERIZATION_MODE_OFF
-                },
-            },
-            BlendState: D3D12_BLEND_DESC {
-                AlphaToCoverageEnable: BOOL::from(blend.alpha_to_coverage_enabled),
-                IndependentBlendEnable: BOOL::from(blend.independent_blend_enabled),
-                RenderTarget: to_d3d12_render_target_blend(&blend.render_target),
-            },
-            DepthStencilState: D3D12_DEPTH_STENCIL_DESC {
-                DepthEnable: BOOL::from(depth_stencil.depth_enabled),
-                DepthWriteMask: to_d3d12_write_mask(&depth_stencil.depth_write_mask),
-                DepthFunc: to_d3d12_comparison_func(depth_stencil.depth_func),
-                StencilEnable: BOOL::from(depth_stencil.stencil_enabled),
-                StencilReadMask: depth_stencil.stencil_read_mask,
-                StencilWriteMask: depth_stencil.stencil_write_mask,
-                FrontFace: D3D12_DEPTH_STENCILOP_DESC {
-                    StencilFailOp: to_d3d12_stencil_op(&depth_stencil.front_face.fail),
-                    StencilDepthFailOp: to_d3d12_stencil_op(&depth_stencil.front_face.depth_fail),
-                    StencilPassOp: to_d3d12_stencil_op(&depth_stencil.front_face.pass),
-                    StencilFunc: to_d3d12_comparison_func(depth_stencil.front_face.func),
-                },
-                BackFace: D3D12_DEPTH_STENCILOP_DESC {
-                    StencilFailOp: to_d3d12_stencil_op(&depth_stencil.back_face.fail),
-                    StencilDepthFailOp: to_d3d12_stencil_op(&depth_stencil.back_face.depth_fail),
-                    StencilPassOp: to_d3d12_stencil_op(&depth_stencil.back_face.pass),
-                    StencilFunc: to_d3d12_comparison_func(depth_stencil.back_face.func),
-                },
-            },
-            SampleMask: u32::max_value(), // TODO:
-            PrimitiveTopologyType: to_d3d12_primitive_topology_type(info.topology),
-            NumRenderTargets: info.pass.rt_formats.len() as u32,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: info.pass.sample_count,
-                Quality: 0,
-            },
-            ..Default::default()
-        };
-
-        // Set formats from pass
-        for i in 0..info.pass.rt_formats.len() {
-            desc.RTVFormats[i] = info.pass.rt_formats[i];
-        }
-        desc.DSVFormat = info.pass.ds_format;
-
-        Ok(RenderPipeline {
-            pso: unsafe { self.device.CreateGraphicsPipelineState(&desc)? },
-            root_signature,
-            topology: to_d3d12_primitive_topology(info.topology, info.patch_index),
-        })
+        self.create_render_pipeline_with_library(info, None)
     }
 
     fn create_shader<T: Sized>(
@@ -1391,9 +3187,15 @@ impl super::Device for Device {
         info: &super::ShaderInfo,
         src: &[T],
     ) -> std::result::Result<Shader, super::Error> {
-        // compile source
+        // compile source, shader model 6 targets are compiled to dxil with dxc
         let mut shader_blob = None;
         if let Some(compile_info) = &info.compile_info {
+            if get_shader_model(&compile_info.target) >= 6 {
+                return Ok(Shader {
+                    blob: None,
+                    precompiled: Some(compile_dxil(compile_info, slice_as_u8_slice(src))?)
+                });
+            }
             let compile_flags = to_d3d12_compile_flags(&compile_info.flags);
             unsafe {
                 let nullt_entry_point = CString::new(compile_info.entry_point.clone())?;
@@ -1416,11 +3218,8 @@ impl super::Device for Device {
                 );
                 if result.is_err() {
                     if let Some(e) = errors {
-                        let buf = e.GetBufferPointer();
-                        let c_str: &CStr = CStr::from_ptr(buf as *const i8);
-                        let str_slice: &str = c_str.to_str().unwrap();
                         return Err(super::Error {
-                            msg: String::from(str_slice),
+                            msg: get_d3d12_error_blob_string(&e),
                         });
                     }
                     panic!("hotline_rs::gfx::d3d12: shader compile failed with no error information!");
@@ -1442,18 +3241,8 @@ impl super::Device for Device {
                 std::ptr::copy_nonoverlapping(src.as_ptr() as *mut u8, bytes.as_mut_ptr(), src.len());
             }
 
-            // validate DXBC 
-            // TODO: DXIL
-            let mut valid = true;
-            let validate = [b'D', b'X', b'B', b'C'];
-            for i in 0..4 {
-                if bytes[i] != validate[i] {
-                    valid = false;
-                    break;
-                }
-            }
-
-            if valid {
+            // validate the container, fxc (DXBC) and dxc (DXIL) both output a container with the DXBC fourcc
+            if &bytes[0..4] == b"DXBC" {
                 return Ok(Shader {
                     blob: None,
                     precompiled: Some(bytes)
@@ -1461,12 +3250,75 @@ impl super::Device for Device {
             }
         }
 
-        // invalid dxil shader bytecode
+        // invalid shader bytecode
         Err( super::Error {
             msg: String::from("hotline_rs::gfx::d3d12: shader byte code (src) is not valid"),
         })
     }
 
+    fn reflect_shader(&self, shader: &Shader) -> result::Result<super::ShaderReflection, super::Error> {
+        unsafe {
+            let bytecode = std::slice::from_raw_parts(shader.get_buffer_pointer() as *const u8, shader.get_buffer_size());
+            let reflector = if is_dxil_container(bytecode) {
+                // fxc reflection cannot read dxil
+                let utils: IDxcUtils = create_dxc_instance(&DXC_UTILS_CLSID)?;
+                utils.CreateReflection::<ID3D12ShaderReflection>(&DxcBuffer {
+                    Ptr: bytecode.as_ptr() as *const core::ffi::c_void,
+                    Size: bytecode.len(),
+                    Encoding: 0
+                })?
+            }
+            else {
+                let mut reflector: Option<ID3D12ShaderReflection> = None;
+                D3DReflect(
+                    bytecode.as_ptr() as *const core::ffi::c_void,
+                    bytecode.len(),
+                    &ID3D12ShaderReflection::IID,
+                    &mut reflector as *mut _ as *mut *mut core::ffi::c_void
+                )?;
+                reflector.ok_or_else(|| super::Error {
+                    msg: "hotline_rs::gfx::d3d12: failed to reflect shader".to_string()
+                })?
+            };
+
+            let mut desc = D3D12_SHADER_DESC::default();
+            reflector.GetDesc(&mut desc)?;
+
+            let mut bindings = Vec::new();
+            for i in 0..desc.BoundResources {
+                let mut bind_desc = D3D12_SHADER_INPUT_BIND_DESC::default();
+                reflector.GetResourceBindingDesc(i, &mut bind_desc)?;
+                let binding_type = match bind_desc.Type {
+                    D3D_SIT_CBUFFER => super::DescriptorType::ConstantBuffer,
+                    D3D_SIT_SAMPLER => super::DescriptorType::Sampler,
+                    D3D_SIT_UAV_RWTYPED |
+                    D3D_SIT_UAV_RWSTRUCTURED |
+                    D3D_SIT_UAV_RWBYTEADDRESS |
+                    D3D_SIT_UAV_APPEND_STRUCTURED |
+                    D3D_SIT_UAV_CONSUME_STRUCTURED |
+                    D3D_SIT_UAV_RWSTRUCTURED_WITH_COUNTER => super::DescriptorType::UnorderedAccess,
+                    _ => super::DescriptorType::ShaderResource
+                };
+                // unbounded arrays are reported with a bind count of 0
+                let num_descriptors = match bind_desc.BindCount {
+                    0 | u32::MAX => None,
+                    count => Some(count)
+                };
+                bindings.push(super::ShaderResourceBinding {
+                    name: CStr::from_ptr(bind_desc.Name.0 as *const i8).to_string_lossy().to_string(),
+                    shader_register: bind_desc.BindPoint,
+                    register_space: bind_desc.Space,
+                    binding_type,
+                    num_descriptors,
+                });
+            }
+
+            Ok(super::ShaderReflection {
+                bindings
+            })
+        }
+    }
+
     fn create_buffer<T: Sized>(
         &mut self,
         info: &super::BufferInfo,
@@ -1475,7 +3327,30 @@ impl super::Device for Device {
         let mut buf: Option<ID3D12Resource> = None;
         let dxgi_format = to_dxgi_format(info.format);
         let size_bytes = info.stride * info.num_elements;
+        validate_index_format(info)?;
         validate_data_size(size_bytes, data)?;
+        let unordered_access = matches!(info.usage,
+            super::BufferUsage::RWStructured | super::BufferUsage::AppendStructured | super::BufferUsage::RWRaw);
+        let raw = matches!(info.usage, super::BufferUsage::Raw | super::BufferUsage::RWRaw);
+        if raw && size_bytes % 4 != 0 {
+            return Err(super::Error {
+                msg: format!("hotline_rs::gfx::d3d12: raw buffer size ({}) bytes must be a multiple of 4", size_bytes),
+            });
+        }
+        // uav counters must be placed at an aligned offset, they are stored after the elements
+        let counter_offset = if matches!(info.usage, super::BufferUsage::AppendStructured) {
+            Some(super::align_pow2(size_bytes as u64, D3D12_UAV_COUNTER_PLACEMENT_ALIGNMENT as u64))
+        }
+        else {
+            None
+        };
+        // constant buffer views must be a multiple of 256 bytes, so the resource is padded to fit the view
+        let resource_size = if matches!(info.usage, super::BufferUsage::ConstantBuffer) {
+            super::align_pow2(size_bytes as u64, D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as u64)
+        }
+        else {
+            counter_offset.map_or(size_bytes as u64, |offset| offset + 4)
+        };
         unsafe {
             self.device.CreateCommittedResource(
                 &D3D12_HEAP_PROPERTIES {
@@ -1489,7 +3364,7 @@ impl super::Device for Device {
                 D3D12_HEAP_FLAG_NONE,
                 &D3D12_RESOURCE_DESC {
                     Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
-                    Width: size_bytes as u64,
+                    Width: resource_size,
                     Height: 1,
                     DepthOrArraySize: 1,
                     MipLevels: 1,
@@ -1498,6 +3373,12 @@ impl super::Device for Device {
                         Quality: 0,
                     },
                     Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+                    Flags: if unordered_access {
+                        D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS
+                    }
+                    else {
+                        D3D12_RESOURCE_FLAG_NONE
+                    },
                     ..Default::default()
                 },
                 // initial state
@@ -1557,38 +3438,19 @@ impl super::Device for Device {
                 }
                 res.Unmap(0, std::ptr::null());
 
-                // copy resource
-                let fence: ID3D12Fence = self.device.CreateFence(0, D3D12_FENCE_FLAG_NONE).unwrap();
-
-                self.command_list.CopyResource(&buf, upload);
-
-                let barrier = transition_barrier(
-                    &buf.clone().unwrap(),
-                    D3D12_RESOURCE_STATE_COPY_DEST,
-                    D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE,
-                );
-
-                // transition to shader resource
-                self.command_list.ResourceBarrier(&[barrier.clone()]);
-                self.command_list.Close()?;
-
-                let cmd = ID3D12CommandList::from(&self.command_list);
-                self.command_queue.ExecuteCommandLists(&[Some(cmd)]);
-                self.command_queue.Signal(&fence, 1)?;
-
-                let event = CreateEventA(std::ptr::null_mut(), false, false, None)?;
-                fence.SetEventOnCompletion(1, event)?;
-                WaitForSingleObject(event, INFINITE);
-
-                self.command_list.Reset(&self.command_allocator, None)?;
-                let _: D3D12_RESOURCE_TRANSITION_BARRIER =
-                    std::mem::ManuallyDrop::into_inner(barrier.Anonymous.Transition);
+                // copy resource on the upload queue and transition to shader resource once the batch is submitted
+                let mut upload_queue = self.upload_queue.lock().unwrap();
+                upload_queue.open()?;
+                upload_queue.list.CopyBufferRegion(&buf, 0, &upload, 0, size_bytes as u64);
+                upload_queue.push_upload(upload.unwrap(), buf.as_ref().unwrap(), D3D12_RESOURCE_STATE_PIXEL_SHADER_RESOURCE);
             }
 
             // create optional views
             let mut vbv: Option<D3D12_VERTEX_BUFFER_VIEW> = None;
             let mut ibv: Option<D3D12_INDEX_BUFFER_VIEW> = None;
             let mut srv_index = None;
+            let mut uav_index = None;
+            let mut uav_cpu = None;
 
             match info.usage {
                 super::BufferUsage::Vertex => {
@@ -1606,24 +3468,106 @@ impl super::Device for Device {
                     })
                 }
                 super::BufferUsage::ConstantBuffer => {
-                    let h = self.shader_heap.allocate();
+                    let h = self.shader_heap.allocate()?;
                     self.device.CreateConstantBufferView(
                         &D3D12_CONSTANT_BUFFER_VIEW_DESC {
                             BufferLocation: buf.clone().unwrap().GetGPUVirtualAddress(),
-                            SizeInBytes: size_bytes as u32,
+                            SizeInBytes: resource_size as u32,
                         },
                         h,
                     );
+                    self.shader_heap.commit(h);
                     srv_index = Some(self.shader_heap.get_handle_index(&h));
                 }
-            }
+                super::BufferUsage::IndirectArgument => (),
+                super::BufferUsage::Structured
+                | super::BufferUsage::RWStructured
+                | super::BufferUsage::AppendStructured
+                | super::BufferUsage::Raw
+                | super::BufferUsage::RWRaw => {
+                    // raw views address the buffer as 32 bit elements
+                    let (view_format, num_elements, stride) = if raw {
+                        (DXGI_FORMAT_R32_TYPELESS, size_bytes as u32 / 4, 0)
+                    }
+                    else {
+                        (DXGI_FORMAT_UNKNOWN, info.num_elements as u32, info.stride as u32)
+                    };
+                    let h = self.shader_heap.allocate()?;
+                    self.device.CreateShaderResourceView(
+                        &buf,
+                        &D3D12_SHADER_RESOURCE_VIEW_DESC {
+                            Format: view_format,
+                            ViewDimension: D3D12_SRV_DIMENSION_BUFFER,
+                            Anonymous: D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                                Buffer: D3D12_BUFFER_SRV {
+                                    FirstElement: 0,
+                                    NumElements: num_elements,
+                                    StructureByteStride: stride,
+                                    Flags: if raw {
+                                        D3D12_BUFFER_SRV_FLAG_RAW
+                                    }
+                                    else {
+                                        D3D12_BUFFER_SRV_FLAG_NONE
+                                    },
+                                },
+                            },
+                            Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
+                        },
+                        h,
+                    );
+                    self.shader_heap.commit(h);
+                    srv_index = Some(self.shader_heap.get_handle_index(&h));
+
+                    if unordered_access {
+                        let h = self.shader_cpu_heap.allocate()?;
+                        let counter_resource = if counter_offset.is_some() {
+                            buf.clone()
+                        }
+                        else {
+                            None
+                        };
+                        self.device.CreateUnorderedAccessView(
+                            &buf,
+                            &counter_resource,
+                            &D3D12_UNORDERED_ACCESS_VIEW_DESC {
+                                Format: view_format,
+                                ViewDimension: D3D12_UAV_DIMENSION_BUFFER,
+                                Anonymous: D3D12_UNORDERED_ACCESS_VIEW_DESC_0 {
+                                    Buffer: D3D12_BUFFER_UAV {
+                                        FirstElement: 0,
+                                        NumElements: num_elements,
+                                        StructureByteStride: stride,
+                                        CounterOffsetInBytes: counter_offset.unwrap_or(0),
+                                        Flags: if raw {
+                                            D3D12_BUFFER_UAV_FLAG_RAW
+                                        }
+                                        else {
+                                            D3D12_BUFFER_UAV_FLAG_NONE
+                                        },
+                                    },
+                                },
+                            },
+                            h,
+                        );
+                        uav_index = Some(self.copy_cpu_uav_to_shader_heap(h)?);
+                        uav_cpu = Some(h);
+                    }
+                }
+            }
 
             Ok(Buffer {
                 resource: buf.unwrap(),
                 vbv,
                 ibv,
                 srv_index,
-                uav_index: None,
+                uav_index,
+                uav_cpu,
+                state: if info.cpu_access.contains(super::CpuAccessFlags::WRITE) {
+                    None
+                }
+                else {
+                    Some(Arc::new(Mutex::new(super::ResourceState::ShaderResource)))
+                },
             })
         }
     }
@@ -1636,9 +3580,70 @@ impl super::Device for Device {
         let mut resource: Option<ID3D12Resource> = None;
         let mut resolved_resource: Option<ID3D12Resource> = None;
         let dxgi_format = to_dxgi_format(info.format);
-        let size_bytes = size_for_format(info.format, info.width, info.height, info.depth) as usize;
-        validate_data_size(size_bytes, data)?;
+        // 3D textures have depth slices in a single subresource, 1D and 2D textures can have array layers
+        let array_size = match info.tex_type {
+            super::TextureType::Texture3D => 1,
+            _ => info.array_levels.max(1)
+        };
+        let depth_or_array_size = match info.tex_type {
+            super::TextureType::Texture3D => info.depth,
+            _ => array_size
+        };
+        // data can contain only the top mip, or the full mip chain of each array layer
+        let mip_levels = info.mip_levels.max(1);
+        let chain_size_bytes = super::mip_chain_size_for_format(
+            info.format, info.width, info.height, info.depth, mip_levels) as usize * array_size as usize;
+        let upload_mips = match data {
+            Some(data) if mip_levels > 1 && data.len() * std::mem::size_of::<T>() == chain_size_bytes => mip_levels,
+            _ => 1
+        };
+        if upload_mips == 1 {
+            let size_bytes = size_for_format(info.format, info.width, info.height, info.depth * array_size) as usize;
+            validate_data_size(size_bytes, data)?;
+        }
         let initial_state = to_d3d12_resource_state(info.initial_state);
+
+        // block compressed textures must be a whole number of blocks
+        let block_dim = super::block_dimension_for_format(info.format);
+        if info.width % block_dim != 0 || info.height % block_dim != 0 {
+            return Err(super::Error {
+                msg: format!("hotline_rs::gfx::d3d12: block compressed texture size ({}, {}) must be a multiple of {}",
+                    info.width, info.height, block_dim)
+            });
+        }
+
+        // cubemaps are 2D arrays with 6 square faces per cube
+        if matches!(info.tex_type, super::TextureType::TextureCube) &&
+            (array_size % 6 != 0 || info.width != info.height || info.samples > 1) {
+            return Err(super::Error {
+                msg: format!("hotline_rs::gfx::d3d12: cubemap textures must be square, non msaa and have array_levels as a multiple of 6 ({})",
+                    array_size)
+            });
+        }
+
+        // mips are generated with typed uav stores, srgb formats are written through a linear view of a typeless resource
+        let generate_mips = info.usage.contains(super::TextureUsage::GENERATE_MIP_MAPS);
+        if generate_mips && !super::format_supports_generate_mips(info.format) {
+            return Err(super::Error {
+                msg: format!("hotline_rs::gfx::d3d12: TextureUsage::GENERATE_MIP_MAPS is not supported for format {:?}",
+                    info.format)
+            });
+        }
+
+        // typeless resources need explicitly typed views, which are only supported for 2D textures
+        let typeless = info.usage.contains(super::TextureUsage::TYPELESS) ||
+            (generate_mips && to_dxgi_format_linear(dxgi_format) != dxgi_format);
+        if typeless && !matches!(info.tex_type, super::TextureType::Texture2D) {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: TextureUsage::TYPELESS and srgb mip generation are only supported for Texture2D".to_string()
+            });
+        }
+        let resource_format = if typeless {
+            to_dxgi_format_typeless(info.format)
+        }
+        else {
+            dxgi_format
+        };
         unsafe {
             // create texture resource
             self.device.CreateCommittedResource(
@@ -1650,15 +3655,15 @@ impl super::Device for Device {
                 &D3D12_RESOURCE_DESC {
                     Dimension: match info.tex_type {
                         super::TextureType::Texture1D => D3D12_RESOURCE_DIMENSION_TEXTURE1D,
-                        super::TextureType::Texture2D => D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+                        super::TextureType::Texture2D | super::TextureType::TextureCube => D3D12_RESOURCE_DIMENSION_TEXTURE2D,
                         super::TextureType::Texture3D => D3D12_RESOURCE_DIMENSION_TEXTURE3D,
                     },
                     Alignment: 0,
                     Width: info.width,
                     Height: info.height as u32,
-                    DepthOrArraySize: info.depth as u16,
+                    DepthOrArraySize: depth_or_array_size as u16,
                     MipLevels: info.mip_levels as u16,
-                    Format: dxgi_format,
+                    Format: resource_format,
                     SampleDesc: DXGI_SAMPLE_DESC {
                         Count: info.samples,
                         Quality: 0,
@@ -1686,15 +3691,15 @@ impl super::Device for Device {
                     &D3D12_RESOURCE_DESC {
                         Dimension: match info.tex_type {
                             super::TextureType::Texture1D => D3D12_RESOURCE_DIMENSION_TEXTURE1D,
-                            super::TextureType::Texture2D => D3D12_RESOURCE_DIMENSION_TEXTURE2D,
+                            super::TextureType::Texture2D | super::TextureType::TextureCube => D3D12_RESOURCE_DIMENSION_TEXTURE2D,
                             super::TextureType::Texture3D => D3D12_RESOURCE_DIMENSION_TEXTURE3D,
                         },
                         Alignment: 0,
                         Width: info.width,
                         Height: info.height as u32,
-                        DepthOrArraySize: info.depth as u16,
+                        DepthOrArraySize: depth_or_array_size as u16,
                         MipLevels: info.mip_levels as u16,
-                        Format: dxgi_format,
+                        Format: resource_format,
                         SampleDesc: DXGI_SAMPLE_DESC {
                             Count: 1,
                             Quality: 0,
@@ -1713,12 +3718,13 @@ impl super::Device for Device {
             }
 
             if let Some(data) = &data {
-                // create upload buffer
-                let row_pitch = super::row_pitch_for_format(info.format, info.width);
-                let upload_pitch =
-                    super::align_pow2(row_pitch, D3D12_TEXTURE_DATA_PITCH_ALIGNMENT as u64);
-                let upload_size = info.height * upload_pitch;
+                // get placed footprints for each mip of each array layer, 3D textures have a single layer with depth slices
+                let desc = resource.clone().unwrap().GetDesc();
+                let num_subresources = array_size * mip_levels;
+                let footprints = get_copyable_footprints(&self.device, &desc, 0, num_subresources);
+                let upload_size = footprints.total_size;
 
+                // create upload buffer
                 let mut upload: Option<ID3D12Resource> = None;
                 self.device.CreateCommittedResource(
                     &D3D12_HEAP_PROPERTIES {
@@ -1746,7 +3752,7 @@ impl super::Device for Device {
                     &mut upload,
                 )?;
 
-                // copy data to upload buffer
+                // copy data to upload buffer, source data is tightly packed layer by layer, mip by mip, slice by slice
                 let range = D3D12_RANGE {
                     Begin: 0,
                     End: upload_size as usize,
@@ -1755,87 +3761,67 @@ impl super::Device for Device {
                 let res = upload.clone().unwrap();
                 res.Map(0, &range, &mut map_data)?;
                 if !map_data.is_null() {
-                    for y in 0..info.height {
-                        let src = data.as_ptr().offset((y * info.width * 4) as isize) as *const u8;
-                        let dst = (map_data as *mut u8).offset((y * upload_pitch) as isize);
-                        std::ptr::copy_nonoverlapping(src, dst, (info.width * 4) as usize);
+                    let src_data = data.as_ptr() as *const u8;
+                    let mut src_offset = 0;
+                    for layer in 0..array_size {
+                        for mip in 0..upload_mips {
+                            let subresource = super::subresource_index(mip, layer, mip_levels) as usize;
+                            let footprint = &footprints.layouts[subresource];
+                            let row_size = footprints.row_sizes[subresource] as usize;
+                            let row_pitch = footprint.Footprint.RowPitch as usize;
+                            let rows = footprints.num_rows[subresource] as usize;
+                            for z in 0..footprint.Footprint.Depth as usize {
+                                for y in 0..rows {
+                                    let dst_offset = footprint.Offset as usize + (z * rows + y) * row_pitch;
+                                    std::ptr::copy_nonoverlapping(
+                                        src_data.add(src_offset),
+                                        (map_data as *mut u8).add(dst_offset),
+                                        row_size
+                                    );
+                                    src_offset += row_size;
+                                }
+                            }
+                        }
                     }
                 }
                 res.Unmap(0, std::ptr::null());
 
-                // copy resource
-                let fence: ID3D12Fence = self.device.CreateFence(0, D3D12_FENCE_FLAG_NONE)?;
-
-                let src = D3D12_TEXTURE_COPY_LOCATION {
-                    pResource: Some(upload.unwrap()),
-                    Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
-                    Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
-                        PlacedFootprint: D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
-                            Offset: 0,
-                            Footprint: D3D12_SUBRESOURCE_FOOTPRINT {
-                                Width: info.width as u32,
-                                Height: info.height as u32,
-                                Depth: 1,
-                                Format: dxgi_format,
-                                RowPitch: upload_pitch as u32,
-                            },
+                // copy resource on the upload queue
+                let mut upload_queue = self.upload_queue.lock().unwrap();
+                upload_queue.open()?;
+                let subresources = (0..array_size).flat_map(|layer| (0..upload_mips).map(move |mip| super::subresource_index(mip, layer, mip_levels)));
+                for subresource in subresources {
+                    let src = D3D12_TEXTURE_COPY_LOCATION {
+                        pResource: Some(upload.clone().unwrap()),
+                        Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
+                        Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                            PlacedFootprint: footprints.layouts[subresource as usize],
                         },
-                    },
-                };
-
-                let dst = D3D12_TEXTURE_COPY_LOCATION {
-                    pResource: Some(resource.clone().unwrap()),
-                    Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
-                    Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
-                        SubresourceIndex: 0,
-                    },
-                };
-
-                self.command_list.CopyTextureRegion(&dst, 0, 0, 0, &src, std::ptr::null_mut());
-
-                let barrier = transition_barrier(
-                    &resource.clone().unwrap(),
-                    D3D12_RESOURCE_STATE_COPY_DEST,
-                    initial_state,
-                );
-
-                // transition to shader resource
-                self.command_list.ResourceBarrier(&[barrier.clone()]);
-                let _: D3D12_RESOURCE_TRANSITION_BARRIER =
-                    std::mem::ManuallyDrop::into_inner(barrier.Anonymous.Transition);
+                    };
 
-                self.command_list.Close()?;
+                    let dst = D3D12_TEXTURE_COPY_LOCATION {
+                        pResource: Some(resource.clone().unwrap()),
+                        Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+                        Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                            SubresourceIndex: subresource,
+                        },
+                    };
 
-                let cmd = ID3D12CommandList::from(&self.command_list);
-                self.command_queue.ExecuteCommandLists(&[Some(cmd)]);
-                self.command_queue.Signal(&fence, 1)?;
+                    upload_queue.list.CopyTextureRegion(&dst, 0, 0, 0, &src, std::ptr::null_mut());
+                }
 
-                let event = CreateEventA(std::ptr::null_mut(), false, false, None)?;
-                fence.SetEventOnCompletion(1, event)?;
-                WaitForSingleObject(event, INFINITE);
-                self.command_list.Reset(&self.command_allocator, None)?;
+                // transition to the initial state once the batch is submitted
+                upload_queue.push_upload(upload.unwrap(), resource.as_ref().unwrap(), initial_state);
             }
 
             // create srv
             let mut srv_index = None;
             if info.usage.contains(super::TextureUsage::SHADER_RESOURCE) {
-                let h = self.shader_heap.allocate();
-                self.device.CreateShaderResourceView(
-                    &resource,
-                    &D3D12_SHADER_RESOURCE_VIEW_DESC {
-                        Format: to_dxgi_format_srv(info.format),
-                        ViewDimension: to_d3d12_texture_srv_dimension(info.tex_type, info.samples),
-                        Anonymous: D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
-                            Texture2D: D3D12_TEX2D_SRV {
-                                MipLevels: info.mip_levels,
-                                MostDetailedMip: 0,
-                                ..Default::default()
-                            },
-                        },
-                        Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
-                    },
-                    h,
-                );
+                let h = self.shader_heap.allocate()?;
+                let srv_desc = to_d3d12_texture_srv_desc(
+                    info.tex_type, to_dxgi_format_srv(info.format), info.samples, info.mip_levels, array_size);
+                self.device.CreateShaderResourceView(&resource, &srv_desc, h);
+                self.shader_heap.commit(h);
                 srv_index = Some(self.shader_heap.get_handle_index(&h));
             }
 
@@ -1843,23 +3829,14 @@ impl super::Device for Device {
             let mut resolved_srv_index = None;
             let mut resolved_format = DXGI_FORMAT_UNKNOWN;
             if info.samples > 1 && info.usage.contains(super::TextureUsage::SHADER_RESOURCE) {
-                let h = self.shader_heap.allocate();
+                let h = self.shader_heap.allocate()?;
                 self.device.CreateShaderResourceView(
                     &resolved_resource,
-                    &D3D12_SHADER_RESOURCE_VIEW_DESC {
-                        Format: to_dxgi_format_srv(info.format),
-                        ViewDimension: to_d3d12_texture_srv_dimension(info.tex_type, 1),
-                        Anonymous: D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
-                            Texture2D: D3D12_TEX2D_SRV {
-                                MipLevels: info.mip_levels,
-                                MostDetailedMip: 0,
-                                ..Default::default()
-                            },
-                        },
-                        Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
-                    },
+                    &to_d3d12_texture_srv_desc(
+                        info.tex_type, to_dxgi_format_srv(info.format), 1, info.mip_levels, array_size),
                     h,
                 );
+                self.shader_heap.commit(h);
                 resolved_srv_index = Some(self.shader_heap.get_handle_index(&h));
                 resolved_format = to_dxgi_format_srv(info.format);
             }
@@ -1867,30 +3844,95 @@ impl super::Device for Device {
             // create rtv
             let mut rtv_handle = None;
             if info.usage.contains(super::TextureUsage::RENDER_TARGET) {
-                let h = self.rtv_heap.allocate();
-                self.device.CreateRenderTargetView(&resource.clone().unwrap(), std::ptr::null_mut(), h);
+                let h = self.rtv_heap.allocate()?;
+                if typeless {
+                    self.device.CreateRenderTargetView(
+                        &resource.clone().unwrap(),
+                        &D3D12_RENDER_TARGET_VIEW_DESC {
+                            Format: dxgi_format,
+                            ViewDimension: if info.samples > 1 {
+                                D3D12_RTV_DIMENSION_TEXTURE2DMS
+                            } else {
+                                D3D12_RTV_DIMENSION_TEXTURE2D
+                            },
+                            Anonymous: D3D12_RENDER_TARGET_VIEW_DESC_0 {
+                                Texture2D: D3D12_TEX2D_RTV {
+                                    MipSlice: 0,
+                                    PlaneSlice: 0,
+                                },
+                            },
+                        },
+                        h,
+                    );
+                }
+                else {
+                    self.device.CreateRenderTargetView(&resource.clone().unwrap(), std::ptr::null_mut(), h);
+                }
                 rtv_handle = Some(h);
             }
 
             // create dsv
             let mut dsv_handle = None;
             if info.usage.contains(super::TextureUsage::DEPTH_STENCIL) {
-                let h = self.dsv_heap.allocate();
-                self.device.CreateDepthStencilView(&resource.clone().unwrap(), std::ptr::null_mut(), h);
+                let h = self.dsv_heap.allocate()?;
+                if typeless {
+                    self.device.CreateDepthStencilView(
+                        &resource.clone().unwrap(),
+                        &D3D12_DEPTH_STENCIL_VIEW_DESC {
+                            Format: dxgi_format,
+                            ViewDimension: if info.samples > 1 {
+                                D3D12_DSV_DIMENSION_TEXTURE2DMS
+                            } else {
+                                D3D12_DSV_DIMENSION_TEXTURE2D
+                            },
+                            Flags: D3D12_DSV_FLAG_NONE,
+                            Anonymous: D3D12_DEPTH_STENCIL_VIEW_DESC_0 {
+                                Texture2D: D3D12_TEX2D_DSV {
+                                    MipSlice: 0,
+                                },
+                            },
+                        },
+                        h,
+                    );
+                }
+                else {
+                    self.device.CreateDepthStencilView(&resource.clone().unwrap(), std::ptr::null_mut(), h);
+                }
                 dsv_handle = Some(h);
             }
 
-            // create uav
+            // create uav, the view is created non shader visible and copied into the shader heap
             let mut uav_index = None;
+            let mut uav_cpu = None;
             if info.usage.contains(super::TextureUsage::UNORDERED_ACCESS) {
-                let h = self.shader_heap.allocate();
-                self.device.CreateUnorderedAccessView(
-                    &resource.clone().unwrap(),
-                    None,
-                    std::ptr::null_mut(),
-                    h,
-                );
-                uav_index = Some(self.shader_heap.get_handle_index(&h));
+                let h = self.shader_cpu_heap.allocate()?;
+                if typeless {
+                    self.device.CreateUnorderedAccessView(
+                        &resource.clone().unwrap(),
+                        None,
+                        &D3D12_UNORDERED_ACCESS_VIEW_DESC {
+                            Format: dxgi_format,
+                            ViewDimension: D3D12_UAV_DIMENSION_TEXTURE2D,
+                            Anonymous: D3D12_UNORDERED_ACCESS_VIEW_DESC_0 {
+                                Texture2D: D3D12_TEX2D_UAV {
+                                    MipSlice: 0,
+                                    PlaneSlice: 0,
+                                },
+                            },
+                        },
+                        h,
+                    );
+                }
+                else {
+                    self.device.CreateUnorderedAccessView(
+                        &resource.clone().unwrap(),
+                        None,
+                        std::ptr::null_mut(),
+                        h,
+                    );
+                }
+                uav_index = Some(self.copy_cpu_uav_to_shader_heap(h)?);
+                uav_cpu = Some(h);
             }
 
             // create shared handle for video decode targets
@@ -1907,6 +3949,7 @@ impl super::Device for Device {
 
             Ok(Texture {
                 resource: resource.unwrap(),
+                format: dxgi_format,
                 resolved_resource,
                 resolved_format,
                 rtv: rtv_handle,
@@ -1914,15 +3957,787 @@ impl super::Device for Device {
                 srv_index,
                 resolved_srv_index,
                 uav_index,
-                shared_handle
+                uav_cpu,
+                shared_handle,
+                state: Arc::new(Mutex::new(info.initial_state)),
+            })
+        }
+    }
+
+    fn create_texture_view(
+        &mut self,
+        texture: &Texture,
+        format: super::Format,
+        tex_type: super::TextureType,
+    ) -> result::Result<usize, super::Error> {
+        unsafe {
+            let desc = texture.resource.GetDesc();
+            if to_dxgi_format_typeless(format) != desc.Format {
+                return Err(super::Error {
+                    msg: "hotline_rs::gfx::d3d12: texture views require a texture created with TextureUsage::TYPELESS and a compatible format".to_string()
+                });
+            }
+            let h = self.shader_heap.allocate()?;
+            let array_size = if desc.Dimension == D3D12_RESOURCE_DIMENSION_TEXTURE3D {
+                1
+            }
+            else {
+                desc.DepthOrArraySize as u32
+            };
+            self.device.CreateShaderResourceView(
+                &texture.resource,
+                &to_d3d12_texture_srv_desc(
+                    tex_type, to_dxgi_format_srv(format), desc.SampleDesc.Count, desc.MipLevels as u32, array_size),
+                h,
+            );
+            self.shader_heap.commit(h);
+            Ok(self.shader_heap.get_handle_index(&h))
+        }
+    }
+
+    fn create_render_target_view(
+        &mut self,
+        texture: &Texture,
+        mip: u32,
+        slice: u32
+    ) -> result::Result<usize, super::Error> {
+        let desc = unsafe { texture.resource.GetDesc() };
+        if (desc.Flags.0 & D3D12_RESOURCE_FLAG_ALLOW_RENDER_TARGET.0) == 0 {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: render target views require a texture created with TextureUsage::RENDER_TARGET".to_string()
+            });
+        }
+        validate_view_subresource(&desc, mip, slice)?;
+        let (view_dimension, view) = match desc.Dimension {
+            D3D12_RESOURCE_DIMENSION_TEXTURE1D => (
+                D3D12_RTV_DIMENSION_TEXTURE1DARRAY,
+                D3D12_RENDER_TARGET_VIEW_DESC_0 {
+                    Texture1DArray: D3D12_TEX1D_ARRAY_RTV {
+                        MipSlice: mip,
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                    }
+                }
+            ),
+            D3D12_RESOURCE_DIMENSION_TEXTURE3D => (
+                D3D12_RTV_DIMENSION_TEXTURE3D,
+                D3D12_RENDER_TARGET_VIEW_DESC_0 {
+                    Texture3D: D3D12_TEX3D_RTV {
+                        MipSlice: mip,
+                        FirstWSlice: slice,
+                        WSize: 1,
+                    }
+                }
+            ),
+            _ if desc.SampleDesc.Count > 1 => (
+                D3D12_RTV_DIMENSION_TEXTURE2DMSARRAY,
+                D3D12_RENDER_TARGET_VIEW_DESC_0 {
+                    Texture2DMSArray: D3D12_TEX2DMS_ARRAY_RTV {
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                    }
+                }
+            ),
+            _ => (
+                D3D12_RTV_DIMENSION_TEXTURE2DARRAY,
+                D3D12_RENDER_TARGET_VIEW_DESC_0 {
+                    Texture2DArray: D3D12_TEX2D_ARRAY_RTV {
+                        MipSlice: mip,
+                        FirstArraySlice: slice,
+                        ArraySize: 1,
+                        PlaneSlice: 0,
+                    }
+                }
+            )
+        };
+        let h = self.rtv_heap.allocate()?;
+        unsafe {
+            self.device.CreateRenderTargetView(
+                &texture.resource,
+                &D3D12_RENDER_TARGET_VIEW_DESC {
+                    Format: texture.format,
+                    ViewDimension: view_dimension,
+                    Anonymous: view,
+                },
+                h,
+            );
+        }
+        Ok(self.rtv_heap.get_handle_index(&h))
+    }
+
+    fn create_depth_stencil_view(
+        &mut self,
+        texture: &Texture,
+        mip: u32,
+        slice: u32
+    ) -> result::Result<usize, super::Error> {
+        self.create_depth_stencil_view_with_flags(texture, mip, slice, D3D12_DSV_FLAG_NONE)
+    }
+
+    fn create_read_only_depth_stencil_view(
+        &mut self,
+        texture: &Texture,
+        mip: u32,
+        slice: u32
+    ) -> result::Result<usize, super::Error> {
+        let flags = if has_stencil_plane(texture.format) {
+            D3D12_DSV_FLAGS(D3D12_DSV_FLAG_READ_ONLY_DEPTH.0 | D3D12_DSV_FLAG_READ_ONLY_STENCIL.0)
+        }
+        else {
+            D3D12_DSV_FLAG_READ_ONLY_DEPTH
+        };
+        self.create_depth_stencil_view_with_flags(texture, mip, slice, flags)
+    }
+
+    fn generate_mips(&mut self, cmd: &mut CmdBuf, texture: &Texture) -> result::Result<(), super::Error> {
+        let desc = unsafe { texture.resource.GetDesc() };
+        let mip_levels = desc.MipLevels as u32;
+        if mip_levels <= 1 {
+            return Ok(());
+        }
+        if desc.Dimension != D3D12_RESOURCE_DIMENSION_TEXTURE2D || desc.SampleDesc.Count > 1 {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: generate_mips is only supported for non msaa Texture2D".to_string()
+            });
+        }
+        if (desc.Flags.0 & D3D12_RESOURCE_FLAG_ALLOW_UNORDERED_ACCESS.0) == 0 {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: generate_mips requires a texture created with TextureUsage::GENERATE_MIP_MAPS".to_string()
+            });
+        }
+
+        if self.mip_pipeline.is_none() {
+            self.mip_pipeline = Some(self.create_mip_pipeline()?);
+        }
+        let pipeline = self.mip_pipeline.clone().unwrap();
+        let array_size = desc.DepthOrArraySize as u32;
+        let state = to_d3d12_resource_state(*texture.state.lock().unwrap());
+        let format = to_dxgi_format_linear(texture.format);
+
+        // views of each source and destination mip, allocated before recording in case the heap grows
+        let mut views = Vec::new();
+        unsafe {
+            for mip in 1..mip_levels {
+                let srv = self.shader_heap.allocate()?;
+                self.device.CreateShaderResourceView(&texture.resource, &D3D12_SHADER_RESOURCE_VIEW_DESC {
+                    Format: format,
+                    ViewDimension: D3D12_SRV_DIMENSION_TEXTURE2DARRAY,
+                    Anonymous: D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                        Texture2DArray: D3D12_TEX2D_ARRAY_SRV {
+                            MostDetailedMip: mip - 1,
+                            MipLevels: 1,
+                            FirstArraySlice: 0,
+                            ArraySize: array_size,
+                            ..Default::default()
+                        },
+                    },
+                    Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
+                }, srv);
+                self.shader_heap.commit(srv);
+
+                let uav = self.shader_heap.allocate()?;
+                self.device.CreateUnorderedAccessView(&texture.resource, None, &D3D12_UNORDERED_ACCESS_VIEW_DESC {
+                    Format: format,
+                    ViewDimension: D3D12_UAV_DIMENSION_TEXTURE2DARRAY,
+                    Anonymous: D3D12_UNORDERED_ACCESS_VIEW_DESC_0 {
+                        Texture2DArray: D3D12_TEX2D_ARRAY_UAV {
+                            MipSlice: mip,
+                            FirstArraySlice: 0,
+                            ArraySize: array_size,
+                            PlaneSlice: 0,
+                        },
+                    },
+                }, uav);
+                self.shader_heap.commit(uav);
+                views.push((self.shader_heap.get_handle_index(&srv), self.shader_heap.get_handle_index(&uav)));
+            }
+
+            let list = cmd.cmd();
+            let barrier = |before, after, subresource| {
+                let barrier = transition_barrier_subresource_index(&texture.resource, before, after, subresource);
+                list.ResourceBarrier(&[barrier.clone()]);
+                let _: D3D12_RESOURCE_TRANSITION_BARRIER =
+                    std::mem::ManuallyDrop::into_inner(barrier.Anonymous.Transition);
+            };
+
+            // all mips start as uav, each source mip is transitioned to srv before it is read
+            if state != D3D12_RESOURCE_STATE_UNORDERED_ACCESS {
+                barrier(state, D3D12_RESOURCE_STATE_UNORDERED_ACCESS, D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES);
+            }
+
+            cmd.bind_heap(&self.shader_heap);
+            list.SetComputeRootSignature(&pipeline.root_signature);
+            list.SetPipelineState(&pipeline.pso);
+            list.SetComputeRootDescriptorTable(1, self.shader_heap.heap.GetGPUDescriptorHandleForHeapStart());
+
+            let mut src_size = (desc.Width as u32, desc.Height);
+            for (mip, (srv, uav)) in (1..mip_levels).zip(&views) {
+                for layer in 0..array_size {
+                    barrier(
+                        D3D12_RESOURCE_STATE_UNORDERED_ACCESS,
+                        D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
+                        layer * mip_levels + mip - 1
+                    );
+                }
+
+                let constants = [*srv as u32, *uav as u32, src_size.0, src_size.1];
+                list.SetComputeRoot32BitConstants(0, 4, constants.as_ptr() as *const ::core::ffi::c_void, 0);
+
+                let dst_size = ((src_size.0 / 2).max(1), (src_size.1 / 2).max(1));
+                list.Dispatch((dst_size.0 + 7) / 8, (dst_size.1 + 7) / 8, array_size);
+                src_size = dst_size;
+            }
+
+            // the last mip is still uav, after which all mips are returned to the tracked state together
+            for layer in 0..array_size {
+                barrier(
+                    D3D12_RESOURCE_STATE_UNORDERED_ACCESS,
+                    D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE,
+                    layer * mip_levels + mip_levels - 1
+                );
+            }
+            if state != D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE {
+                barrier(D3D12_RESOURCE_STATE_NON_PIXEL_SHADER_RESOURCE, state, D3D12_RESOURCE_BARRIER_ALL_SUBRESOURCES);
+            }
+        }
+
+        // views are released once the frame which executes `cmd` has completed
+        for (srv, uav) in views {
+            self.cleanup_mip_views.push((0, srv));
+            self.cleanup_mip_views.push((0, uav));
+        }
+        Ok(())
+    }
+
+    fn get_timer_results(&self) -> HashMap<String, f64> {
+        let mut timers = self.timers.lock().unwrap();
+        if let Err(err) = timers.update() {
+            println!("hotline_rs::gfx::d3d12: failed to read back timers: {}", err.msg);
+        }
+        timers.results.clone()
+    }
+
+    fn get_occlusion_results(&self) -> Vec<bool> {
+        let mut queries = self.occlusion_queries.lock().unwrap();
+        if let Err(err) = queries.update() {
+            println!("hotline_rs::gfx::d3d12: failed to read back occlusion queries: {}", err.msg);
+        }
+        queries.results.clone()
+    }
+
+    fn upload_buffer_region<T: Sized>(
+        &mut self,
+        dst: &Buffer,
+        offset: usize,
+        data: &[T]
+    ) -> result::Result<(), super::Error> {
+        if dst.state.is_none() {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: upload_buffer_region dst must be a gpu only buffer, use Buffer::update for cpu writable buffers".to_string()
+            });
+        }
+        let size = super::align_pow2((data.len() * std::mem::size_of::<T>()) as u64, 16);
+        let dst_size = unsafe { dst.resource.GetDesc().Width };
+        if offset as u64 + (data.len() * std::mem::size_of::<T>()) as u64 > dst_size {
+            return Err(super::Error {
+                msg: format!(
+                    "hotline_rs::gfx::d3d12: upload of ({}) bytes at offset ({}) exceeds buffer size ({})",
+                    data.len() * std::mem::size_of::<T>(), offset, dst_size
+                ),
+            });
+        }
+        if size > UPLOAD_RING_SIZE {
+            return Err(super::Error {
+                msg: format!(
+                    "hotline_rs::gfx::d3d12: upload of ({}) bytes exceeds the upload ring size ({})",
+                    size, UPLOAD_RING_SIZE
+                ),
+            });
+        }
+
+        let mut upload_queue = self.upload_queue.lock().unwrap();
+        let ring_offset = loop {
+            if let Some(ring_offset) = upload_queue.try_allocate_ring(size) {
+                break ring_offset;
+            }
+            // ring is full, submit pending regions and wait for the oldest to complete
+            upload_queue.submit(&self.command_queue)?;
+            let oldest = upload_queue.ring_regions.front().and_then(|region| region.0).unwrap_or(0);
+            upload_queue.fence.wait_cpu(oldest)?;
+        };
+
+        upload_queue.open()?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr() as *const u8,
+                upload_queue.ring_data.add(ring_offset as usize),
+                data.len() * std::mem::size_of::<T>()
+            );
+            // buffers decay to the common state between command lists, so they can be copied on the copy queue
+            upload_queue.list.CopyBufferRegion(
+                &dst.resource,
+                offset as u64,
+                &upload_queue.ring,
+                ring_offset,
+                (data.len() * std::mem::size_of::<T>()) as u64
+            );
+        }
+        upload_queue.pending = true;
+        Ok(())
+    }
+
+    fn flush_uploads(&self) -> result::Result<(), super::Error> {
+        let mut upload_queue = self.upload_queue.lock().unwrap();
+        upload_queue.submit(&self.command_queue)?;
+        upload_queue.fence.wait_cpu(upload_queue.fence_value)
+    }
+
+    fn create_fence(&self) -> result::Result<Fence, super::Error> {
+        Fence::create(&self.device)
+    }
+
+    fn signal(&self, fence: &Fence, value: u64) -> result::Result<(), super::Error> {
+        unsafe {
+            self.command_queue.Signal(&fence.fence, value)?;
+        }
+        Ok(())
+    }
+
+    fn wait(&self, fence: &Fence, value: u64) -> result::Result<(), super::Error> {
+        fence.wait_cpu(value)
+    }
+
+    fn queue_signal(&self, queue: super::QueueType, fence: &Fence, value: u64) -> result::Result<(), super::Error> {
+        unsafe {
+            self.get_queue(queue).Signal(&fence.fence, value)?;
+        }
+        Ok(())
+    }
+
+    fn queue_wait(&self, queue: super::QueueType, fence: &Fence, value: u64) -> result::Result<(), super::Error> {
+        unsafe {
+            self.get_queue(queue).Wait(&fence.fence, value)?;
+        }
+        Ok(())
+    }
+
+    fn create_pipeline_library(&self, data: Option<&[u8]>) -> result::Result<PipelineLibrary, super::Error> {
+        unsafe {
+            let device: ID3D12Device1 = self.device.cast()?;
+            if let Some(data) = data {
+                // blobs from a different driver version or adapter are rejected, so we start a new library instead
+                let data: Arc<[u8]> = Arc::from(data);
+                let library = device.CreatePipelineLibrary::<ID3D12PipelineLibrary>(
+                    data.as_ptr() as *const core::ffi::c_void, data.len());
+                if let Ok(library) = library {
+                    return Ok(PipelineLibrary {
+                        library,
+                        _data: Some(data)
+                    });
+                }
+            }
+            Ok(PipelineLibrary {
+                library: device.CreatePipelineLibrary(std::ptr::null(), 0)?,
+                _data: None
             })
         }
     }
 
+    fn create_render_pipeline_from_library(
+        &self,
+        info: &super::RenderPipelineInfo<Device>,
+        library: &PipelineLibrary,
+        name: &str
+    ) -> result::Result<RenderPipeline, super::Error> {
+        self.create_render_pipeline_with_library(info, Some((library, name)))
+    }
+
+    fn create_compute_pipeline_from_library(
+        &self,
+        info: &super::ComputePipelineInfo<Device>,
+        library: &PipelineLibrary,
+        name: &str
+    ) -> result::Result<ComputePipeline, super::Error> {
+        self.create_compute_pipeline_with_library(info, Some((library, name)))
+    }
+
     fn destroy_texture(&mut self, texture: Self::Texture) {
         self.cleanup_textures.push((0, texture));
     }
 
+    fn destroy_buffer(&mut self, buffer: Self::Buffer) {
+        self.cleanup_buffers.push((0, buffer));
+    }
+
+    fn destroy_render_pipeline(&mut self, pipeline: Self::RenderPipeline) {
+        self.cleanup_render_pipelines.push((0, pipeline));
+    }
+
+    fn destroy_compute_pipeline(&mut self, pipeline: Self::ComputePipeline) {
+        self.cleanup_compute_pipelines.push((0, pipeline));
+    }
+
+    fn destroy_sampler(&mut self, sampler: Self::Sampler) {
+        self.cleanup_samplers.push((0, sampler));
+    }
+
+    fn destroy_render_target_view(&mut self, index: usize) {
+        self.cleanup_rtvs.push((0, index));
+    }
+
+    fn destroy_depth_stencil_view(&mut self, index: usize) {
+        self.cleanup_dsvs.push((0, index));
+    }
+
+    fn create_raytracing_blas(
+        &self,
+        info: &super::RaytracingBLASInfo<Device>,
+    ) -> result::Result<RaytracingBLAS, super::Error> {
+        self.validate_raytracing_supported()?;
+        let geometry = info.geometry.iter().map(|geometry| {
+            let (index_buffer, index_format, index_count) = match geometry.index_buffer {
+                Some(index_buffer) => unsafe {
+                    (index_buffer.resource.GetGPUVirtualAddress(), to_dxgi_format(geometry.index_format),
+                        geometry.index_count as u32)
+                },
+                None => (0, DXGI_FORMAT_UNKNOWN, 0)
+            };
+            D3D12_RAYTRACING_GEOMETRY_DESC {
+                Type: D3D12_RAYTRACING_GEOMETRY_TYPE_TRIANGLES,
+                Flags: if geometry.opaque {
+                    D3D12_RAYTRACING_GEOMETRY_FLAG_OPAQUE
+                }
+                else {
+                    D3D12_RAYTRACING_GEOMETRY_FLAG_NONE
+                },
+                Anonymous: D3D12_RAYTRACING_GEOMETRY_DESC_0 {
+                    Triangles: D3D12_RAYTRACING_GEOMETRY_TRIANGLES_DESC {
+                        Transform3x4: 0,
+                        IndexFormat: index_format,
+                        VertexFormat: to_dxgi_format(geometry.vertex_format),
+                        IndexCount: index_count,
+                        VertexCount: geometry.vertex_count as u32,
+                        IndexBuffer: index_buffer,
+                        VertexBuffer: D3D12_GPU_VIRTUAL_ADDRESS_AND_STRIDE {
+                            StartAddress: unsafe { geometry.vertex_buffer.resource.GetGPUVirtualAddress() },
+                            StrideInBytes: geometry.vertex_stride as u64,
+                        },
+                    }
+                }
+            }
+        }).collect::<Vec<D3D12_RAYTRACING_GEOMETRY_DESC>>();
+
+        let flags = to_d3d12_acceleration_structure_build_flags(info.flags);
+        let (resource, scratch) = self.create_acceleration_structure_buffers(&to_d3d12_blas_inputs(&geometry, flags))?;
+        Ok(RaytracingBLAS {
+            resource,
+            scratch,
+            geometry,
+            flags
+        })
+    }
+
+    fn create_raytracing_tlas(
+        &mut self,
+        info: &super::RaytracingTLASInfo<Device>,
+    ) -> result::Result<RaytracingTLAS, super::Error> {
+        self.validate_raytracing_supported()?;
+        let instances = info.instances.iter().map(|instance| {
+            RaytracingInstanceDesc {
+                transform: instance.transform,
+                instance_id_and_mask: (instance.instance_id & 0xffffff) | (instance.mask as u32) << 24,
+                hit_group_index_and_flags: instance.hit_group_index & 0xffffff,
+                acceleration_structure: unsafe { instance.blas.resource.GetGPUVirtualAddress() },
+            }
+        }).collect::<Vec<RaytracingInstanceDesc>>();
+        let instance_data = unsafe {
+            std::slice::from_raw_parts(instances.as_ptr() as *const u8,
+                instances.len() * std::mem::size_of::<RaytracingInstanceDesc>())
+        };
+        let instance_buffer = self.create_upload_buffer_with_data(instance_data)?;
+
+        let flags = to_d3d12_acceleration_structure_build_flags(info.flags);
+        let num_instances = instances.len() as u32;
+        let inputs = unsafe {
+            to_d3d12_tlas_inputs(instance_buffer.GetGPUVirtualAddress(), num_instances, flags)
+        };
+        let (resource, scratch) = self.create_acceleration_structure_buffers(&inputs)?;
+
+        // acceleration structures are viewed by address and not by resource
+        let h = self.shader_heap.allocate()?;
+        let null_resource: Option<ID3D12Resource> = None;
+        unsafe {
+            self.device.CreateShaderResourceView(
+                &null_resource,
+                &D3D12_SHADER_RESOURCE_VIEW_DESC {
+                    Format: DXGI_FORMAT_UNKNOWN,
+                    ViewDimension: D3D12_SRV_DIMENSION_RAYTRACING_ACCELERATION_STRUCTURE,
+                    Anonymous: D3D12_SHADER_RESOURCE_VIEW_DESC_0 {
+                        RaytracingAccelerationStructure: D3D12_RAYTRACING_ACCELERATION_STRUCTURE_SRV {
+                            Location: resource.GetGPUVirtualAddress()
+                        },
+                    },
+                    Shader4ComponentMapping: D3D12_DEFAULT_SHADER_4_COMPONENT_MAPPING,
+                },
+                h,
+            );
+        }
+        self.shader_heap.commit(h);
+
+        Ok(RaytracingTLAS {
+            resource,
+            scratch,
+            instances: instance_buffer,
+            num_instances,
+            flags,
+            srv_index: self.shader_heap.get_handle_index(&h),
+            _blas: info.instances.iter().map(|instance| instance.blas.resource.clone()).collect()
+        })
+    }
+
+    fn create_raytracing_pipeline(
+        &self,
+        info: &super::RaytracingPipelineInfo<Device>,
+    ) -> result::Result<RaytracingPipeline, super::Error> {
+        self.validate_raytracing_supported()?;
+        let (root_signature, bindless_slot) = self.create_root_signature(&info.descriptor_layout)?;
+
+        // wide names must outlive the state object creation
+        let to_wide = |name: Option<&String>| {
+            name.map(|name| name.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>())
+        };
+        let to_pcwstr = |name: &Option<Vec<u16>>| {
+            name.as_ref().map_or(PCWSTR(std::ptr::null()), |name| PCWSTR(name.as_ptr()))
+        };
+        let hit_group_names = info.hit_groups.iter().map(|group| {
+            [
+                to_wide(Some(&group.name)),
+                to_wide(group.closest_hit.as_ref()),
+                to_wide(group.any_hit.as_ref()),
+                to_wide(group.intersection.as_ref())
+            ]
+        }).collect::<Vec<[Option<Vec<u16>>; 4]>>();
+        let hit_groups = info.hit_groups.iter().zip(&hit_group_names).map(|(group, names)| {
+            D3D12_HIT_GROUP_DESC {
+                HitGroupExport: to_pcwstr(&names[0]),
+                Type: if group.intersection.is_some() {
+                    D3D12_HIT_GROUP_TYPE_PROCEDURAL_PRIMITIVE
+                }
+                else {
+                    D3D12_HIT_GROUP_TYPE_TRIANGLES
+                },
+                ClosestHitShaderImport: to_pcwstr(&names[1]),
+                AnyHitShaderImport: to_pcwstr(&names[2]),
+                IntersectionShaderImport: to_pcwstr(&names[3]),
+            }
+        }).collect::<Vec<D3D12_HIT_GROUP_DESC>>();
+
+        // with no explicit exports every entry point in the library is exported
+        let library = D3D12_DXIL_LIBRARY_DESC {
+            DXILLibrary: D3D12_SHADER_BYTECODE {
+                pShaderBytecode: info.library.get_buffer_pointer(),
+                BytecodeLength: info.library.get_buffer_size(),
+            },
+            NumExports: 0,
+            pExports: std::ptr::null_mut(),
+        };
+        let shader_config = D3D12_RAYTRACING_SHADER_CONFIG {
+            MaxPayloadSizeInBytes: info.max_payload_size,
+            MaxAttributeSizeInBytes: info.max_attribute_size,
+        };
+        let global_root_signature = D3D12_GLOBAL_ROOT_SIGNATURE {
+            pGlobalRootSignature: Some(root_signature.clone())
+        };
+        let pipeline_config = D3D12_RAYTRACING_PIPELINE_CONFIG {
+            MaxTraceRecursionDepth: info.max_recursion_depth
+        };
+
+        let mut subobjects = vec![
+            D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_DXIL_LIBRARY,
+                pDesc: &library as *const _ as _
+            },
+            D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_SHADER_CONFIG,
+                pDesc: &shader_config as *const _ as _
+            },
+            D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_GLOBAL_ROOT_SIGNATURE,
+                pDesc: &global_root_signature as *const _ as _
+            },
+            D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_RAYTRACING_PIPELINE_CONFIG,
+                pDesc: &pipeline_config as *const _ as _
+            },
+        ];
+        for hit_group in &hit_groups {
+            subobjects.push(D3D12_STATE_SUBOBJECT {
+                Type: D3D12_STATE_SUBOBJECT_TYPE_HIT_GROUP,
+                pDesc: hit_group as *const _ as _
+            });
+        }
+
+        let state_object = unsafe {
+            let device: ID3D12Device5 = self.device.cast()?;
+            device.CreateStateObject::<ID3D12StateObject>(&D3D12_STATE_OBJECT_DESC {
+                Type: D3D12_STATE_OBJECT_TYPE_RAYTRACING_PIPELINE,
+                NumSubobjects: subobjects.len() as u32,
+                pSubobjects: subobjects.as_ptr() as _,
+            })?
+        };
+
+        Ok(RaytracingPipeline {
+            state_object,
+            root_signature,
+            bindless_slot
+        })
+    }
+
+    fn create_raytracing_shader_binding_table(
+        &self,
+        info: &super::RaytracingShaderBindingTableInfo<Device>,
+    ) -> result::Result<RaytracingShaderBindingTable, super::Error> {
+        let properties: ID3D12StateObjectProperties = info.pipeline.state_object.cast()?;
+        let identifier_size = D3D12_SHADER_IDENTIFIER_SIZE_IN_BYTES as usize;
+        let record_size = super::align_pow2(identifier_size as u64, D3D12_RAYTRACING_SHADER_RECORD_BYTE_ALIGNMENT as u64);
+        let table_alignment = D3D12_RAYTRACING_SHADER_TABLE_BYTE_ALIGNMENT as u64;
+
+        // ray generation, miss and hit group tables each start at an aligned offset
+        let num_miss_shaders = info.miss_shaders.len() as u64;
+        let num_hit_groups = info.hit_groups.len() as u64;
+        let miss_offset = super::align_pow2(record_size, table_alignment);
+        let hit_group_offset = super::align_pow2(miss_offset + record_size * num_miss_shaders, table_alignment);
+        let mut data = vec![0u8; (hit_group_offset + record_size * num_hit_groups) as usize];
+
+        let records = std::iter::once((0, &info.ray_generation_shader))
+            .chain(info.miss_shaders.iter().enumerate().map(|(i, name)| (miss_offset + i as u64 * record_size, name)))
+            .chain(info.hit_groups.iter().enumerate().map(|(i, name)| (hit_group_offset + i as u64 * record_size, name)));
+        for (offset, name) in records {
+            let wide_name = name.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+            let identifier = unsafe { properties.GetShaderIdentifier(PCWSTR(wide_name.as_ptr())) };
+            if identifier.is_null() {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::gfx::d3d12: shader or hit group `{}` is not exported from the pipeline", name)
+                });
+            }
+            let offset = offset as usize;
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    identifier as *const u8, data[offset..offset + identifier_size].as_mut_ptr(), identifier_size);
+            }
+        }
+
+        Ok(RaytracingShaderBindingTable {
+            resource: self.create_upload_buffer_with_data(&data)?,
+            record_size,
+            miss_offset,
+            num_miss_shaders,
+            hit_group_offset,
+            num_hit_groups
+        })
+    }
+
+    fn create_sampler(&mut self, info: &super::SamplerInfo) -> result::Result<Sampler, super::Error> {
+        let h = self.sampler_heap.allocate()?;
+        unsafe {
+            self.device.CreateSampler(&D3D12_SAMPLER_DESC {
+                Filter: to_d3d12_filter(info.filter),
+                AddressU: to_d3d12_address_mode(info.address_u),
+                AddressV: to_d3d12_address_mode(info.address_v),
+                AddressW: to_d3d12_address_mode(info.address_w),
+                MipLODBias: info.mip_lod_bias,
+                MaxAnisotropy: info.max_aniso,
+                ComparisonFunc: to_d3d12_address_comparison_func(info.comparison),
+                BorderColor: to_d3d12_sampler_border_colour_rgba(info.border_colour),
+                MinLOD: info.min_lod,
+                MaxLOD: info.max_lod,
+            }, h);
+        }
+        self.sampler_heap.commit(h);
+        Ok(Sampler {
+            index: self.sampler_heap.get_handle_index(&h)
+        })
+    }
+
+    fn create_mesh_pipeline(
+        &self,
+        info: &super::MeshPipelineInfo<Device>,
+    ) -> result::Result<RenderPipeline, super::Error> {
+        if !self.adapter_info.mesh_shader_supported() {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: mesh shaders are not supported on this device".to_string()
+            });
+        }
+
+        let (root_signature, bindless_slot) = self.create_root_signature(&info.descriptor_layout)?;
+        let bytecode = |shader: Option<&Shader>| {
+            match shader {
+                Some(shader) => D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: shader.get_buffer_pointer(),
+                    BytecodeLength: shader.get_buffer_size(),
+                },
+                None => D3D12_SHADER_BYTECODE {
+                    pShaderBytecode: std::ptr::null_mut(),
+                    BytecodeLength: 0,
+                }
+            }
+        };
+
+        let mut rt_formats = D3D12_RT_FORMAT_ARRAY {
+            NumRenderTargets: info.pass.rt_formats.len() as u32,
+            ..Default::default()
+        };
+        for (i, format) in info.pass.rt_formats.iter().enumerate() {
+            rt_formats.RTFormats[i] = *format;
+        }
+
+        let mut stream = MeshPipelineStateStream {
+            root_signature: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_ROOT_SIGNATURE, Some(root_signature.clone())),
+            amplification_shader: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_AS, bytecode(info.amp)),
+            mesh_shader: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_MS, bytecode(Some(info.ms))),
+            pixel_shader: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_PS, bytecode(info.fs)),
+            rasterizer: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RASTERIZER,
+                to_d3d12_rasterizer_desc(&info.raster_info, info.pass.sample_count > 1)),
+            blend: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_BLEND,
+                to_d3d12_blend_desc(&info.blend_info, info.pass.rt_formats.len())?),
+            depth_stencil: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL,
+                to_d3d12_depth_stencil_desc(&info.depth_stencil_info)),
+            rt_formats: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_RENDER_TARGET_FORMATS, rt_formats),
+            ds_format: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_DEPTH_STENCIL_FORMAT, info.pass.ds_format),
+            sample_desc: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_SAMPLE_DESC, DXGI_SAMPLE_DESC {
+                    Count: info.pass.sample_count,
+                    Quality: 0,
+                }),
+            sample_mask: PipelineStateSubobject::new(
+                D3D12_PIPELINE_STATE_SUBOBJECT_TYPE_SAMPLE_MASK, u32::max_value()),
+        };
+
+        let pso = unsafe {
+            let device: ID3D12Device2 = self.device.cast()?;
+            device.CreatePipelineState::<ID3D12PipelineState>(&D3D12_PIPELINE_STATE_STREAM_DESC {
+                SizeInBytes: std::mem::size_of::<MeshPipelineStateStream>(),
+                pPipelineStateSubobjectStream: &mut stream as *mut _ as *mut core::ffi::c_void,
+            })?
+        };
+
+        Ok(RenderPipeline {
+            pso,
+            root_signature,
+            topology: D3D_PRIMITIVE_TOPOLOGY_UNDEFINED,
+            bindless_slot,
+        })
+    }
+
     fn create_render_pass(
         &self,
         info: &super::RenderPassInfo<Device>,
@@ -1943,10 +4758,35 @@ impl super::Device for Device {
         } else if info.discard {
             begin_type = D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_DISCARD;
         }
+        if let Some(rt_views) = &info.rt_views {
+            if rt_views.len() != info.render_targets.len() {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::gfx::d3d12: number of render target views ({}) does not match number of render targets ({})",
+                        rt_views.len(),
+                        info.render_targets.len()
+                )});
+            }
+        }
+        if let Some(rt_clears) = &info.rt_clears {
+            if rt_clears.len() != info.render_targets.len() {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::gfx::d3d12: number of clear colours ({}) does not match number of render targets ({})",
+                        rt_clears.len(),
+                        info.render_targets.len()
+                )});
+            }
+        }
         let mut sample_count = None;
-        for target in &info.render_targets {
+        for (i, target) in info.render_targets.iter().enumerate() {
+            // per target clears take precedence over the single broadcast clear
+            let (begin_type, clear_col) = if let Some(rt_clears) = &info.rt_clears {
+                (D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_CLEAR, rt_clears[i])
+            }
+            else {
+                (begin_type, clear_col)
+            };
             let desc = unsafe { target.resource.GetDesc() };
-            let dxgi_format = desc.Format;
+            let dxgi_format = target.format;
             let target_sample_count = desc.SampleDesc.Count;
             if sample_count.is_none() {
                 sample_count = Some(target_sample_count);
@@ -1979,7 +4819,10 @@ impl super::Device for Device {
             };
             formats.push(dxgi_format);
             rt.push(D3D12_RENDER_PASS_RENDER_TARGET_DESC {
-                cpuDescriptor: target.rtv.unwrap(),
+                cpuDescriptor: match &info.rt_views {
+                    Some(rt_views) => self.rtv_heap.get_handle_for_index(rt_views[i]),
+                    None => target.rtv.unwrap()
+                },
                 BeginningAccess: begin,
                 EndingAccess: end,
             })
@@ -2018,8 +4861,15 @@ impl super::Device for Device {
                 }
             }
 
-            let desc = unsafe { depth_stencil.resource.GetDesc() };
-            ds_format = desc.Format;
+            ds_format = depth_stencil.format;
+
+            // depth and stencil planes are accessed independently so clearing only one preserves the other, formats
+            // without a stencil plane must not access it at all
+            let mut stencil_end_type = end_type;
+            if !has_stencil_plane(ds_format) {
+                stencil_begin_type = D3D12_RENDER_PASS_BEGINNING_ACCESS_TYPE_NO_ACCESS;
+                stencil_end_type = D3D12_RENDER_PASS_ENDING_ACCESS_TYPE_NO_ACCESS;
+            }
 
             let depth_begin = D3D12_RENDER_PASS_BEGINNING_ACCESS {
                 Type: depth_begin_type,
@@ -2062,7 +4912,7 @@ impl super::Device for Device {
             };
 
             let stencil_end = D3D12_RENDER_PASS_ENDING_ACCESS {
-                Type: end_type,
+                Type: stencil_end_type,
                 Anonymous: D3D12_RENDER_PASS_ENDING_ACCESS_0 {
                     Resolve: Default::default(),
                 },
@@ -2070,7 +4920,10 @@ impl super::Device for Device {
 
             // TODO: if no dsv
             ds = Some(D3D12_RENDER_PASS_DEPTH_STENCIL_DESC {
-                cpuDescriptor: depth_stencil.dsv.unwrap(),
+                cpuDescriptor: match info.ds_view {
+                    Some(ds_view) => self.dsv_heap.get_handle_for_index(ds_view),
+                    None => depth_stencil.dsv.unwrap()
+                },
                 DepthBeginningAccess: depth_begin,
                 StencilBeginningAccess: stencil_begin,
                 DepthEndingAccess: depth_end,
@@ -2100,31 +4953,108 @@ impl super::Device for Device {
         &self,
         info: &super::ComputePipelineInfo<Self>,
     ) -> result::Result<ComputePipeline, super::Error> {
-        let cs = &info.cs;
-        let root_signature = self.create_root_signature(&info.descriptor_layout)?;
+        self.create_compute_pipeline_with_library(info, None)
+    }
 
-        let desc = D3D12_COMPUTE_PIPELINE_STATE_DESC {
-            CS: D3D12_SHADER_BYTECODE {
-                pShaderBytecode: cs.get_buffer_pointer(),
-                BytecodeLength: cs.get_buffer_size(),
-            },
-            pRootSignature: Some(root_signature.clone()),
-            ..Default::default()
+    fn create_ring_buffer(
+        &mut self,
+        info: &super::RingBufferInfo,
+    ) -> result::Result<RingBuffer, super::Error> {
+        let frame_size = super::align_pow2(
+            info.frame_size as u64, D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as u64) as usize;
+        let size_bytes = frame_size * info.num_buffers as usize;
+        let mut buf: Option<ID3D12Resource> = None;
+        unsafe {
+            self.device.CreateCommittedResource(
+                &D3D12_HEAP_PROPERTIES {
+                    Type: D3D12_HEAP_TYPE_UPLOAD,
+                    ..Default::default()
+                },
+                D3D12_HEAP_FLAG_NONE,
+                &D3D12_RESOURCE_DESC {
+                    Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
+                    Alignment: 0,
+                    Width: size_bytes as u64,
+                    Height: 1,
+                    DepthOrArraySize: 1,
+                    MipLevels: 1,
+                    Format: DXGI_FORMAT_UNKNOWN,
+                    SampleDesc: DXGI_SAMPLE_DESC {
+                        Count: 1,
+                        Quality: 0,
+                    },
+                    Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+                    Flags: D3D12_RESOURCE_FLAG_NONE,
+                },
+                D3D12_RESOURCE_STATE_GENERIC_READ,
+                std::ptr::null(),
+                &mut buf,
+            )?;
+
+            // upload heaps can remain persistently mapped, we never read from the cpu
+            let resource = buf.unwrap();
+            let range = D3D12_RANGE { Begin: 0, End: 0 };
+            let mut map_data = std::ptr::null_mut();
+            resource.Map(0, &range, &mut map_data)?;
+
+            Ok(RingBuffer {
+                gpu_address: resource.GetGPUVirtualAddress(),
+                resource,
+                cpu_address: map_data as *mut u8,
+                frame_size,
+                bb_index: 0,
+                offset: 0,
+            })
+        }
+    }
+
+    fn execute(&self, cmd: &CmdBuf) -> result::Result<(), super::Error> {
+        self.execute_multiple(&[cmd])
+    }
+
+    fn execute_multiple(&self, cmds: &[&CmdBuf]) -> result::Result<(), super::Error> {
+        let queue_type = match cmds.first() {
+            Some(cmd) => cmd.queue_type,
+            None => return Ok(())
         };
+        if cmds.iter().any(|cmd| cmd.queue_type != queue_type) {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: execute_multiple requires all command buffers to be for the same queue".to_string()
+            });
+        }
 
+        // submit pending uploads first, the direct queue waits on the gpu for them to complete
+        let mut upload_queue = self.upload_queue.lock().unwrap();
+        upload_queue.submit(&self.command_queue)?;
+        let queue = self.get_queue(queue_type);
         unsafe {
-            Ok(ComputePipeline {
-                pso: self.device.CreateComputePipelineState(&desc)?,
-                root_signature,
-            })
+            // uploads are transitioned on the direct queue, so the compute queue waits for those to complete
+            if queue_type == super::QueueType::Compute {
+                queue.Wait(&upload_queue.fence.fence, upload_queue.fence_value)?;
+            }
+            let command_lists = cmds.iter().map(|cmd| {
+                Some(ID3D12CommandList::from(&cmd.command_list[cmd.bb_index]))
+            }).collect::<Vec<_>>();
+            queue.ExecuteCommandLists(&command_lists);
+            // ExecuteCommandLists does not return errors, so check if the device was removed
+            self.device.GetDeviceRemovedReason()
+                .map_err(|err| to_device_error(&self.device, err, "execute"))
         }
     }
 
-    fn execute(&self, cmd: &CmdBuf) {
+    fn is_device_removed(&self) -> bool {
         unsafe {
-            let command_list = ID3D12CommandList::from(&cmd.command_list[cmd.bb_index]);
-            self.command_queue.ExecuteCommandLists(&[Some(command_list)]);
+            self.device.GetDeviceRemovedReason().is_err()
+        }
+    }
+
+    fn get_dred_breadcrumbs(&self) -> result::Result<String, super::Error> {
+        if !self.is_device_removed() {
+            return Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: dred breadcrumbs are only available after the device is removed".to_string()
+            });
         }
+        get_dred_info(&self.device)
     }
 
     fn report_live_objects(&self) -> result::Result<(), super::Error> {
@@ -2137,35 +5067,56 @@ impl super::Device for Device {
 
     fn clean_up_resources(&mut self, swap_chain: &SwapChain) {
         use crate::gfx::Heap;
-        let num_bb = swap_chain.num_bb;
-        let mut todo = true;
-        let mut cur = 0;
-        while todo {
-            todo = false;
-            for i in cur..self.cleanup_textures.len() {
-                // increment frames waited
-                self.cleanup_textures[i].0 += 1;
-                if self.cleanup_textures[i].0 > num_bb {
-                    // if we have waited longer than the swap chain length we can cleanup
-                    let (_, tex) = self.cleanup_textures.remove(i);
-                    if let Some(srv) = tex.srv_index {
-                        self.shader_heap.deallocate(srv);
-                    }
-                    if let Some(uav) = tex.uav_index {
-                        self.shader_heap.deallocate(uav);
-                    }
-                    if let Some(rtv) = &tex.rtv {
-                        self.rtv_heap.deallocate_internal(rtv);
-                    }
-                    if let Some(dsv) = &tex.dsv {
-                        self.dsv_heap.deallocate_internal(dsv)
-                    }
-                    cur = i;
-                    todo = true;
-                    break;
-                }
+        let num_bb = swap_chain.num_bb.max(self.max_swap_chain_buffers);
+        self.upload_queue.lock().unwrap().release_completed_uploads();
+        for tex in take_complete_cleanups(&mut self.cleanup_textures, num_bb) {
+            if let Some(srv) = tex.srv_index {
+                self.shader_heap.deallocate(srv);
+            }
+            if let Some(uav) = tex.uav_index {
+                self.shader_heap.deallocate(uav);
+            }
+            if let Some(uav_cpu) = &tex.uav_cpu {
+                self.shader_cpu_heap.deallocate_internal(uav_cpu);
             }
+            if let Some(resolved_srv) = tex.resolved_srv_index {
+                self.shader_heap.deallocate(resolved_srv);
+            }
+            if let Some(rtv) = &tex.rtv {
+                self.rtv_heap.deallocate_internal(rtv);
+            }
+            if let Some(dsv) = &tex.dsv {
+                self.dsv_heap.deallocate_internal(dsv)
+            }
+        }
+        for buf in take_complete_cleanups(&mut self.cleanup_buffers, num_bb) {
+            if let Some(srv) = buf.srv_index {
+                self.shader_heap.deallocate(srv);
+            }
+            if let Some(uav) = buf.uav_index {
+                self.shader_heap.deallocate(uav);
+            }
+            if let Some(uav_cpu) = &buf.uav_cpu {
+                self.shader_cpu_heap.deallocate_internal(uav_cpu);
+            }
+        }
+        // pipelines hold no descriptors, they are released when dropped
+        take_complete_cleanups(&mut self.cleanup_render_pipelines, num_bb);
+        take_complete_cleanups(&mut self.cleanup_compute_pipelines, num_bb);
+        for sampler in take_complete_cleanups(&mut self.cleanup_samplers, num_bb) {
+            self.sampler_heap.deallocate(sampler.index);
         }
+        for rtv in take_complete_cleanups(&mut self.cleanup_rtvs, num_bb) {
+            self.rtv_heap.deallocate(rtv);
+        }
+        for dsv in take_complete_cleanups(&mut self.cleanup_dsvs, num_bb) {
+            self.dsv_heap.deallocate(dsv);
+        }
+        for view in take_complete_cleanups(&mut self.cleanup_mip_views, num_bb) {
+            self.shader_heap.deallocate(view);
+        }
+        self.shader_heap.release_retired_heaps(num_bb);
+        self.sampler_heap.release_retired_heaps(num_bb);
     }
 
     fn get_shader_heap(&self) -> &Self::Heap {
@@ -2176,10 +5127,40 @@ impl super::Device for Device {
         &mut self.shader_heap
     }
 
+    fn get_sampler_heap(&self) -> &Self::Heap {
+        &self.sampler_heap
+    }
+
     fn get_adapter_info(&self) -> &AdapterInfo {
         &self.adapter_info
     }
 
+    fn set_stable_power_state(&self, enabled: bool) -> result::Result<(), super::Error> {
+        unsafe {
+            self.device.SetStablePowerState(enabled).map_err(|e| super::Error {
+                msg: format!("hotline_rs::gfx::d3d12: failed to set stable power state, developer mode is required: {}", e)
+            })
+        }
+    }
+
+    fn get_video_memory_info(&self) -> result::Result<super::VideoMemoryInfo, super::Error> {
+        let query = |segment_group| -> result::Result<super::MemoryBudget, super::Error> {
+            let mut info = DXGI_QUERY_VIDEO_MEMORY_INFO::default();
+            unsafe {
+                self.adapter.QueryVideoMemoryInfo(0, segment_group, &mut info)?;
+            }
+            Ok(super::MemoryBudget {
+                budget: info.Budget as usize,
+                current_usage: info.CurrentUsage as usize,
+                available_for_reservation: info.AvailableForReservation as usize,
+            })
+        };
+        Ok(super::VideoMemoryInfo {
+            local: query(DXGI_MEMORY_SEGMENT_GROUP_LOCAL)?,
+            non_local: query(DXGI_MEMORY_SEGMENT_GROUP_NON_LOCAL)?,
+        })
+    }
+
     fn as_ptr(&self) -> *const Self {
         self as *const Self
     }
@@ -2190,13 +5171,15 @@ impl super::Device for Device {
 }
 
 impl SwapChain {
+    /// Waits until the frame which last rendered to backbuffer `frame_index` has completed on the gpu, so its command
+    /// allocators and resources can be reused. With n backbuffers the cpu can run up to n frames ahead, each
+    /// backbuffer stores the fence value signalled after its last present in `frame_fence_value`
     fn wait_for_frame(&mut self, frame_index: usize) {
         unsafe {
-            let mut fv = self.frame_fence_value[frame_index];
+            let fv = self.frame_fence_value[frame_index];
 
             // 0 means no fence was signaled
             if fv != 0 {
-                fv = 0;
                 self.fence
                     .SetEventOnCompletion(fv, self.fence_event)
                     .expect("hotline_rs::gfx::d3d12: failed to set on completion event!");
@@ -2237,11 +5220,14 @@ impl super::SwapChain<Device> for SwapChain {
                 
                 cmd.drop_complete_in_flight_barriers(cmd.bb_index);
 
-                // clean up rtv handles
+                // clean up rtv and srv handles
                 for bb_tex in &self.backbuffer_textures {
                     if bb_tex.rtv.is_some() {
                         device.rtv_heap.deallocate_internal(&bb_tex.rtv.unwrap());
                     }
+                    if let Some(srv_index) = bb_tex.srv_index {
+                        device.shader_heap.deallocate(srv_index);
+                    }
                 }
 
                 // clean up texture resource
@@ -2257,13 +5243,9 @@ impl super::SwapChain<Device> for SwapChain {
                     )
                     .expect("hotline_rs::gfx::d3d12: warning: present failed!");
 
-                let data_size = super::slice_pitch_for_format(
-                    self.format,
-                    self.width as u64,
-                    self.height as u64,
-                );
                 self.backbuffer_textures =
-                    create_swap_chain_rtv(&self.swap_chain, device, self.num_bb);
+                    create_swap_chain_rtv(&self.swap_chain, device, self.num_bb, self.usage)
+                        .expect("hotline_rs::gfx::d3d12: failed to create swap chain render targets!");
                 self.backbuffer_passes = device.create_render_passes_for_swap_chain(
                     self.num_bb,
                     &self.backbuffer_textures,
@@ -2275,7 +5257,10 @@ impl super::SwapChain<Device> for SwapChain {
                     None,
                 );
 
-                self.readback_buffer = create_read_back_buffer(device, data_size);
+                self.readback_footprints = get_copyable_footprints(
+                    &device.device, &self.backbuffer_textures[0].resource.GetDesc(), 0, 1);
+                self.readback_buffer = Some(create_read_back_buffer(&device.device, self.readback_footprints.total_size)
+                    .expect("hotline_rs::gfx::d3d12: failed to create swap chain readback buffer!"));
                 self.width = size.x;
                 self.height = size.y;
                 self.bb_index = 0;
@@ -2293,6 +5278,11 @@ impl super::SwapChain<Device> for SwapChain {
         &self.backbuffer_textures[self.bb_index]
     }
 
+    fn get_previous_backbuffer_texture(&self) -> &Texture {
+        let num_bb = self.num_bb as usize;
+        &self.backbuffer_textures[(self.bb_index + num_bb - 1) % num_bb]
+    }
+
     fn get_backbuffer_pass(&self) -> &RenderPass {
         &self.backbuffer_passes[self.bb_index]
     }
@@ -2309,17 +5299,28 @@ impl super::SwapChain<Device> for SwapChain {
         &mut self.backbuffer_passes_no_clear[self.bb_index]
     }
 
-    fn swap(&mut self, device: &Device) {
+    fn set_vsync(&mut self, vsync: bool) {
+        self.sync_interval = if vsync { 1 } else { 0 };
+    }
+
+    fn swap(&mut self, device: &Device) -> result::Result<(), super::Error> {
         unsafe {
             // present
-            self.swap_chain.Present(1, 0).expect("hotline_rs::gfx::d3d12: warning: present failed!");
+            let flags = if self.sync_interval == 0 && self.allow_tearing {
+                DXGI_PRESENT_ALLOW_TEARING
+            }
+            else {
+                0
+            };
+            self.swap_chain.Present(self.sync_interval, flags)
+                .map_err(|err| to_device_error(&device.device, err, "present"))?;
 
             // signal fence
             let fv = self.fence_last_signalled_value + 1;
             device
                 .command_queue
                 .Signal(&self.fence, fv)
-                .expect("hotline_rs::gfx::d3d12: warning: command_queue.Signal failed!");
+                .map_err(|err| to_device_error(&device.device, err, "signal"))?;
 
             // update fence tracking
             self.fence_last_signalled_value = fv;
@@ -2330,6 +5331,7 @@ impl super::SwapChain<Device> for SwapChain {
             self.frame_index += 1;
             self.bb_index = (self.bb_index + 1) % self.num_bb as usize;
         }
+        Ok(())
     }
 
     fn as_ptr(&self) -> *const Self {
@@ -2346,6 +5348,148 @@ impl CmdBuf {
         &self.command_list[self.bb_index]
     }
 
+    /// returns the resource with shader visible and non shader visible uav handles of `texture` or `buffer` for uav
+    /// clears, binding `heap` which the shader visible handle must be in
+    fn get_uav_clear_handles(
+        &self,
+        texture: Option<&Texture>,
+        buffer: Option<&Buffer>,
+        heap: &Heap
+    ) -> result::Result<(ID3D12Resource, D3D12_GPU_DESCRIPTOR_HANDLE, D3D12_CPU_DESCRIPTOR_HANDLE), super::Error> {
+        let uav = texture.map(|tex| (tex.resource.clone(), tex.uav_index, tex.uav_cpu))
+            .or_else(|| buffer.map(|buf| (buf.resource.clone(), buf.uav_index, buf.uav_cpu)));
+        match uav {
+            Some((resource, Some(index), Some(cpu_handle))) => {
+                self.bind_heap(heap);
+                let mut gpu_handle = unsafe { heap.heap.GetGPUDescriptorHandleForHeapStart() };
+                gpu_handle.ptr += (index * heap.increment_size) as u64;
+                Ok((resource, gpu_handle, cpu_handle))
+            }
+            _ => Err(super::Error {
+                msg: "hotline_rs::gfx::d3d12: clear unordered access view requires a texture or buffer with a uav".to_string()
+            })
+        }
+    }
+
+    /// sets `heap` on the command list alongside the heap of the other type which is already bound
+    fn bind_heap(&self, heap: &Heap) {
+        let mut bound_heaps = self.bound_heaps.lock().unwrap();
+        let index = if heap.heap_type == super::HeapType::Sampler { 1 } else { 0 };
+        bound_heaps[index] = Some(heap.heap.clone());
+        let heaps = bound_heaps.iter().filter(|heap| heap.is_some()).cloned().collect::<Vec<_>>();
+        unsafe {
+            self.cmd().SetDescriptorHeaps(&heaps);
+        }
+    }
+
+    /// binds the heap set with `set_bindless_heap` to the bindless table `slot` of the current pipeline as
+    /// (slot, compute), if there is one
+    fn bind_bindless_table(&self, slot: Option<(u32, bool)>) {
+        let mut bindless = self.bindless.lock().unwrap();
+        bindless.slot = slot;
+        if let (Some(heap_start), Some((slot, compute))) = (bindless.heap_start, slot) {
+            unsafe {
+                if compute {
+                    self.cmd().SetComputeRootDescriptorTable(slot, heap_start);
+                }
+                else {
+                    self.cmd().SetGraphicsRootDescriptorTable(slot, heap_start);
+                }
+            }
+        }
+    }
+
+    /// records an acceleration structure build followed by a uav barrier, so following builds or traces can read it
+    fn build_acceleration_structure(
+        &self,
+        resource: &ID3D12Resource,
+        scratch: &ID3D12Resource,
+        inputs: D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_INPUTS
+    ) {
+        let cmd4: ID3D12GraphicsCommandList4 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: acceleration structure builds require ID3D12GraphicsCommandList4");
+        let barrier = D3D12_RESOURCE_BARRIER {
+            Type: D3D12_RESOURCE_BARRIER_TYPE_UAV,
+            Flags: D3D12_RESOURCE_BARRIER_FLAG_NONE,
+            Anonymous: D3D12_RESOURCE_BARRIER_0 {
+                UAV: std::mem::ManuallyDrop::new(D3D12_RESOURCE_UAV_BARRIER {
+                    pResource: Some(resource.clone())
+                })
+            },
+        };
+        unsafe {
+            cmd4.BuildRaytracingAccelerationStructure(&D3D12_BUILD_RAYTRACING_ACCELERATION_STRUCTURE_DESC {
+                DestAccelerationStructureData: resource.GetGPUVirtualAddress(),
+                Inputs: inputs,
+                SourceAccelerationStructureData: 0,
+                ScratchAccelerationStructureData: scratch.GetGPUVirtualAddress(),
+            }, &[]);
+            self.cmd().ResourceBarrier(&[barrier.clone()]);
+            let _: D3D12_RESOURCE_UAV_BARRIER = std::mem::ManuallyDrop::into_inner(barrier.Anonymous.UAV);
+        }
+    }
+
+    fn execute_indirect(
+        &self,
+        signature: &ID3D12CommandSignature,
+        args: &Buffer,
+        args_offset: usize,
+        max_draw_count: u32,
+        count_buffer: Option<&Buffer>,
+        count_offset: usize
+    ) {
+        unsafe {
+            if let Some(count_buffer) = count_buffer {
+                self.cmd().ExecuteIndirect(
+                    signature,
+                    max_draw_count,
+                    &args.resource,
+                    args_offset as u64,
+                    &count_buffer.resource,
+                    count_offset as u64
+                );
+            }
+            else {
+                self.cmd().ExecuteIndirect(
+                    signature,
+                    max_draw_count,
+                    &args.resource,
+                    args_offset as u64,
+                    None,
+                    0
+                );
+            }
+        }
+    }
+
+    fn push_barrier(&mut self, barrier: D3D12_RESOURCE_BARRIER) {
+        unsafe {
+            let bb = self.bb_index;
+            self.command_list[bb].ResourceBarrier(&[barrier.clone()]);
+            self.in_flight_barriers[bb].push(barrier);
+        }
+    }
+
+    fn push_split_barrier(&mut self, barrier: &TransitionBarrier<Device>, flags: D3D12_RESOURCE_BARRIER_FLAGS) {
+        let resource = if let Some(tex) = &barrier.texture {
+            &tex.resource
+        }
+        else if let Some(buf) = &barrier.buffer {
+            &buf.resource
+        }
+        else {
+            return;
+        };
+        let mut d3d12_barrier = transition_barrier(
+            resource,
+            to_d3d12_resource_state(barrier.state_before),
+            to_d3d12_resource_state(barrier.state_after),
+        );
+        d3d12_barrier.Flags = flags;
+        // each half of the split barrier holds its own resource reference, so both are released independently
+        self.push_barrier(d3d12_barrier);
+    }
+
     fn drop_complete_in_flight_barriers(&mut self, bb: usize) {
         let size = self.in_flight_barriers[bb].len();
         for i in (0..size).rev() {
@@ -2357,6 +5501,34 @@ impl CmdBuf {
         }
         self.in_flight_barriers[bb].clear();
     }
+
+    /// returns a free readback buffer of at least `size` bytes whose last copy has completed on the gpu or creates a
+    /// new one, the buffer is owned by the `ReadBackRequest` it is returned in until the request is dropped
+    fn get_read_back_buffer(
+        &self,
+        device: &ID3D12Device,
+        size: u64,
+        fence: &ID3D12Fence,
+        fence_value: u64
+    ) -> result::Result<Arc<ReadBackBuffer>, super::Error> {
+        let mut free_list = self.free_read_back_buffers.lock().unwrap();
+        let free = free_list.iter().position(|buffer| unsafe {
+            buffer.size >= size && buffer.fence.GetCompletedValue() >= buffer.fence_value
+        });
+        let (size, resource) = match free {
+            Some(index) => {
+                let buffer = free_list.swap_remove(index);
+                (buffer.size, buffer.resource)
+            }
+            None => (size, create_read_back_buffer(device, size)?)
+        };
+        Ok(Arc::new(ReadBackBuffer {
+            size,
+            resource,
+            fence: Mutex::new((fence.clone(), fence_value)),
+            free_list: self.free_read_back_buffers.clone(),
+        }))
+    }
 }
 
 impl super::CmdBuf<Device> for CmdBuf {
@@ -2373,8 +5545,14 @@ impl super::CmdBuf<Device> for CmdBuf {
                     .Reset(&self.command_allocator[bb], None)
                     .expect("hotline_rs::gfx::d3d12: failed to reset command_list!");
             }
+            // the list is open again, it must be closed before it can be reset
+            self.needs_reset[bb] = false;
         }
         self.drop_complete_in_flight_barriers(prev_bb);
+        self.timer_stack.clear();
+        self.frame_fence = Some((swap_chain.fence.clone(), swap_chain.fence_last_signalled_value + 1));
+        *self.bound_heaps.lock().unwrap() = [None, None];
+        *self.bindless.lock().unwrap() = BindlessBinding::default();
     }
 
     fn close(&mut self) -> result::Result<(), super::Error> {
@@ -2388,6 +5566,11 @@ impl super::CmdBuf<Device> for CmdBuf {
                 msg: "mismatch begin/end events called on cmdbuf!".to_string()
             })
         }
+        else if !self.timer_stack.is_empty() {
+            Err(super::Error {
+                msg: "mismatch begin/end timers called on cmdbuf!".to_string()
+            })
+        }
         else {
             Ok(())
         }
@@ -2397,6 +5580,10 @@ impl super::CmdBuf<Device> for CmdBuf {
         self.bb_index as u32
     }
 
+    fn get_queue_type(&self) -> super::QueueType {
+        self.queue_type
+    }
+
     fn begin_render_pass(&self, render_pass: &RenderPass) {
         unsafe {
             let cmd4: ID3D12GraphicsCommandList4 = self.cmd().cast().unwrap();
@@ -2435,64 +5622,308 @@ impl super::CmdBuf<Device> for CmdBuf {
         self.event_stack_count -= 1;
     }
 
+    fn uav_barrier(&mut self, texture: Option<&Texture>, buffer: Option<&Buffer>) {
+        let resource = texture.map(|tex| tex.resource.clone()).or_else(|| buffer.map(|buf| buf.resource.clone()));
+        let barrier = D3D12_RESOURCE_BARRIER {
+            Type: D3D12_RESOURCE_BARRIER_TYPE_UAV,
+            Flags: D3D12_RESOURCE_BARRIER_FLAG_NONE,
+            Anonymous: D3D12_RESOURCE_BARRIER_0 {
+                UAV: std::mem::ManuallyDrop::new(D3D12_RESOURCE_UAV_BARRIER {
+                    pResource: resource
+                })
+            },
+        };
+        unsafe {
+            self.cmd().ResourceBarrier(&[barrier.clone()]);
+            let _: D3D12_RESOURCE_UAV_BARRIER = std::mem::ManuallyDrop::into_inner(barrier.Anonymous.UAV);
+        }
+    }
+
+    fn copy_texture_region(
+        &self,
+        dst: &Texture,
+        dst_subresource: u32,
+        dst_origin: super::Size3,
+        src: &Texture,
+        src_subresource: u32,
+        src_region: Option<super::TextureRegion>) {
+        let dst_location = D3D12_TEXTURE_COPY_LOCATION {
+            pResource: Some(dst.resource.clone()),
+            Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+            Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                SubresourceIndex: dst_subresource,
+            },
+        };
+        let src_location = D3D12_TEXTURE_COPY_LOCATION {
+            pResource: Some(src.resource.clone()),
+            Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+            Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                SubresourceIndex: src_subresource,
+            },
+        };
+        let src_box = src_region.map(|region| D3D12_BOX {
+            left: region.left,
+            top: region.top,
+            front: region.front,
+            right: region.right,
+            bottom: region.bottom,
+            back: region.back,
+        });
+        unsafe {
+            self.cmd().CopyTextureRegion(
+                &dst_location,
+                dst_origin.x,
+                dst_origin.y,
+                dst_origin.z,
+                &src_location,
+                src_box.as_ref().map_or(std::ptr::null(), |src_box| src_box as *const D3D12_BOX)
+            );
+        }
+    }
+
+    fn copy_texture(&self, dst: &Texture, src: &Texture) {
+        unsafe {
+            self.cmd().CopyResource(&dst.resource, &src.resource);
+        }
+    }
+
+    fn copy_buffer_region(&self, dst: &Buffer, dst_offset: usize, src: &Buffer, src_offset: usize, size: usize) {
+        unsafe {
+            self.cmd().CopyBufferRegion(&dst.resource, dst_offset as u64, &src.resource, src_offset as u64, size as u64);
+        }
+    }
+
+    fn clear_unordered_access_view_uint(
+        &self,
+        texture: Option<&Texture>,
+        buffer: Option<&Buffer>,
+        heap: &Heap,
+        values: [u32; 4]) -> result::Result<(), super::Error> {
+        let (resource, gpu_handle, cpu_handle) = self.get_uav_clear_handles(texture, buffer, heap)?;
+        unsafe {
+            self.cmd().ClearUnorderedAccessViewUint(gpu_handle, cpu_handle, &resource, values.as_ptr(), &[]);
+        }
+        Ok(())
+    }
+
+    fn clear_unordered_access_view_float(
+        &self,
+        texture: Option<&Texture>,
+        buffer: Option<&Buffer>,
+        heap: &Heap,
+        values: [f32; 4]) -> result::Result<(), super::Error> {
+        let (resource, gpu_handle, cpu_handle) = self.get_uav_clear_handles(texture, buffer, heap)?;
+        unsafe {
+            self.cmd().ClearUnorderedAccessViewFloat(gpu_handle, cpu_handle, &resource, values.as_ptr(), &[]);
+        }
+        Ok(())
+    }
+
+    fn begin_timer(&mut self, name: &str) {
+        let timers = self.timers.clone();
+        let mut timers = timers.lock().unwrap();
+        let query = timers.allocate();
+        unsafe {
+            self.cmd().EndQuery(&timers.heap, D3D12_QUERY_TYPE_TIMESTAMP, query);
+        }
+        self.timer_stack.push((name.to_string(), query));
+    }
+
+    fn end_timer(&mut self) {
+        if let Some((name, query)) = self.timer_stack.pop() {
+            let mut timers = self.timers.lock().unwrap();
+            unsafe {
+                let cmd = &self.command_list[self.bb_index];
+                cmd.EndQuery(&timers.heap, D3D12_QUERY_TYPE_TIMESTAMP, query + 1);
+                cmd.ResolveQueryData(&timers.heap, D3D12_QUERY_TYPE_TIMESTAMP, query, 2, &timers.readback, query as u64 * 8);
+            }
+            if let Some((fence, fence_value)) = &self.frame_fence {
+                timers.pending.push(PendingTimer {
+                    name,
+                    query,
+                    fence: fence.clone(),
+                    fence_value: *fence_value
+                });
+            }
+        }
+    }
+
+    fn begin_occlusion_query(&mut self, index: u32) {
+        debug_assert!(index < MAX_OCCLUSION_QUERIES,
+            "hotline_rs::gfx::d3d12: occlusion query index ({}) out of range", index);
+        let queries = self.occlusion_queries.lock().unwrap();
+        unsafe {
+            self.cmd().BeginQuery(&queries.heap, D3D12_QUERY_TYPE_BINARY_OCCLUSION, index);
+        }
+    }
+
+    fn end_occlusion_query(&mut self, index: u32) {
+        debug_assert!(index < MAX_OCCLUSION_QUERIES,
+            "hotline_rs::gfx::d3d12: occlusion query index ({}) out of range", index);
+        let mut queries = self.occlusion_queries.lock().unwrap();
+        unsafe {
+            let cmd = &self.command_list[self.bb_index];
+            cmd.EndQuery(&queries.heap, D3D12_QUERY_TYPE_BINARY_OCCLUSION, index);
+            cmd.ResolveQueryData(
+                &queries.heap, D3D12_QUERY_TYPE_BINARY_OCCLUSION, index, 1, &queries.readback, index as u64 * 8);
+        }
+        if let Some((fence, fence_value)) = &self.frame_fence {
+            queries.pending.push(PendingOcclusionQuery {
+                index,
+                fence: fence.clone(),
+                fence_value: *fence_value
+            });
+        }
+    }
+
     fn transition_barrier(&mut self, barrier: &TransitionBarrier<Device>) {
         if let Some(tex) = &barrier.texture {
-            let barrier = transition_barrier(
+            self.push_barrier(transition_barrier(
                 &tex.resource,
                 to_d3d12_resource_state(barrier.state_before),
                 to_d3d12_resource_state(barrier.state_after),
-            );
-            unsafe {
-                let bb = self.bb_index;
-                self.command_list[bb].ResourceBarrier(&[barrier.clone()]);
-                self.in_flight_barriers[bb].push(barrier);
+            ));
+            *tex.state.lock().unwrap() = barrier.state_after;
+        }
+        else if let Some(buf) = &barrier.buffer {
+            self.push_barrier(transition_barrier(
+                &buf.resource,
+                to_d3d12_resource_state(barrier.state_before),
+                to_d3d12_resource_state(barrier.state_after),
+            ));
+            if let Some(state) = &buf.state {
+                *state.lock().unwrap() = barrier.state_after;
+            }
+        }
+    }
+
+    fn begin_transition(&mut self, barrier: &TransitionBarrier<Device>) {
+        self.push_split_barrier(barrier, D3D12_RESOURCE_BARRIER_FLAG_BEGIN_ONLY);
+    }
+
+    fn end_transition(&mut self, barrier: &TransitionBarrier<Device>) {
+        self.push_split_barrier(barrier, D3D12_RESOURCE_BARRIER_FLAG_END_ONLY);
+        if let Some(tex) = &barrier.texture {
+            *tex.state.lock().unwrap() = barrier.state_after;
+        }
+        else if let Some(state) = barrier.buffer.as_ref().and_then(|buf| buf.state.as_ref()) {
+            *state.lock().unwrap() = barrier.state_after;
+        }
+    }
+
+    fn transition_barrier_range(
+        &mut self,
+        barrier: &TransitionBarrier<Device>,
+        first_subresource: u32,
+        num_subresources: u32,
+    ) {
+        if let Some(tex) = &barrier.texture {
+            for subresource in first_subresource..first_subresource + num_subresources {
+                self.push_barrier(transition_barrier_subresource_index(
+                    &tex.resource,
+                    to_d3d12_resource_state(barrier.state_before),
+                    to_d3d12_resource_state(barrier.state_after),
+                    subresource,
+                ));
+            }
+        }
+    }
+
+    fn transition(&mut self, texture: &Texture, state_after: super::ResourceState) {
+        let state_before = *texture.state.lock().unwrap();
+        if state_before != state_after {
+            self.push_barrier(transition_barrier(
+                &texture.resource,
+                to_d3d12_resource_state(state_before),
+                to_d3d12_resource_state(state_after),
+            ));
+            *texture.state.lock().unwrap() = state_after;
+        }
+    }
+
+    fn transition_buffer(&mut self, buffer: &Buffer, state_after: super::ResourceState) {
+        // upload buffers must remain in the generic read state
+        if let Some(state) = &buffer.state {
+            let state_before = *state.lock().unwrap();
+            if state_before != state_after {
+                self.push_barrier(transition_barrier(
+                    &buffer.resource,
+                    to_d3d12_resource_state(state_before),
+                    to_d3d12_resource_state(state_after),
+                ));
+                *state.lock().unwrap() = state_after;
             }
         }
     }
 
+    fn validate_texture_state(&self, texture: &Texture, expected: super::ResourceState) -> result::Result<(), super::Error> {
+        #[cfg(debug_assertions)]
+        validate_resource_state("texture", *texture.state.lock().unwrap(), expected)?;
+        #[cfg(not(debug_assertions))]
+        let _ = (texture, expected);
+        Ok(())
+    }
+
+    fn validate_buffer_state(&self, buffer: &Buffer, expected: super::ResourceState) -> result::Result<(), super::Error> {
+        #[cfg(debug_assertions)]
+        if let Some(state) = &buffer.state {
+            validate_resource_state("buffer", *state.lock().unwrap(), expected)?;
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = (buffer, expected);
+        Ok(())
+    }
+
     fn transition_barrier_subresource(&mut self, barrier: &TransitionBarrier<Device>, subresource: Subresource) {        
         if let Some(tex) = &barrier.texture {
             let res = match subresource {
                 super::Subresource::Resource => &tex.resource,
                 super::Subresource::ResolveResource => &tex.resolved_resource.as_ref().unwrap()
             };
-            let barrier = transition_barrier(
+            self.push_barrier(transition_barrier(
                 res,
                 to_d3d12_resource_state(barrier.state_before),
                 to_d3d12_resource_state(barrier.state_after),
-            );
-            unsafe {
-                let bb = self.bb_index;
-                self.command_list[bb].ResourceBarrier(&[barrier.clone()]);
-                self.in_flight_barriers[bb].push(barrier);
+            ));
+            if subresource == super::Subresource::Resource {
+                *tex.state.lock().unwrap() = barrier.state_after;
             }
         }
     }
 
     fn set_viewport(&self, viewport: &super::Viewport) {
-        let d3d12_vp = D3D12_VIEWPORT {
+        self.set_viewports(std::slice::from_ref(viewport));
+    }
+
+    fn set_scissor_rect(&self, scissor_rect: &super::ScissorRect) {
+        self.set_scissor_rects(std::slice::from_ref(scissor_rect));
+    }
+
+    fn set_viewports(&self, viewports: &[super::Viewport]) {
+        debug_assert!(viewports.len() <= D3D12_VIEWPORT_AND_SCISSORRECT_OBJECT_COUNT_PER_PIPELINE as usize);
+        let d3d12_vps = viewports.iter().map(|viewport| D3D12_VIEWPORT {
             TopLeftX: viewport.x,
             TopLeftY: viewport.y,
             Width: viewport.width,
             Height: viewport.height,
             MinDepth: viewport.min_depth,
             MaxDepth: viewport.max_depth,
-        };
+        }).collect::<Vec<D3D12_VIEWPORT>>();
         unsafe {
-            self.cmd().RSSetViewports(&[d3d12_vp]);
+            self.cmd().RSSetViewports(&d3d12_vps);
         }
     }
 
-    fn set_scissor_rect(&self, scissor_rect: &super::ScissorRect) {
-        let d3d12_sr = RECT {
+    fn set_scissor_rects(&self, scissor_rects: &[super::ScissorRect]) {
+        debug_assert!(scissor_rects.len() <= D3D12_VIEWPORT_AND_SCISSORRECT_OBJECT_COUNT_PER_PIPELINE as usize);
+        let d3d12_srs = scissor_rects.iter().map(|scissor_rect| RECT {
             left: scissor_rect.left,
             top: scissor_rect.top,
             right: scissor_rect.right,
             bottom: scissor_rect.bottom,
-        };
-        let cmd = &self.command_list[self.bb_index];
+        }).collect::<Vec<RECT>>();
         unsafe {
-            cmd.RSSetScissorRects(&[d3d12_sr]);
+            self.cmd().RSSetScissorRects(&d3d12_srs);
         }
     }
 
@@ -2521,6 +5952,7 @@ impl super::CmdBuf<Device> for CmdBuf {
             cmd.SetPipelineState(&pipeline.pso);
             cmd.IASetPrimitiveTopology(pipeline.topology)
         }
+        self.bind_bindless_table(pipeline.bindless_slot.map(|slot| (slot, false)));
     }
 
     fn set_compute_pipeline(&self, pipeline: &ComputePipeline) {
@@ -2529,11 +5961,23 @@ impl super::CmdBuf<Device> for CmdBuf {
             cmd.SetComputeRootSignature(&pipeline.root_signature);
             cmd.SetPipelineState(&pipeline.pso);
         }
+        self.bind_bindless_table(pipeline.bindless_slot.map(|slot| (slot, true)));
+    }
+
+    fn set_bindless_heap(&self, heap: &Heap) {
+        self.bind_heap(heap);
+        let heap_start = unsafe { heap.heap.GetGPUDescriptorHandleForHeapStart() };
+        let slot = {
+            let mut bindless = self.bindless.lock().unwrap();
+            bindless.heap_start = Some(heap_start);
+            bindless.slot
+        };
+        self.bind_bindless_table(slot);
     }
 
     fn set_compute_heap(&self, slot: u32, heap: &Heap) {
+        self.bind_heap(heap);
         unsafe {
-            self.cmd().SetDescriptorHeaps(&[Some(heap.heap.clone())]);
             self.cmd().SetComputeRootDescriptorTable(
                 slot,
                 heap.heap.GetGPUDescriptorHandleForHeapStart(),
@@ -2542,32 +5986,77 @@ impl super::CmdBuf<Device> for CmdBuf {
     }
 
     fn set_render_heap(&self, slot: u32, heap: &Heap, offset: usize) {
+        self.bind_heap(heap);
         unsafe {
-            self.cmd().SetDescriptorHeaps(&[Some(heap.heap.clone())]);
-
             let mut base = heap.heap.GetGPUDescriptorHandleForHeapStart();
             base.ptr += (offset * heap.increment_size) as u64;
 
-            self.cmd().SetGraphicsRootDescriptorTable(slot, base);
+            self.cmd().SetGraphicsRootDescriptorTable(slot, base);
+        }
+    }
+
+    fn set_marker(&self, colour: u32, name: &str) {
+        let cmd = &self.command_list[self.bb_index];
+        if self.pix.is_some() {
+            self.pix.unwrap().set_marker_on_command_list(cmd, colour as u64, name);
+        }
+    }
+
+    fn push_constants<T: Sized>(&self, slot: u32, num_values: u32, dest_offset: u32, data: &[T]) {
+        let cmd = self.cmd();
+        unsafe {
+            cmd.SetGraphicsRoot32BitConstants(
+                slot,
+                num_values,
+                data.as_ptr() as *const ::core::ffi::c_void,
+                dest_offset,
+            )
+        }
+    }
+
+    fn set_constant_buffer(&self, slot: u32, buffer: &Buffer, offset: usize) {
+        debug_assert!(offset % D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as usize == 0,
+            "hotline_rs::gfx::d3d12: constant buffer offset ({}) must be a multiple of 256 bytes", offset);
+        unsafe {
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetGraphicsRootConstantBufferView(slot, address);
+        }
+    }
+
+    fn set_compute_constant_buffer(&self, slot: u32, buffer: &Buffer, offset: usize) {
+        debug_assert!(offset % D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as usize == 0,
+            "hotline_rs::gfx::d3d12: constant buffer offset ({}) must be a multiple of 256 bytes", offset);
+        unsafe {
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetComputeRootConstantBufferView(slot, address);
+        }
+    }
+
+    fn set_root_shader_resource(&self, slot: u32, buffer: &Buffer, offset: usize) {
+        unsafe {
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetGraphicsRootShaderResourceView(slot, address);
+        }
+    }
+
+    fn set_root_unordered_access(&self, slot: u32, buffer: &Buffer, offset: usize) {
+        unsafe {
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetGraphicsRootUnorderedAccessView(slot, address);
         }
     }
 
-    fn set_marker(&self, colour: u32, name: &str) {
-        let cmd = &self.command_list[self.bb_index];
-        if self.pix.is_some() {
-            self.pix.unwrap().set_marker_on_command_list(cmd, colour as u64, name);
+    fn set_compute_root_shader_resource(&self, slot: u32, buffer: &Buffer, offset: usize) {
+        unsafe {
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetComputeRootShaderResourceView(slot, address);
         }
     }
 
-    fn push_constants<T: Sized>(&self, slot: u32, num_values: u32, dest_offset: u32, data: &[T]) {
-        let cmd = self.cmd();
+    fn set_compute_root_unordered_access(&self, slot: u32, buffer: &Buffer, offset: usize) {
         unsafe {
-            cmd.SetGraphicsRoot32BitConstants(
-                slot,
-                num_values,
-                data.as_ptr() as *const ::core::ffi::c_void,
-                dest_offset,
-            )
+            let address = buffer.resource.GetGPUVirtualAddress() + offset as u64;
+            self.cmd().SetComputeRootUnorderedAccessView(slot, address);
         }
     }
 
@@ -2602,12 +6091,121 @@ impl super::CmdBuf<Device> for CmdBuf {
         }
     }
 
+    fn draw_indirect(
+        &self,
+        args: &Buffer,
+        args_offset: usize,
+        max_draw_count: u32,
+        count_buffer: Option<&Buffer>,
+        count_offset: usize
+    ) {
+        self.execute_indirect(&self.draw_indirect_signature, args, args_offset, max_draw_count, count_buffer, count_offset);
+    }
+
+    fn draw_indexed_indirect(
+        &self,
+        args: &Buffer,
+        args_offset: usize,
+        max_draw_count: u32,
+        count_buffer: Option<&Buffer>,
+        count_offset: usize
+    ) {
+        self.execute_indirect(
+            &self.draw_indexed_indirect_signature, args, args_offset, max_draw_count, count_buffer, count_offset);
+    }
+
     fn dispatch(&self, group_count: Size3, _thread_count: Size3) {
         unsafe {
             self.cmd().Dispatch(group_count.x, group_count.y, group_count.z);
         }
     }
 
+    fn dispatch_indirect(&self, args: &Buffer, args_offset: usize) {
+        self.execute_indirect(&self.dispatch_indirect_signature, args, args_offset, 1, None, 0);
+    }
+
+    fn build_raytracing_blas(&mut self, blas: &RaytracingBLAS) {
+        let inputs = to_d3d12_blas_inputs(&blas.geometry, blas.flags);
+        self.build_acceleration_structure(&blas.resource, &blas.scratch, inputs);
+    }
+
+    fn build_raytracing_tlas(&mut self, tlas: &RaytracingTLAS) {
+        let inputs = unsafe {
+            to_d3d12_tlas_inputs(tlas.instances.GetGPUVirtualAddress(), tlas.num_instances, tlas.flags)
+        };
+        self.build_acceleration_structure(&tlas.resource, &tlas.scratch, inputs);
+    }
+
+    fn set_shading_rate(&self, rate: super::ShadingRate, combiners: [super::ShadingRateCombiner; 2]) {
+        if self.variable_shading_rate_tier == D3D12_VARIABLE_SHADING_RATE_TIER_NOT_SUPPORTED.0 as u32 {
+            return;
+        }
+        let cmd5: ID3D12GraphicsCommandList5 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: variable rate shading requires ID3D12GraphicsCommandList5");
+        let combiners = combiners.map(to_d3d12_shading_rate_combiner);
+        unsafe {
+            cmd5.RSSetShadingRate(to_d3d12_shading_rate(rate), combiners.as_ptr());
+        }
+    }
+
+    fn set_shading_rate_image(&self, texture: Option<&Texture>) {
+        if self.variable_shading_rate_tier < D3D12_VARIABLE_SHADING_RATE_TIER_2.0 as u32 {
+            return;
+        }
+        let cmd5: ID3D12GraphicsCommandList5 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: variable rate shading requires ID3D12GraphicsCommandList5");
+        let resource = texture.map(|texture| texture.resource.clone());
+        unsafe {
+            cmd5.RSSetShadingRateImage(&resource);
+        }
+    }
+
+    fn set_raytracing_pipeline(&self, pipeline: &RaytracingPipeline) {
+        let cmd4: ID3D12GraphicsCommandList4 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: ray tracing requires ID3D12GraphicsCommandList4");
+        unsafe {
+            cmd4.SetComputeRootSignature(&pipeline.root_signature);
+            cmd4.SetPipelineState1(&pipeline.state_object);
+        }
+        self.bind_bindless_table(pipeline.bindless_slot.map(|slot| (slot, true)));
+    }
+
+    fn dispatch_rays(&self, table: &RaytracingShaderBindingTable, size: Size3) {
+        let cmd4: ID3D12GraphicsCommandList4 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: ray tracing requires ID3D12GraphicsCommandList4");
+        unsafe {
+            let start = table.resource.GetGPUVirtualAddress();
+            cmd4.DispatchRays(&D3D12_DISPATCH_RAYS_DESC {
+                RayGenerationShaderRecord: D3D12_GPU_VIRTUAL_ADDRESS_RANGE {
+                    StartAddress: start,
+                    SizeInBytes: table.record_size,
+                },
+                MissShaderTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+                    StartAddress: start + table.miss_offset,
+                    SizeInBytes: table.record_size * table.num_miss_shaders,
+                    StrideInBytes: table.record_size,
+                },
+                HitGroupTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE {
+                    StartAddress: start + table.hit_group_offset,
+                    SizeInBytes: table.record_size * table.num_hit_groups,
+                    StrideInBytes: table.record_size,
+                },
+                CallableShaderTable: D3D12_GPU_VIRTUAL_ADDRESS_RANGE_AND_STRIDE::default(),
+                Width: size.x,
+                Height: size.y,
+                Depth: size.z,
+            });
+        }
+    }
+
+    fn dispatch_mesh(&self, group_count: Size3) {
+        let cmd: ID3D12GraphicsCommandList6 = self.cmd().cast()
+            .expect("hotline_rs::gfx::d3d12: dispatch_mesh requires ID3D12GraphicsCommandList6");
+        unsafe {
+            cmd.DispatchMesh(group_count.x, group_count.y, group_count.z);
+        }
+    }
+
     fn read_back_backbuffer(&mut self, swap_chain: &SwapChain) -> ReadBackRequest {
         let bb = self.bb_index;
         let bbz = self.bb_index as u32;
@@ -2636,16 +6234,7 @@ impl super::CmdBuf<Device> for CmdBuf {
                 pResource: Some(swap_chain.readback_buffer.clone().unwrap()),
                 Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
                 Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
-                    PlacedFootprint: D3D12_PLACED_SUBRESOURCE_FOOTPRINT {
-                        Offset: 0,
-                        Footprint: D3D12_SUBRESOURCE_FOOTPRINT {
-                            Width: swap_chain.width as u32,
-                            Height: swap_chain.height as u32,
-                            Depth: 1,
-                            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
-                            RowPitch: (swap_chain.width * 4) as u32,
-                        },
-                    },
+                    PlacedFootprint: swap_chain.readback_footprints.layouts[0],
                 },
             };
 
@@ -2663,11 +6252,82 @@ impl super::CmdBuf<Device> for CmdBuf {
 
             ReadBackRequest {
                 resource: Some(swap_chain.readback_buffer.clone().unwrap()),
-                fence_value: swap_chain.frame_index as u64,
-                size: (swap_chain.width * swap_chain.height * 4) as usize,
-                row_pitch: (swap_chain.width * 4) as usize,
-                slice_pitch: (swap_chain.width * swap_chain.height * 4) as usize,
+                fence: swap_chain.fence.clone(),
+                fence_value: swap_chain.fence_last_signalled_value + 1,
+                size: swap_chain.readback_footprints.total_size as usize,
+                row_pitch: swap_chain.readback_footprints.layouts[0].Footprint.RowPitch as usize,
+                slice_pitch: swap_chain.readback_footprints.total_size as usize,
+                buffer: None,
+            }
+        }
+    }
+
+    fn read_back_texture(
+        &mut self,
+        swap_chain: &SwapChain,
+        texture: &Texture,
+        subresource: u32
+    ) -> result::Result<ReadBackRequest, super::Error> {
+        #[cfg(debug_assertions)]
+        validate_resource_state("read back texture", *texture.state.lock().unwrap(), super::ResourceState::CopySrc)?;
+        unsafe {
+            let mut device: Option<ID3D12Device> = None;
+            self.cmd().GetDevice(&mut device)?;
+            let device = device.unwrap();
+
+            // 3D textures have a single array layer with depth slices in each subresource
+            let desc = texture.resource.GetDesc();
+            let array_size = if desc.Dimension == D3D12_RESOURCE_DIMENSION_TEXTURE3D {
+                1
             }
+            else {
+                desc.DepthOrArraySize as u32
+            };
+            let num_subresources = desc.MipLevels as u32 * array_size;
+            if subresource >= num_subresources {
+                return Err(super::Error {
+                    msg: format!(
+                        "hotline_rs::gfx::d3d12: read back subresource: ({}) is out of range, texture has ({}) subresources",
+                        subresource, num_subresources
+                    ),
+                });
+            }
+
+            // get the footprint of the subresource so we copy with the row pitch alignment the device requires
+            let footprints = get_copyable_footprints(&device, &desc, subresource, 1);
+            let footprint = footprints.layouts[0];
+            let total_size = footprints.total_size;
+            let fence_value = swap_chain.fence_last_signalled_value + 1;
+            let readback_buffer = self.get_read_back_buffer(&device, total_size, &swap_chain.fence, fence_value)?;
+
+            let src = D3D12_TEXTURE_COPY_LOCATION {
+                pResource: Some(texture.resource.clone()),
+                Type: D3D12_TEXTURE_COPY_TYPE_SUBRESOURCE_INDEX,
+                Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                    SubresourceIndex: subresource,
+                },
+            };
+
+            let dst = D3D12_TEXTURE_COPY_LOCATION {
+                pResource: Some(readback_buffer.resource.clone()),
+                Type: D3D12_TEXTURE_COPY_TYPE_PLACED_FOOTPRINT,
+                Anonymous: D3D12_TEXTURE_COPY_LOCATION_0 {
+                    PlacedFootprint: footprint,
+                },
+            };
+
+            self.cmd().CopyTextureRegion(&dst, 0, 0, 0, &src, std::ptr::null_mut());
+
+            let row_pitch = footprint.Footprint.RowPitch as usize;
+            Ok(ReadBackRequest {
+                resource: Some(readback_buffer.resource.clone()),
+                fence: swap_chain.fence.clone(),
+                fence_value,
+                size: total_size as usize,
+                row_pitch,
+                slice_pitch: row_pitch * footprints.num_rows[0] as usize,
+                buffer: Some(readback_buffer),
+            })
         }
     }
 
@@ -2685,7 +6345,7 @@ impl super::CmdBuf<Device> for CmdBuf {
             }
             else {
                 return Err(super::Error {
-                    msg: format!("t")
+                    msg: format!("hotline_rs::gfx::d3d12: texture has no resolve resource, only msaa textures can be resolved")
                 })
             }
         }
@@ -2714,6 +6374,10 @@ impl super::Buffer<Device> for Buffer {
         self.uav_index
     }
 
+    fn get_state(&self) -> Option<ResourceState> {
+        self.state.as_ref().map(|state| *state.lock().unwrap())
+    }
+
     fn map(&self, info: &MapInfo) -> *mut u8 {
         let range = D3D12_RANGE {
             Begin: info.read_start,
@@ -2737,6 +6401,44 @@ impl super::Buffer<Device> for Buffer {
     }
 }
 
+impl super::RingBuffer<Device> for RingBuffer {
+    fn reset(&mut self, swap_chain: &SwapChain) {
+        self.bb_index = swap_chain.bb_index;
+        self.offset = 0;
+    }
+
+    fn allocate(&mut self, size: usize) -> result::Result<RingBufferAllocation, super::Error> {
+        let aligned_size = super::align_pow2(
+            size as u64, D3D12_CONSTANT_BUFFER_DATA_PLACEMENT_ALIGNMENT as u64) as usize;
+        if self.offset + aligned_size > self.frame_size {
+            return Err(super::Error {
+                msg: format!(
+                    "hotline_rs::gfx::d3d12: ring buffer is full! frame size: ({}) bytes, requested: ({}) bytes",
+                    self.frame_size, aligned_size
+                ),
+            });
+        }
+        let frame_offset = self.bb_index * self.frame_size + self.offset;
+        self.offset += aligned_size;
+        unsafe {
+            Ok(RingBufferAllocation {
+                cpu_ptr: self.cpu_address.add(frame_offset),
+                gpu_address: self.gpu_address + frame_offset as u64,
+                size,
+            })
+        }
+    }
+
+    fn push<T: Sized>(&mut self, data: &[T]) -> result::Result<RingBufferAllocation, super::Error> {
+        let size_bytes = data.len() * std::mem::size_of::<T>();
+        let alloc = self.allocate(size_bytes)?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr() as *const u8, alloc.cpu_ptr, size_bytes);
+        }
+        Ok(alloc)
+    }
+}
+
 // public accessors for texture
 pub fn get_texture_shared_handle(tex: &Texture) -> &Option<HANDLE> {
     &tex.shared_handle
@@ -2760,17 +6462,28 @@ impl super::Texture<Device> for Texture {
         self.clone()
     }
 
+    fn get_state(&self) -> ResourceState {
+        *self.state.lock().unwrap()
+    }
+
     fn is_resolvable(&self) -> bool {
         self.resolved_resource.is_some()
     }
 }
 
 impl super::ReadBackRequest<Device> for ReadBackRequest {
-    fn is_complete(&self, swap_chain: &SwapChain) -> bool {
-        if swap_chain.frame_index as u64 > self.fence_value + 1 {
-            return true;
+    fn is_complete(&self, _swap_chain: &SwapChain) -> bool {
+        unsafe {
+            self.fence.GetCompletedValue() >= self.fence_value
+        }
+    }
+
+    fn set_fence(&mut self, fence: &Fence, value: u64) {
+        self.fence = fence.fence.clone();
+        self.fence_value = value;
+        if let Some(buffer) = &self.buffer {
+            *buffer.fence.lock().unwrap() = (fence.fence.clone(), value);
         }
-        false
     }
 
     fn map(&self, info: &MapInfo) -> result::Result<ReadBackData, super::Error> {
@@ -2815,6 +6528,384 @@ impl super::ReadBackRequest<Device> for ReadBackRequest {
 
 impl super::ComputePipeline<Device> for ComputePipeline {}
 
+impl PipelineLibrary {
+    /// load a pipeline from the library or create it and store it if it does not exist
+    fn load_graphics_pipeline(
+        &self,
+        device: &ID3D12Device,
+        name: &str,
+        desc: &D3D12_GRAPHICS_PIPELINE_STATE_DESC
+    ) -> result::Result<ID3D12PipelineState, super::Error> {
+        let wide_name = to_wide_null_terminated(name);
+        unsafe {
+            let pso = self.library.LoadGraphicsPipeline::<_, ID3D12PipelineState>(PCWSTR(wide_name.as_ptr()), desc);
+            if let Ok(pso) = pso {
+                return Ok(pso);
+            }
+            let pso = device.CreateGraphicsPipelineState(desc)?;
+            // storing fails if the name exists with a different desc, in which case the pipeline is just not cached
+            let _ = self.library.StorePipeline(PCWSTR(wide_name.as_ptr()), &pso);
+            Ok(pso)
+        }
+    }
+
+    /// load a pipeline from the library or create it and store it if it does not exist
+    fn load_compute_pipeline(
+        &self,
+        device: &ID3D12Device,
+        name: &str,
+        desc: &D3D12_COMPUTE_PIPELINE_STATE_DESC
+    ) -> result::Result<ID3D12PipelineState, super::Error> {
+        let wide_name = to_wide_null_terminated(name);
+        unsafe {
+            let pso = self.library.LoadComputePipeline::<_, ID3D12PipelineState>(PCWSTR(wide_name.as_ptr()), desc);
+            if let Ok(pso) = pso {
+                return Ok(pso);
+            }
+            let pso = device.CreateComputePipelineState(desc)?;
+            let _ = self.library.StorePipeline(PCWSTR(wide_name.as_ptr()), &pso);
+            Ok(pso)
+        }
+    }
+}
+
+impl Timers {
+    fn create(device: &ID3D12Device, command_queue: &ID3D12CommandQueue) -> result::Result<Timers, super::Error> {
+        unsafe {
+            let mut heap: Option<ID3D12QueryHeap> = None;
+            device.CreateQueryHeap(&D3D12_QUERY_HEAP_DESC {
+                Type: D3D12_QUERY_HEAP_TYPE_TIMESTAMP,
+                Count: MAX_TIMESTAMP_QUERIES,
+                NodeMask: 0,
+            }, &mut heap)?;
+            Ok(Timers {
+                heap: heap.unwrap(),
+                readback: create_read_back_buffer(device, MAX_TIMESTAMP_QUERIES as u64 * 8)?,
+                frequency: command_queue.GetTimestampFrequency()?,
+                next_query: 0,
+                pending: Vec::new(),
+                results: HashMap::new()
+            })
+        }
+    }
+
+    /// allocates a begin and end query, queries are recycled in order so results must be collected within
+    /// `MAX_TIMESTAMP_QUERIES / 2` timers
+    fn allocate(&mut self) -> u32 {
+        let query = self.next_query;
+        self.next_query = (self.next_query + 2) % MAX_TIMESTAMP_QUERIES;
+        query
+    }
+
+    /// reads back the results of pending timers whose frame has completed on the gpu
+    fn update(&mut self) -> result::Result<(), super::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            let range = D3D12_RANGE {
+                Begin: 0,
+                End: MAX_TIMESTAMP_QUERIES as usize * 8,
+            };
+            let mut map_data = std::ptr::null_mut();
+            self.readback.Map(0, &range, &mut map_data)?;
+            let timestamps = map_data as *const u64;
+            let frequency = self.frequency as f64;
+            let results = &mut self.results;
+            self.pending.retain(|timer| {
+                if timer.fence.GetCompletedValue() < timer.fence_value {
+                    return true;
+                }
+                let begin = *timestamps.add(timer.query as usize);
+                let end = *timestamps.add(timer.query as usize + 1);
+                results.insert(timer.name.to_string(), end.saturating_sub(begin) as f64 / frequency * 1000.0);
+                false
+            });
+            self.readback.Unmap(0, &D3D12_RANGE { Begin: 0, End: 0 });
+        }
+        Ok(())
+    }
+}
+
+impl OcclusionQueries {
+    fn create(device: &ID3D12Device) -> result::Result<OcclusionQueries, super::Error> {
+        unsafe {
+            let mut heap: Option<ID3D12QueryHeap> = None;
+            device.CreateQueryHeap(&D3D12_QUERY_HEAP_DESC {
+                Type: D3D12_QUERY_HEAP_TYPE_OCCLUSION,
+                Count: MAX_OCCLUSION_QUERIES,
+                NodeMask: 0,
+            }, &mut heap)?;
+            Ok(OcclusionQueries {
+                heap: heap.unwrap(),
+                readback: create_read_back_buffer(device, MAX_OCCLUSION_QUERIES as u64 * 8)?,
+                pending: Vec::new(),
+                results: vec![false; MAX_OCCLUSION_QUERIES as usize],
+            })
+        }
+    }
+
+    /// reads back the result of pending queries whose frame has completed on the gpu, a non-zero sample count means
+    /// the query passed. slots which are resolved again in a frame still in flight keep their previous result
+    fn update(&mut self) -> result::Result<(), super::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            let completed = self.pending.iter()
+                .map(|query| query.fence.GetCompletedValue() >= query.fence_value)
+                .collect::<Vec<bool>>();
+            let in_flight = self.pending.iter().zip(&completed)
+                .filter(|(_, complete)| !**complete)
+                .map(|(query, _)| query.index)
+                .collect::<HashSet<u32>>();
+            let range = D3D12_RANGE {
+                Begin: 0,
+                End: MAX_OCCLUSION_QUERIES as usize * 8,
+            };
+            let mut map_data = std::ptr::null_mut();
+            self.readback.Map(0, &range, &mut map_data)?;
+            let samples = map_data as *const u64;
+            for (query, complete) in std::mem::take(&mut self.pending).into_iter().zip(completed) {
+                if !complete {
+                    self.pending.push(query);
+                }
+                else if !in_flight.contains(&query.index) {
+                    self.results[query.index as usize] = *samples.add(query.index as usize) != 0;
+                }
+            }
+            self.readback.Unmap(0, &D3D12_RANGE { Begin: 0, End: 0 });
+        }
+        Ok(())
+    }
+}
+
+impl UploadQueue {
+    fn create(device: &ID3D12Device) -> result::Result<UploadQueue, super::Error> {
+        unsafe {
+            let queue = device.CreateCommandQueue(&D3D12_COMMAND_QUEUE_DESC {
+                Type: D3D12_COMMAND_LIST_TYPE_COPY,
+                NodeMask: 1,
+                ..Default::default()
+            })?;
+            let allocator = device.CreateCommandAllocator(D3D12_COMMAND_LIST_TYPE_COPY)?;
+            let list = device.CreateCommandList(0, D3D12_COMMAND_LIST_TYPE_COPY, &allocator, None)?;
+            let transition_allocator = device.CreateCommandAllocator(D3D12_COMMAND_LIST_TYPE_DIRECT)?;
+            let transition_list = device.CreateCommandList(0, D3D12_COMMAND_LIST_TYPE_DIRECT, &transition_allocator, None)?;
+
+            // staging ring stays mapped for its lifetime
+            let mut ring: Option<ID3D12Resource> = None;
+            device.CreateCommittedResource(
+                &D3D12_HEAP_PROPERTIES {
+                    Type: D3D12_HEAP_TYPE_UPLOAD,
+                    ..Default::default()
+                },
+                D3D12_HEAP_FLAG_NONE,
+                &D3D12_RESOURCE_DESC {
+                    Dimension: D3D12_RESOURCE_DIMENSION_BUFFER,
+                    Alignment: 0,
+                    Width: UPLOAD_RING_SIZE,
+                    Height: 1,
+                    DepthOrArraySize: 1,
+                    MipLevels: 1,
+                    Format: DXGI_FORMAT_UNKNOWN,
+                    SampleDesc: DXGI_SAMPLE_DESC {
+                        Count: 1,
+                        Quality: 0,
+                    },
+                    Layout: D3D12_TEXTURE_LAYOUT_ROW_MAJOR,
+                    Flags: D3D12_RESOURCE_FLAG_NONE,
+                },
+                D3D12_RESOURCE_STATE_GENERIC_READ,
+                std::ptr::null(),
+                &mut ring,
+            )?;
+            let ring = ring.unwrap();
+            let mut ring_data = std::ptr::null_mut();
+            ring.Map(0, &D3D12_RANGE { Begin: 0, End: 0 }, &mut ring_data)?;
+
+            Ok(UploadQueue {
+                queue,
+                allocator,
+                list,
+                transition_allocator,
+                transition_list,
+                transitions: Vec::new(),
+                fence: Fence::create(device)?,
+                fence_value: 0,
+                open: true,
+                pending: false,
+                upload_buffers: Vec::new(),
+                in_flight_upload_buffers: Vec::new(),
+                ring,
+                ring_data: ring_data as *mut u8,
+                ring_head: 0,
+                ring_regions: VecDeque::new()
+            })
+        }
+    }
+
+    /// opens the lists for recording, the allocators can only be reset once the previous batch has completed
+    fn open(&mut self) -> result::Result<(), super::Error> {
+        if !self.open {
+            self.fence.wait_cpu(self.fence_value)?;
+            unsafe {
+                self.allocator.Reset()?;
+                self.list.Reset(&self.allocator, None)?;
+                self.transition_allocator.Reset()?;
+                self.transition_list.Reset(&self.transition_allocator, None)?;
+            }
+            self.in_flight_upload_buffers.clear();
+            self.open = true;
+        }
+        Ok(())
+    }
+
+    /// keep `upload` alive until the batch completes and transition `resource` into `state_after` after copying
+    fn push_upload(&mut self, upload: ID3D12Resource, resource: &ID3D12Resource, state_after: D3D12_RESOURCE_STATES) {
+        self.upload_buffers.push(upload);
+        // resources decay to common after copy queue access
+        if state_after != D3D12_RESOURCE_STATE_COMMON {
+            self.transitions.push(transition_barrier(resource, D3D12_RESOURCE_STATE_COMMON, state_after));
+        }
+        self.pending = true;
+    }
+
+    /// returns the offset of `size` free bytes in the staging ring, or None if the space is still in use
+    fn try_allocate_ring(&mut self, size: u64) -> Option<u64> {
+        // release regions from the front of the ring which have completed
+        let completed = self.fence.get_completed_value();
+        while let Some((Some(fence_value), _, _)) = self.ring_regions.front() {
+            if *fence_value > completed {
+                break;
+            }
+            self.ring_regions.pop_front();
+        }
+
+        let offset = if let Some((_, tail, _)) = self.ring_regions.front() {
+            let tail = *tail;
+            if self.ring_head >= tail {
+                // free space at the end, or wrap to the start
+                if self.ring_head + size <= UPLOAD_RING_SIZE {
+                    self.ring_head
+                }
+                else if size < tail {
+                    0
+                }
+                else {
+                    return None;
+                }
+            }
+            else if self.ring_head + size < tail {
+                self.ring_head
+            }
+            else {
+                return None;
+            }
+        }
+        else {
+            0
+        };
+
+        self.ring_head = offset + size;
+        self.ring_regions.push_back((None, offset, offset + size));
+        Some(offset)
+    }
+
+    /// drop upload buffers of batches which have completed on the gpu
+    fn release_completed_uploads(&mut self) {
+        let completed = self.fence.get_completed_value();
+        self.in_flight_upload_buffers.retain(|(fence_value, _)| *fence_value > completed);
+    }
+
+    /// submits pending copies to the copy queue and makes `direct_queue` wait for them before transitioning
+    fn submit(&mut self, direct_queue: &ID3D12CommandQueue) -> result::Result<(), super::Error> {
+        if !self.pending {
+            return Ok(());
+        }
+        unsafe {
+            self.list.Close()?;
+            self.queue.ExecuteCommandLists(&[Some(ID3D12CommandList::from(&self.list))]);
+            self.fence_value += 1;
+            self.queue.Signal(&self.fence.fence, self.fence_value)?;
+
+            // gpu side wait, the direct queue does not execute further work until copies complete
+            direct_queue.Wait(&self.fence.fence, self.fence_value)?;
+            if !self.transitions.is_empty() {
+                self.transition_list.ResourceBarrier(&self.transitions);
+            }
+            self.transition_list.Close()?;
+            direct_queue.ExecuteCommandLists(&[Some(ID3D12CommandList::from(&self.transition_list))]);
+            self.fence_value += 1;
+            direct_queue.Signal(&self.fence.fence, self.fence_value)?;
+
+            for barrier in self.transitions.drain(..) {
+                let _: D3D12_RESOURCE_TRANSITION_BARRIER =
+                    std::mem::ManuallyDrop::into_inner(barrier.Anonymous.Transition);
+            }
+        }
+        let fence_value = self.fence_value;
+        self.in_flight_upload_buffers.extend(self.upload_buffers.drain(..).map(|upload| (fence_value, upload)));
+        for region in self.ring_regions.iter_mut().filter(|region| region.0.is_none()) {
+            region.0 = Some(fence_value);
+        }
+        self.pending = false;
+        self.open = false;
+        Ok(())
+    }
+}
+
+impl super::RaytracingBLAS<Device> for RaytracingBLAS {}
+
+impl super::RaytracingTLAS<Device> for RaytracingTLAS {
+    fn get_srv_index(&self) -> usize {
+        self.srv_index
+    }
+}
+
+impl super::RaytracingPipeline<Device> for RaytracingPipeline {}
+
+impl super::RaytracingShaderBindingTable<Device> for RaytracingShaderBindingTable {}
+
+impl super::Sampler<Device> for Sampler {
+    fn get_index(&self) -> usize {
+        self.index
+    }
+}
+
+impl Fence {
+    /// creates a fence on `device` with an initial value of 0
+    fn create(device: &ID3D12Device) -> result::Result<Fence, super::Error> {
+        unsafe {
+            Ok(Fence {
+                fence: device.CreateFence(0, D3D12_FENCE_FLAG_NONE)?
+            })
+        }
+    }
+}
+
+impl super::Fence<Device> for Fence {
+    fn get_completed_value(&self) -> u64 {
+        unsafe {
+            self.fence.GetCompletedValue()
+        }
+    }
+
+    fn wait_cpu(&self, value: u64) -> result::Result<(), super::Error> {
+        wait_for_fence(&self.fence, value)
+    }
+}
+
+impl super::PipelineLibrary<Device> for PipelineLibrary {
+    fn serialize(&self) -> result::Result<Vec<u8>, super::Error> {
+        unsafe {
+            let mut data = vec![0u8; self.library.GetSerializedSize()];
+            self.library.Serialize(data.as_mut_ptr() as *mut core::ffi::c_void, data.len())?;
+            Ok(data)
+        }
+    }
+}
+
 impl From<os::win32::NativeHandle> for HWND {
     fn from(handle: os::win32::NativeHandle) -> HWND {
         handle.hwnd
@@ -1,6 +1,8 @@
 use stb_image_rust;
 use stb_image_write_rust::ImageWriter::ImageWriter;
 
+use crate::gfx;
+
 use std::fs;
 use std::io::Read;
 
@@ -16,6 +18,27 @@ pub struct ImageData {
     pub data: Vec<u8>,
 }
 
+/// Texture data loaded from file with the information required to create a `gfx::Texture`. `data` is tightly packed
+/// layer by layer then mip by mip, which is the layout expected by `Device::create_texture`
+pub struct TextureData {
+    /// Horizontal dimension of the top mip in texels
+    pub width: u64,
+    /// Vertical dimension of the top mip in texels
+    pub height: u64,
+    /// Number of depth slices for volume textures, otherwise 1
+    pub depth: u32,
+    /// Number of array layers, for cubemaps this is the number of faces
+    pub array_levels: u32,
+    /// Number of mips contained in `data` for each array layer
+    pub mip_levels: u32,
+    /// Format of the texels in `data`
+    pub format: gfx::Format,
+    /// True if the array layers are cubemap faces
+    pub cubemap: bool,
+    /// Vector of image data tightly packed
+    pub data: Vec<u8>,
+}
+
 /// Writes a buffer of image data to a file. The type of image format written is determined by filename ext
 /// supported image formats are (png, bmp, tga and jpg).
 pub fn write_to_file(
@@ -128,3 +151,188 @@ pub fn load_from_file(filename: String) -> ImageData {
         data: data_out,
     }
 }
+
+/// Loads texture data from file, dds files are parsed directly and can contain mip chains, arrays, cubemaps, volumes
+/// and block compressed formats. Other formats are decoded with stb_image into `Format::RGBA8n` (png, tga, bmp, jpg, gif)
+pub fn load_texture_from_file(filename: &str) -> Result<TextureData, super::Error> {
+    let mut contents = fs::read(filename).map_err(|err| super::Error {
+        msg: format!("hotline_rs::image: failed to read file '{}': {}", filename, err)
+    })?;
+
+    let ext = std::path::Path::new(filename).extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase());
+    if ext.as_deref() == Some("dds") {
+        return parse_dds(&contents).map_err(|msg| super::Error {
+            msg: format!("hotline_rs::image: failed to load '{}': {}", filename, msg)
+        });
+    }
+
+    let mut x = 0;
+    let mut y = 0;
+    let mut comp = 0;
+    let mut data = Vec::new();
+    unsafe {
+        let img = stb_image_rust::stbi_load_from_memory(
+            contents.as_mut_ptr(),
+            contents.len() as i32,
+            &mut x,
+            &mut y,
+            &mut comp,
+            stb_image_rust::STBI_rgb_alpha,
+        );
+        if img.is_null() {
+            return Err(super::Error {
+                msg: format!("hotline_rs::image: failed to decode image '{}'", filename)
+            });
+        }
+
+        // always request 4 components, regardless of the components in the file
+        let data_size_bytes = (x * y * 4) as usize;
+        data.resize(data_size_bytes, 0);
+        std::ptr::copy_nonoverlapping(img, data.as_mut_ptr(), data_size_bytes);
+        stb_image_rust::c_runtime::free(img);
+    }
+
+    Ok(TextureData {
+        width: x as u64,
+        height: y as u64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        format: gfx::Format::RGBA8n,
+        cubemap: false,
+        data
+    })
+}
+
+const DDS_MAGIC: u32 = 0x20534444;
+const DDS_HEADER_SIZE: usize = 128;
+const DDS_DX10_HEADER_SIZE: usize = 20;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// reads a little endian u32 from `data` at byte `offset`
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// returns a four character code as u32 for comparing against dds pixel formats
+const fn four_cc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+/// maps a dxgi format from a dds dx10 header to a `gfx::Format`
+fn dds_dxgi_format(dxgi_format: u32) -> Option<gfx::Format> {
+    match dxgi_format {
+        2 => Some(gfx::Format::RGBA32f),
+        10 => Some(gfx::Format::RGBA16f),
+        24 => Some(gfx::Format::RGB10A2n),
+        28 => Some(gfx::Format::RGBA8n),
+        29 => Some(gfx::Format::RGBA8nSRGB),
+        41 => Some(gfx::Format::R32f),
+        71 => Some(gfx::Format::BC1n),
+        77 => Some(gfx::Format::BC3n),
+        80 => Some(gfx::Format::BC4n),
+        83 => Some(gfx::Format::BC5n),
+        87 => Some(gfx::Format::BGRA8n),
+        91 => Some(gfx::Format::BGRA8nSRGB),
+        95 => Some(gfx::Format::BC6H),
+        98 => Some(gfx::Format::BC7n),
+        _ => None
+    }
+}
+
+/// maps a legacy dds pixel format (four cc or rgb masks) to a `gfx::Format`
+fn dds_legacy_format(data: &[u8]) -> Option<gfx::Format> {
+    let flags = read_u32(data, 80);
+    if flags & DDPF_FOURCC != 0 {
+        let code = read_u32(data, 84);
+        if code == four_cc(b"DXT1") {
+            Some(gfx::Format::BC1n)
+        }
+        else if code == four_cc(b"DXT5") {
+            Some(gfx::Format::BC3n)
+        }
+        else if code == four_cc(b"ATI1") || code == four_cc(b"BC4U") {
+            Some(gfx::Format::BC4n)
+        }
+        else if code == four_cc(b"ATI2") || code == four_cc(b"BC5U") {
+            Some(gfx::Format::BC5n)
+        }
+        else {
+            None
+        }
+    }
+    else if flags & DDPF_RGB != 0 && read_u32(data, 88) == 32 {
+        match (read_u32(data, 92), read_u32(data, 96), read_u32(data, 100)) {
+            (0xff, 0xff00, 0xff0000) => Some(gfx::Format::RGBA8n),
+            (0xff0000, 0xff00, 0xff) => Some(gfx::Format::BGRA8n),
+            _ => None
+        }
+    }
+    else {
+        None
+    }
+}
+
+/// parses a dds file in memory, the surface data in dds files is already laid out layer by layer then mip by mip
+fn parse_dds(data: &[u8]) -> Result<TextureData, String> {
+    if data.len() < DDS_HEADER_SIZE || read_u32(data, 0) != DDS_MAGIC {
+        return Err("invalid dds header".to_string());
+    }
+
+    let flags = read_u32(data, 8);
+    let height = read_u32(data, 12) as u64;
+    let width = read_u32(data, 16) as u64;
+    let caps2 = read_u32(data, 112);
+    let mip_levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+        read_u32(data, 28).max(1)
+    }
+    else {
+        1
+    };
+    let depth = if caps2 & DDSCAPS2_VOLUME != 0 {
+        read_u32(data, 24).max(1)
+    }
+    else {
+        1
+    };
+
+    // dx10 extended header contains a dxgi format and array size
+    let (format, cubemap, array_levels, data_offset) = if read_u32(data, 84) == four_cc(b"DX10") {
+        if data.len() < DDS_HEADER_SIZE + DDS_DX10_HEADER_SIZE {
+            return Err("invalid dds dx10 header".to_string());
+        }
+        let dxgi_format = read_u32(data, DDS_HEADER_SIZE);
+        let format = dds_dxgi_format(dxgi_format).ok_or(format!("unsupported dxgi format: ({})", dxgi_format))?;
+        let cubemap = read_u32(data, DDS_HEADER_SIZE + 8) & DDS_RESOURCE_MISC_TEXTURECUBE != 0;
+        let array_size = read_u32(data, DDS_HEADER_SIZE + 12).max(1);
+        let array_levels = if cubemap { array_size * 6 } else { array_size };
+        (format, cubemap, array_levels, DDS_HEADER_SIZE + DDS_DX10_HEADER_SIZE)
+    }
+    else {
+        let format = dds_legacy_format(data).ok_or_else(|| "unsupported pixel format".to_string())?;
+        let cubemap = caps2 & DDSCAPS2_CUBEMAP != 0;
+        (format, cubemap, if cubemap { 6 } else { 1 }, DDS_HEADER_SIZE)
+    };
+
+    // validate we have enough data for all of the surfaces
+    let size = gfx::mip_chain_size_for_format(format, width, height, depth, mip_levels) * array_levels as u64;
+    if ((data.len() - data_offset) as u64) < size {
+        return Err(format!("expected: ({}) bytes of surface data, found: ({})", size, data.len() - data_offset));
+    }
+
+    Ok(TextureData {
+        width,
+        height,
+        depth,
+        array_levels,
+        mip_levels,
+        format,
+        cubemap,
+        data: data[data_offset..data_offset + size as usize].to_vec()
+    })
+}
@@ -9,11 +9,13 @@ use crate::gfx;
 use crate::gfx::Buffer;
 use crate::gfx::CmdBuf;
 use crate::gfx::Device;
+use crate::gfx::RenderPass;
 use crate::gfx::SwapChain;
 use crate::gfx::Texture;
 
 use std::ffi::CStr;
 use std::ffi::CString;
+use std::collections::HashMap;
 
 use maths_rs::Vec4f;
 
@@ -57,8 +59,14 @@ pub struct ImGuiInfo<'stack, D: Device, A: App> {
 pub struct ImGui<D: Device, A: App> {
     _native_handle: A::NativeHandle,
     _font_texture: D::Texture,
+    vs: D::Shader,
+    fs: D::Shader,
     pipeline: D::RenderPipeline,
+    /// pipelines for rendering into passes other than the backbuffer, grouped by pass format hash
+    pass_pipelines: HashMap<u64, D::RenderPipeline>,
     buffers: Vec<RenderBuffers<D>>,
+    /// buffers for `render_to_pass`, separate from `buffers` so both can be used in the same frame
+    pass_buffers: Vec<RenderBuffers<D>>,
     last_cursor: os::Cursor
 }
 
@@ -203,10 +211,7 @@ fn create_fonts_texture<D: Device>(
     }
 }
 
-fn create_render_pipeline<D: Device, A: App>(info: &ImGuiInfo<D, A>) -> Result<D::RenderPipeline, super::Error> {
-    let device = &info.device;
-    let swap_chain = &info.swap_chain;
-
+fn create_shaders<D: Device>(device: &D) -> Result<(D::Shader, D::Shader), super::Error> {
     // TODO: temp: compile shaders
     let src = "
         cbuffer vertexBuffer : register(b0)
@@ -265,10 +270,21 @@ fn create_render_pipeline<D: Device, A: App>(info: &ImGuiInfo<D, A>) -> Result<D
 
     let vs = device.create_shader(&vs_info, src.as_bytes())?;
     let fs = device.create_shader(&fs_info, src.as_bytes())?;
+    Ok((vs, fs))
+}
 
+fn create_render_pipeline<D: Device>(
+    device: &D,
+    vs: &D::Shader,
+    fs: &D::Shader,
+    pass: &D::RenderPass
+) -> Result<D::RenderPipeline, super::Error> {
     device.create_render_pipeline(&gfx::RenderPipelineInfo {
-        vs: Some(&vs),
-        fs: Some(&fs),
+        vs: Some(vs),
+        fs: Some(fs),
+        hs: None,
+        ds: None,
+        gs: None,
         input_layout: vec![
             gfx::InputElementInfo {
                 semantic: String::from("POSITION"),
@@ -349,7 +365,8 @@ fn create_render_pipeline<D: Device, A: App>(info: &ImGuiInfo<D, A>) -> Result<D
         },
         topology: gfx::Topology::TriangleList,
         patch_index: 0,
-        pass: swap_chain.get_backbuffer_pass(),
+        sample_mask: u32::max_value(),
+        pass,
     })
 }
 
@@ -671,6 +688,7 @@ impl<D, A> ImGui<D, A> where D: Device, A: App {
 
             // create render buffers
             let mut buffers: Vec<RenderBuffers<D>> = Vec::new();
+            let mut pass_buffers: Vec<RenderBuffers<D>> = Vec::new();
             let num_buffers = (*info.swap_chain).get_num_buffers();
 
             let font_tex = create_fonts_texture::<D>(info.device)?;
@@ -678,7 +696,8 @@ impl<D, A> ImGui<D, A> where D: Device, A: App {
             let font_tex_id = to_imgui_texture_id::<D>(&font_tex);
             ImFontAtlas_SetTexID(io.Fonts, font_tex_id);
 
-            let pipeline = create_render_pipeline(info)?;
+            let (vs, fs) = create_shaders(info.device)?;
+            let pipeline = create_render_pipeline(info.device, &vs, &fs, info.swap_chain.get_backbuffer_pass())?;
 
             for _i in 0..num_buffers {
                 buffers.push(RenderBuffers {
@@ -686,7 +705,13 @@ impl<D, A> ImGui<D, A> where D: Device, A: App {
                     vb_size: DEFAULT_VB_SIZE,
                     ib: create_index_buffer::<D>(info.device, DEFAULT_IB_SIZE)?,
                     ib_size: DEFAULT_IB_SIZE,
-                })
+                });
+                pass_buffers.push(RenderBuffers {
+                    vb: create_vertex_buffer::<D>(info.device, DEFAULT_VB_SIZE)?,
+                    vb_size: DEFAULT_VB_SIZE,
+                    ib: create_index_buffer::<D>(info.device, DEFAULT_IB_SIZE)?,
+                    ib_size: DEFAULT_IB_SIZE,
+                });
             }
 
             // enum monitors
@@ -737,8 +762,12 @@ impl<D, A> ImGui<D, A> where D: Device, A: App {
             let imgui = ImGui {
                 _native_handle: info.main_window.get_native_handle(),
                 _font_texture: font_tex,
+                vs,
+                fs,
                 pipeline,
+                pass_pipelines: HashMap::new(),
                 buffers,
+                pass_buffers,
                 last_cursor: os::Cursor::None,
             };
 
@@ -961,6 +990,33 @@ impl<D, A> ImGui<D, A> where D: Device, A: App {
         }
     }
 
+    /// Render imgui draw data into `pass` instead of the backbuffer, ie. a pmfx managed render target so the ui can be
+    /// composited into any target. `cmd` must have begun `pass`, pipelines are created on demand for each pass format.
+    /// Vertex and index buffers are separate from `render`, so both can be called in a frame to draw the same ui into
+    /// the backbuffer and `pass`. Platform windows are only updated by `render`.
+    pub fn render_to_pass(
+        &mut self,
+        device: &mut D,
+        cmd: &mut D::CmdBuf,
+        pass: &D::RenderPass
+    ) -> Result<(), super::Error> {
+        let fmt = pass.get_format_hash();
+        if !self.pass_pipelines.contains_key(&fmt) {
+            let pipeline = create_render_pipeline(device, &self.vs, &self.fs, pass)?;
+            self.pass_pipelines.insert(fmt, pipeline);
+        }
+        unsafe {
+            igRender();
+            render_draw_data::<D>(
+                &*igGetDrawData(),
+                device,
+                cmd,
+                &mut self.pass_buffers,
+                &self.pass_pipelines[&fmt],
+            )
+        }
+    }
+
     pub fn demo(&self) {
         unsafe {
             static mut SHOW_DEMO_WINDOW: bool = true;
@@ -1424,6 +1480,9 @@ unsafe extern "C" fn platform_create_window<D: Device, A: App>(vp: *mut ImGuiVie
             b: 0.60,
             a: 1.00,
         }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
     vd.swap_chain = vec![device.create_swap_chain::<A>(&swap_chain_info, &vd.window[0]).unwrap()];
 
@@ -1615,14 +1674,18 @@ unsafe extern "C" fn renderer_render_window<D: Device, A: App>(vp: *mut ImGuiVie
 
     cmd.close().unwrap();
 
-    ud.device.execute(cmd);
+    if let Err(err) = ud.device.execute(cmd) {
+        println!("hotline_rs::imgui:: {}", err.msg);
+    }
 }
 
 unsafe extern "C" fn renderer_swap_buffers<D: Device, A: App>(vp: *mut ImGuiViewport, _render_arg: *mut cty::c_void) {
     let ud = get_user_data::<D, A>();
     let vd = get_viewport_data::<D, A>(vp);
     assert_ne!(vd.swap_chain.len(), 0);
-    vd.swap_chain[0].swap(ud.device);
+    if let Err(err) = vd.swap_chain[0].swap(ud.device) {
+        println!("hotline_rs::imgui:: {}", err.msg);
+    }
 }
 
 pub type WindowSizeCallback = unsafe extern "C" fn(vp: *mut ImGuiViewport, out_pos: *mut ImVec2);
@@ -9,6 +9,9 @@ use crate::gfx::ResourceState;
 use crate::gfx::RenderPass;
 use crate::gfx::CmdBuf;
 use crate::gfx::Subresource;
+use crate::gfx::Buffer;
+use crate::gfx::PipelineLibrary;
+use crate::image;
 
 use crate::reloader::ReloadState;
 use crate::reloader::Reloader;
@@ -26,9 +29,15 @@ use std::time::SystemTime;
 
 use maths_rs::max;
 
+use rayon::prelude::*;
+
 /// Hash type for quick checks of changed resources from pmfx
 pub type PmfxHash = u64;
 
+/// Permutation mask bit of the wireframe variant which is generated for every render pipeline permutation, it
+/// costs an extra PSO per pipeline permutation and format because fill mode is baked into the pipeline state
+pub const PERMUTATION_WIREFRAME: u32 = 1 << 31;
+
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -38,8 +47,8 @@ pub struct View<D: gfx::Device> {
     pub graph_view_name: String,
     /// name of the pmfx view, this is the source view (camera, render targets)
     pub pmfx_view_name: String,
-    /// A pre-built render pass: multiple colour targets and depth possible
-    pub pass: D::RenderPass,
+    /// A pre-built render pass: multiple colour targets and depth possible, `None` for compute views
+    pub pass: Option<D::RenderPass>,
     /// Pre-calculated viewport based on the output dimensions of the render target adjusted for user data from .pmfx
     pub viewport: gfx::Viewport,
     /// Pre-calculated viewport based on the output dimensions of the render target adjusted for user data from .pmfx
@@ -48,11 +57,26 @@ pub struct View<D: gfx::Device> {
     pub cmd_buf: D::CmdBuf,
     /// name of camera this view intends to be used with
     pub camera: String,
+    /// name of the window the view targets are sized relative to, `None` if the targets have a fixed size
+    pub window: Option<String>,
+    /// tuple (group_count, numthreads) for compute views which specify `dispatch` in data, pass to `CmdBuf::dispatch`
+    pub dispatch: Option<(gfx::Size3, gfx::Size3)>,
     ///this is the name of a single pipeline used for all draw calls in the view. supplied in data as `pipelines: ["name"]`
-    pub view_pipeline: String
+    pub view_pipeline: String,
+    /// coarse shading rate set on the command buffer each frame for render views, `None` shades at full rate
+    pub shading_rate: Option<gfx::ShadingRate>
 }
 pub type ViewRef<D> = Arc<Mutex<View<D>>>;
 
+impl<D> View<D> where D: gfx::Device {
+    /// Returns the render pass of the view or an error for compute views, which have no render pass
+    pub fn get_pass(&self) -> Result<&D::RenderPass, super::Error> {
+        self.pass.as_ref().ok_or_else(|| super::Error {
+            msg: format!("hotline_rs::pmfx:: view: {} has no render pass", self.graph_view_name)
+        })
+    }
+}
+
 /// Compact mesh representation referincing and index buffer, vertex buffer and num index count
 #[derive(Clone)]
 pub struct Mesh<D: gfx::Device> {
@@ -61,7 +85,101 @@ pub struct Mesh<D: gfx::Device> {
     // Index Buffer
     pub ib: D::Buffer,
     /// Number of indices to draw from the index buffer
-    pub num_indices: u32
+    pub num_indices: u32,
+    /// Format of the index buffer, either `R16u` or `R32u`
+    pub index_format: gfx::Format
+}
+
+/// Multi-buffered cpu writable vertex buffer containing per-instance data (ie. world matrices) to bind in a
+/// `PerInstance` input slot, so many instances of the same mesh can be drawn with a single draw call
+pub struct InstanceBuffer<D: gfx::Device> {
+    /// One buffer per swap chain backbuffer, tuple of (buffer, capacity in instances)
+    buffers: Vec<(D::Buffer, usize)>,
+    /// Size of a single instance in bytes
+    stride: usize,
+    /// Index into `buffers` which was last updated
+    bb_index: usize,
+    /// Number of instances written by the last call to `update`
+    pub num_instances: u32
+}
+
+impl<D> InstanceBuffer<D> where D: gfx::Device {
+    /// Creates an instance buffer of `num_buffers` (match the swap chain) each with space for `capacity` instances
+    pub fn create(device: &mut D, stride: usize, capacity: usize, num_buffers: u32) -> Result<Self, super::Error> {
+        let mut buffers = Vec::new();
+        for _ in 0..num_buffers {
+            buffers.push((create_instance_buffer(device, stride, capacity)?, capacity));
+        }
+        Ok(InstanceBuffer {
+            buffers,
+            stride,
+            bb_index: 0,
+            num_instances: 0
+        })
+    }
+
+    /// Writes `data` into the buffer for the current backbuffer, growing the buffer if required
+    pub fn update<T: Sized>(&mut self, device: &mut D, swap_chain: &D::SwapChain, data: &[T]) -> Result<(), super::Error> {
+        // the buffer for this backbuffer is no longer in flight, so it is safe to write or replace
+        self.bb_index = swap_chain.get_backbuffer_index() as usize % self.buffers.len();
+        self.write(device, self.bb_index, data)?;
+        self.num_instances = data.len() as u32;
+        Ok(())
+    }
+
+    /// Writes `data` into all of the buffers, use this for static instance data which is written once before
+    /// any frames are in flight and not updated afterwards
+    pub fn update_all<T: Sized>(&mut self, device: &mut D, data: &[T]) -> Result<(), super::Error> {
+        for i in 0..self.buffers.len() {
+            self.write(device, i, data)?;
+        }
+        self.num_instances = data.len() as u32;
+        Ok(())
+    }
+
+    /// Writes `data` into the buffer at `index`, growing the buffer if required
+    fn write<T: Sized>(&mut self, device: &mut D, index: usize, data: &[T]) -> Result<(), super::Error> {
+        if std::mem::size_of::<T>() != self.stride {
+            return Err(super::Error {
+                msg: format!("hotline_rs::pmfx:: instance data size: {} does not match stride: {}",
+                    std::mem::size_of::<T>(), self.stride)
+            });
+        }
+
+        if data.len() > self.buffers[index].1 {
+            let capacity = data.len().next_power_of_two();
+            self.buffers[index] = (create_instance_buffer(device, self.stride, capacity)?, capacity);
+        }
+
+        self.buffers[index].0.update(0, data)
+    }
+
+    /// Returns the buffer written by the last call to `update`
+    pub fn get_buffer(&self) -> &D::Buffer {
+        &self.buffers[self.bb_index].0
+    }
+}
+
+/// Creates a cpu writable vertex buffer for per-instance data
+fn create_instance_buffer<D: gfx::Device>(device: &mut D, stride: usize, capacity: usize) -> Result<D::Buffer, super::Error> {
+    device.create_buffer::<u8>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Vertex,
+        cpu_access: gfx::CpuAccessFlags::WRITE,
+        format: gfx::Format::Unknown,
+        stride,
+        num_elements: max(capacity, 1)
+    }, None)
+}
+
+/// Draws all instances in `instance_buffer` of `mesh` in a single draw call, the mesh vertex buffer is bound in slot 0
+/// and the instance buffer in slot 1
+pub fn draw_mesh_instanced<D: gfx::Device>(cmd_buf: &D::CmdBuf, mesh: &Mesh<D>, instance_buffer: &InstanceBuffer<D>) {
+    if instance_buffer.num_instances > 0 {
+        cmd_buf.set_index_buffer(&mesh.ib);
+        cmd_buf.set_vertex_buffer(&mesh.vb, 0);
+        cmd_buf.set_vertex_buffer(instance_buffer.get_buffer(), 1);
+        cmd_buf.draw_indexed_instanced(mesh.num_indices, instance_buffer.num_instances, 0, 0, 0);
+    }
 }
 
 /// Additional info to wrap with a texture for tracking changes from windwow sizes or other associated bounds
@@ -71,7 +189,47 @@ struct TrackedTexture<D: gfx::Device>  {
     /// Optional ratio, which will contain window name and scale info if present
     ratio: Option<TextureSizeRatio>,
     /// Tuple of (width, height) to track the current size of the texture and compare for updates
-    size: (u64, u64)
+    size: (u64, u64),
+    /// Render target view heap indices of single subresources keyed by (mip, slice)
+    rt_views: HashMap<(u32, u32), usize>,
+    /// Depth stencil view heap indices of single subresources keyed by (mip, slice)
+    ds_views: HashMap<(u32, u32), usize>,
+    /// Read-only depth stencil view heap indices of single subresources keyed by (mip, slice)
+    read_only_ds_views: HashMap<(u32, u32), usize>
+}
+
+/// A readback node inserted into the render graph after a view to copy a texture into cpu readable memory
+struct ReadBackNode<D: gfx::Device> {
+    /// Name of the texture to read back
+    texture: String,
+    /// The state the texture is in at this point in the render graph
+    state: ResourceState,
+    /// Command buffer to record the transition and copy into
+    cmd_buf: D::CmdBuf,
+    /// True if the `cmd_buf` was recorded this frame and needs executing
+    recorded: bool,
+    /// The most recently issued request for this node
+    request: Option<D::ReadBackRequest>
+}
+
+/// A node in the render graph execute order, which is a view, barrier or readback submitted to `queue`
+pub struct RenderGraphNode {
+    /// Name of the view, barrier or readback
+    pub name: String,
+    /// Queue the node is submitted to, barriers and readbacks are always submitted to the graphics queue
+    pub queue: gfx::QueueType,
+    /// Views on another queue which must complete on the gpu before this node executes
+    pub waits: Vec<String>
+}
+
+impl RenderGraphNode {
+    fn new(name: &str, queue: gfx::QueueType) -> Self {
+        RenderGraphNode {
+            name: name.to_string(),
+            queue,
+            waits: Vec::new()
+        }
+    }
 }
 
 /// Information to track changes to 
@@ -90,12 +248,16 @@ pub struct Pmfx<D: gfx::Device> {
     pmfx_tracking: HashMap<String, PmfxTrackingInfo>,
     /// Folder paths for 
     pmfx_folders: HashMap<String, String>, 
+    /// Folder paths of the pmfx each texture was declared in, texture filepaths are relative to this folder
+    texture_folders: HashMap<String, String>,
     /// Updated by calling 'update_window' this will cause any tracked textures to check for resizes and rebuild textures if necessary
     window_sizes: HashMap<String, (f32, f32)>,
     /// Nested structure of: format (u64) > pipelines (name) > permutation (mask) which is tuple (build_hash, pipeline)
     render_pipelines: HashMap<PmfxHash, HashMap<String, HashMap<u32, (PmfxHash, D::RenderPipeline)>>>,
     /// Compute Pipelines grouped by name then as a tuple (build_hash, pipeline)
     compute_pipelines: HashMap<String, (PmfxHash, D::ComputePipeline)>,
+    /// Pipeline libraries grouped by pmfx folder, pipelines are loaded from the library and only compiled on a miss
+    pipeline_libraries: HashMap<String, D::PipelineLibrary>,
     /// Shaders stored along with their build hash for quick checks if reload is necessary
     shaders: HashMap<String, (PmfxHash, D::Shader)>,
     /// Texture map of tracked texture info
@@ -106,10 +268,34 @@ pub struct Pmfx<D: gfx::Device> {
     cameras: HashMap<String, CameraConstants>,
     /// Auto-generated barriers to insert between view passes to ensure correct resource states
     barriers: HashMap<String, D::CmdBuf>,
-    /// Vector of view names to execute in designated order
-    render_graph_execute_order: Vec<String>,
+    /// Vector of view, barrier and readback nodes to execute in designated order
+    render_graph_execute_order: Vec<RenderGraphNode>,
+    /// Fence used to synchronise views across the graphics and compute queues, created when a graph uses async compute
+    queue_fence: Option<D::Fence>,
+    /// Last value `queue_fence` was signalled with, values increase monotonically across frames
+    queue_fence_value: u64,
+    /// Readback nodes in the render graph grouped by node name
+    readbacks: HashMap<String, ReadBackNode<D>>,
+    /// Texture names which have been requested to read back on the next frame
+    readback_requests: HashSet<String>,
+    /// Texture names loaded with `generate_mips` which have their mip chain recorded on the next frame
+    pending_mips: HashSet<String>,
+    /// Command buffer to record mip generation into, created on first use
+    mips_cmd_buf: Option<D::CmdBuf>,
+    /// True if the `mips_cmd_buf` was recorded this frame and needs executing
+    mips_recorded: bool,
+    /// Enabled state of graph views, disabled views are skipped in `execute` but their barriers still execute
+    view_enabled: HashMap<String, bool>,
+    /// Gpu time in milliseconds of each view, collected from timers recorded around each view's command buffer
+    view_timings: HashMap<String, f64>,
     /// Tracking texture references of views
     view_texture_refs: HashMap<String, HashSet<String>>,
+    /// Debug toggle for render functions to select the `PERMUTATION_WIREFRAME` variant of their pipelines
+    wireframe: bool,
+    /// Requested gpu stable power state for profiling, applied to the device on the next `new_frame`
+    stable_power_state: bool,
+    /// Stable power state last successfully applied to the device
+    applied_stable_power_state: bool,
     /// Watches for filestamp changes and will trigger callbacks in the `PmfxReloadResponder`
     reloader: Reloader,
     /// Errors which occur through render systems can be pushed here for feedback to the user
@@ -125,7 +311,13 @@ struct File {
     pipelines: HashMap<String, PipelinePermutations>,
     depth_stencil_states: HashMap<String, gfx::DepthStencilInfo>,
     raster_states: HashMap<String, gfx::RasterInfo>,
+    /// blend states are optional so pmfx files built before they were supported still load
+    #[serde(default)]
+    blend_states: HashMap<String, gfx::BlendInfo>,
     textures: HashMap<String, TextureInfo>,
+    /// named aliases (ie. `main_colour`) which resolve to texture names so render systems don't hardcode data names
+    #[serde(default)]
+    aliases: HashMap<String, String>,
     views: HashMap<String, ViewInfo>,
     render_graphs: HashMap<String, HashMap<String, GraphViewInfo>>,
     dependencies: Vec<String>
@@ -140,7 +332,9 @@ impl File {
             pipelines: HashMap::new(),
             depth_stencil_states: HashMap::new(),
             raster_states: HashMap::new(),
+            blend_states: HashMap::new(),
             textures: HashMap::new(),
+            aliases: HashMap::new(),
             views: HashMap::new(),
             render_graphs: HashMap::new(),
             dependencies: Vec::new()
@@ -170,6 +364,11 @@ struct TextureInfo {
     samples: u32,
     format: gfx::Format,
     usage: Vec<ResourceState>,
+    /// Creates a cubemap with `array_levels` faces, a multiple of 6
+    cubemap: Option<bool>,
+    /// Generate mips 1..n from the top mip of texture data loaded from `filepath`, files with their own mip chain
+    /// and formats without typed unordered access support keep their mips as is
+    generate_mips: Option<bool>,
     hash: u64
 }
 
@@ -179,35 +378,147 @@ struct Pipeline {
     vs: Option<String>,
     ps: Option<String>,
     cs: Option<String>,
+    /// Hull and domain shaders for tessellated pipelines which use `topology: PatchList`
+    hs: Option<String>,
+    ds: Option<String>,
+    gs: Option<String>,
     vertex_layout: Option<gfx::InputLayout>,
     descriptor_layout: gfx::DescriptorLayout,
     blend_state: Option<String>,
     depth_stencil_state: Option<String>,
     raster_state: Option<String>,
     topology: Option<gfx::Topology>,
+    /// Number of patch control points - 1 for `PatchList` topology
+    patch_index: Option<u32>,
+    /// Enables alpha to coverage, overriding the value of `blend_state` if one is specified
+    alpha_to_coverage: Option<bool>,
+    /// Mask of which msaa samples can be written, all samples are written when `None`
+    sample_mask: Option<u32>,
     hash: PmfxHash
 }
 type PipelinePermutations = HashMap<String, Pipeline>;
 
 #[derive(Serialize, Deserialize, Clone)]
 struct ViewInfo {
-    render_target: Vec<String>,
+    render_target: Vec<RenderTargetInfo>,
     depth_stencil: Vec<String>,
     viewport: Vec<f32>,
     scissor: Vec<f32>,
-    clear_colour: Option<Vec<f32>>,
+    clear_colour: Option<ClearColourInfo>,
+    /// Clear the depth plane of `depth_stencil`, when `None` existing depth is preserved even if `clear_stencil` is set
     clear_depth: Option<f32>,
+    /// Clear the stencil plane of `depth_stencil`, when `None` existing stencil is preserved even if `clear_depth` is set
     clear_stencil: Option<u8>,
+    /// Bind `depth_stencil` with a read-only view so it can be depth tested against and sampled in the same view, the
+    /// target is transitioned to `DepthStencilReadOnlyShaderResource` and cannot be cleared
+    read_only_depth: Option<bool>,
     camera: String,
+    /// Render into a single mip of the targets, if only `slice` is specified this is mip 0
+    mip: Option<u32>,
+    /// Render into a single array slice (or depth slice of a 3D texture) of the targets, if only `mip` is specified
+    /// this is slice 0
+    slice: Option<u32>,
+    /// Reduce the shading rate of all draws in the view, for cheap passes such as fullscreen blurs. Devices without
+    /// variable rate shading shade at full rate
+    shading_rate: Option<gfx::ShadingRate>,
     hash: PmfxHash
 }
 
+/// A render target in a view, either a texture name or a texture name with a single `mip` and / or `slice` to render
+/// into, which overrides the `mip` and `slice` of the view for this target
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum RenderTargetInfo {
+    Name(String),
+    Subresource {
+        name: String,
+        mip: Option<u32>,
+        slice: Option<u32>
+    }
+}
+
+impl RenderTargetInfo {
+    /// Returns the texture name of the target
+    fn name(&self) -> &str {
+        match self {
+            RenderTargetInfo::Name(name) => name,
+            RenderTargetInfo::Subresource { name, .. } => name
+        }
+    }
+
+    /// Returns the (mip, slice) to render into, or `None` to render into the whole resource
+    fn subresource(&self, view: &ViewInfo) -> Option<(u32, u32)> {
+        let (mip, slice) = match self {
+            RenderTargetInfo::Subresource { mip, slice, .. } if mip.is_some() || slice.is_some() => (*mip, *slice),
+            _ => (view.mip, view.slice)
+        };
+        if mip.is_some() || slice.is_some() {
+            Some((mip.unwrap_or(0), slice.unwrap_or(0)))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Tracked state of a texture while generating render graph barriers, subresources which were transitioned
+/// individually are kept in `subresources` until the whole resource is transitioned again
+#[derive(Clone)]
+struct TextureBarrierState {
+    state: ResourceState,
+    subresources: HashMap<u32, ResourceState>
+}
+
+impl TextureBarrierState {
+    fn new(state: ResourceState) -> Self {
+        TextureBarrierState {
+            state,
+            subresources: HashMap::new()
+        }
+    }
+}
+
+/// A single clear colour to broadcast to all render targets, or an array of colours matching the `render_target` list
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum ClearColourInfo {
+    Single(Vec<f32>),
+    PerTarget(Vec<Vec<f32>>)
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct GraphViewInfo {
     view: String,
     pipelines: Option<Vec<String>>,
-    function: String,
+    function: Option<String>,
     depends_on: Option<Vec<String>>,
+    dispatch: Option<DispatchInfo>,
+    resolve: Option<Vec<String>>,
+    readback: Option<Vec<String>>,
+    enabled: Option<bool>,
+    /// Queue to submit the view to, compute views can run on the async compute queue to overlap with graphics work.
+    /// Graphics views which depend on a compute queue view wait on the gpu for it to complete
+    queue: Option<gfx::QueueType>
+}
+
+/// Derives a compute dispatch size from the dimensions of a `target` texture divided by the shader `numthreads`,
+/// `target` and any additional `uavs` are transitioned to unordered access for the dispatch
+#[derive(Serialize, Deserialize, Clone)]
+struct DispatchInfo {
+    target: String,
+    uavs: Option<Vec<String>>,
+    numthreads: gfx::Size3
+}
+
+impl DispatchInfo {
+    /// Returns the dispatch target followed by any additional uav textures
+    fn uav_textures(&self) -> Vec<String> {
+        let mut textures = vec![self.target.to_string()];
+        if let Some(uavs) = &self.uavs {
+            textures.extend(uavs.iter().cloned());
+        }
+        textures
+    }
 }
 
 #[repr(C)]
@@ -218,6 +529,13 @@ pub struct CameraConstants {
     pub view_projection_matrix:  maths_rs::Mat4f
 }
 
+/// returns the filepath of the pipeline library for a pmfx folder: pmfx_folder/pmfx_name.pipelines
+fn get_pipeline_library_filepath(folder: &str) -> std::path::PathBuf {
+    let folder = Path::new(folder);
+    let pmfx_name = folder.file_name().map_or_else(|| folder.to_string_lossy(), |name| name.to_string_lossy());
+    folder.join(format!("{}.pipelines", pmfx_name))
+}
+
 /// creates a shader from an option of filename, returning optional shader back
 fn create_shader_from_file<D: gfx::Device>(device: &D, folder: &Path, file: Option<String>) -> Result<Option<D::Shader>, super::Error> {
     if let Some(shader) = file {
@@ -234,11 +552,17 @@ fn create_shader_from_file<D: gfx::Device>(device: &D, folder: &Path, file: Opti
     }
 }
 
+/// divides `value` by `divisor` rounding up, a divisor of 0 is treated as 1
+fn div_round_up(value: u32, divisor: u32) -> u32 {
+    let divisor = max(divisor, 1);
+    (value + divisor - 1) / divisor
+}
+
 /// get gfx info from a pmfx state, returning default if it does not exist
-fn info_from_state<T: Default + Copy>(name: &Option<String>, map: &HashMap<String, T>) -> T {
+fn info_from_state<T: Default + Clone>(name: &Option<String>, map: &HashMap<String, T>) -> T {
     if let Some(name) = &name {
         if map.contains_key(name) {
-            map[name]
+            map[name].clone()
         }
         else {
             T::default()
@@ -255,7 +579,10 @@ fn to_gfx_texture_info(pmfx_texture: &TextureInfo, ratio_size: (u64, u64)) -> gf
     let (width, height) = ratio_size;
 
     // infer texture type from dimensions
-    let tex_type = if pmfx_texture.depth > 1 {
+    let tex_type = if pmfx_texture.cubemap.unwrap_or(false) {
+        gfx::TextureType::TextureCube
+    }
+    else if pmfx_texture.depth > 1 {
         gfx::TextureType::Texture3D
     }
     else if height > 1 {
@@ -299,6 +626,12 @@ fn to_gfx_texture_info(pmfx_texture: &TextureInfo, ratio_size: (u64, u64)) -> gf
         }
     }
 
+    // mip chains are generated from the top mip on request, when the format can be written as unordered access
+    if pmfx_texture.generate_mips.unwrap_or(false) && pmfx_texture.mip_levels > 1 &&
+        gfx::format_supports_generate_mips(pmfx_texture.format) {
+        usage |= gfx::TextureUsage::GENERATE_MIP_MAPS
+    }
+
     gfx::TextureInfo {
         width,
         height,
@@ -356,6 +689,40 @@ fn to_gfx_clear_colour(clear_colour: Option<Vec<f32>>) -> Option<gfx::ClearColou
     }
 }
 
+/// Transitions any subresources in `tracked` which were transitioned individually back into the whole resource state
+fn join_texture_subresources<D: gfx::Device>(cmd_buf: &mut D::CmdBuf, texture: &D::Texture, tracked: &TextureBarrierState) {
+    for (subresource, state) in &tracked.subresources {
+        cmd_buf.transition_barrier_range(&gfx::TransitionBarrier {
+                texture: Some(texture),
+                buffer: None,
+                state_before: *state,
+                state_after: tracked.state,
+            },
+            *subresource,
+            1
+        );
+    }
+}
+
+/// returns a tuple of (rt_clear, rt_clears) for a `RenderPassInfo` from a pmfx clear colour
+fn to_gfx_clear_colours(clear_colour: Option<ClearColourInfo>) -> (Option<gfx::ClearColour>, Option<Vec<gfx::ClearColour>>) {
+    match clear_colour {
+        Some(ClearColourInfo::Single(col)) => (to_gfx_clear_colour(Some(col)), None),
+        Some(ClearColourInfo::PerTarget(cols)) => {
+            let clears = cols.into_iter().map(|col| {
+                to_gfx_clear_colour(Some(col)).unwrap_or(gfx::ClearColour {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.0
+                })
+            }).collect();
+            (None, Some(clears))
+        }
+        None => (None, None)
+    }
+}
+
 fn to_gfx_clear_depth_stencil(clear_depth: Option<f32>, clear_stencil: Option<u8>) -> Option<gfx::ClearDepthStencil> {
     if clear_depth.is_some() || clear_stencil.is_some() {
         Some( gfx::ClearDepthStencil {
@@ -375,15 +742,29 @@ impl<D> Pmfx<D> where D: gfx::Device {
             pmfx: File::new(),
             pmfx_tracking: HashMap::new(),
             pmfx_folders: HashMap::new(),
+            texture_folders: HashMap::new(),
             render_pipelines: HashMap::new(),
             compute_pipelines: HashMap::new(),
+            pipeline_libraries: HashMap::new(),
             shaders: HashMap::new(),
             textures: HashMap::new(),
             views: HashMap::new(),
             cameras: HashMap::new(),
             barriers: HashMap::new(),
             render_graph_execute_order: Vec::new(),
+            queue_fence: None,
+            queue_fence_value: 0,
+            readbacks: HashMap::new(),
+            readback_requests: HashSet::new(),
+            pending_mips: HashSet::new(),
+            mips_cmd_buf: None,
+            mips_recorded: false,
+            view_enabled: HashMap::new(),
+            view_timings: HashMap::new(),
             view_texture_refs: HashMap::new(),
+            wireframe: false,
+            stable_power_state: false,
+            applied_stable_power_state: false,
             window_sizes: HashMap::new(),
             active_render_graph: String::new(),
             view_errors: Arc::new(Mutex::new(HashMap::new())),
@@ -418,6 +799,11 @@ impl<D> Pmfx<D> where D: gfx::Device {
                 self.pmfx_folders.insert(name.to_string(), String::from(filepath));
             }
 
+            // insert lookup path for textures which are loaded from files relative to the pmfx folder
+            for name in file.textures.keys() {
+                self.texture_folders.insert(name.to_string(), String::from(filepath));
+            }
+
             // create tracking info to check if the pmfx has been rebuilt
             self.pmfx_tracking.insert(pmfx_name, PmfxTrackingInfo {
                 filepath: info_filepath.to_path_buf(),
@@ -442,7 +828,9 @@ impl<D> Pmfx<D> where D: gfx::Device {
         self.pmfx.pipelines.extend(other.pipelines);
         self.pmfx.depth_stencil_states.extend(other.depth_stencil_states);
         self.pmfx.raster_states.extend(other.raster_states);
+        self.pmfx.blend_states.extend(other.blend_states);
         self.pmfx.textures.extend(other.textures);
+        self.pmfx.aliases.extend(other.aliases);
         self.pmfx.views.extend(other.views);
         self.pmfx.render_graphs.extend(other.render_graphs);
         self.pmfx.dependencies.extend(other.dependencies);
@@ -517,17 +905,99 @@ impl<D> Pmfx<D> where D: gfx::Device {
             // create texture from info specified in .pmfx file
             println!("hotline_rs::pmfx:: creating texture: {}", texture_name);
             let pmfx_tex = &self.pmfx.textures[texture_name];
-            let size = self.get_texture_size_from_ratio(pmfx_tex)?;
-            let tex = device.create_texture::<u8>(&to_gfx_texture_info(pmfx_tex, size), None)?;
+            let (tex, size) = if let Some(filepath) = &pmfx_tex.filepath {
+                // load texture data from file, the dimensions and format come from the file
+                let filepath = if let Some(folder) = self.texture_folders.get(texture_name) {
+                    Path::new(folder).join(filepath)
+                }
+                else {
+                    Path::new(filepath).to_path_buf()
+                };
+                let image = image::load_texture_from_file(&filepath.to_string_lossy())?;
+                let size = (image.width, image.height);
+                let mut info = to_gfx_texture_info(pmfx_tex, size);
+                info.tex_type = if image.cubemap {
+                    gfx::TextureType::TextureCube
+                }
+                else if image.depth > 1 {
+                    gfx::TextureType::Texture3D
+                }
+                else {
+                    gfx::TextureType::Texture2D
+                };
+                info.format = image.format;
+                info.depth = image.depth;
+                info.array_levels = image.array_levels;
+                // dds files may contain a mip chain which is used as is and block compressed formats cannot have mips
+                // generated, the pmfx mip count is only kept for mips which will be generated from the top mip
+                if image.mip_levels > 1 || !gfx::format_supports_generate_mips(image.format) {
+                    info.usage.remove(gfx::TextureUsage::GENERATE_MIP_MAPS);
+                }
+                if !info.usage.contains(gfx::TextureUsage::GENERATE_MIP_MAPS) {
+                    info.mip_levels = image.mip_levels;
+                }
+                let tex = device.create_texture(&info, Some(image.data.as_slice()))?;
+                if info.usage.contains(gfx::TextureUsage::GENERATE_MIP_MAPS) {
+                    self.pending_mips.insert(texture_name.to_string());
+                }
+                (tex, size)
+            }
+            else {
+                let size = self.get_texture_size_from_ratio(pmfx_tex)?;
+                let info = to_gfx_texture_info(pmfx_tex, size);
+                (device.create_texture::<u8>(&info, None)?, size)
+            };
             self.textures.insert(texture_name.to_string(), (pmfx_tex.hash, TrackedTexture {
                 texture: tex,
                 ratio: self.pmfx.textures[texture_name].ratio.clone(),
-                size
+                size,
+                rt_views: HashMap::new(),
+                ds_views: HashMap::new(),
+                read_only_ds_views: HashMap::new()
             }));
         }
         Ok(())
     }
 
+    /// Returns the heap index of a render target or depth stencil view of a single subresource of a texture, views are
+    /// created on first use and destroyed along with the texture
+    fn get_subresource_view(
+        &mut self,
+        device: &mut D,
+        texture_name: &str,
+        mip: u32,
+        slice: u32,
+        depth_stencil: bool,
+        read_only: bool
+    ) -> Result<usize, super::Error> {
+        let tracked = &mut self.textures.get_mut(texture_name).ok_or_else(|| super::Error {
+            msg: format!("hotline_rs::pmfx:: missing texture: {}", texture_name)
+        })?.1;
+        let views = if depth_stencil && read_only {
+            &mut tracked.read_only_ds_views
+        }
+        else if depth_stencil {
+            &mut tracked.ds_views
+        }
+        else {
+            &mut tracked.rt_views
+        };
+        if let Some(index) = views.get(&(mip, slice)) {
+            return Ok(*index);
+        }
+        let index = if depth_stencil && read_only {
+            device.create_read_only_depth_stencil_view(&tracked.texture, mip, slice)?
+        }
+        else if depth_stencil {
+            device.create_depth_stencil_view(&tracked.texture, mip, slice)?
+        }
+        else {
+            device.create_render_target_view(&tracked.texture, mip, slice)?
+        };
+        views.insert((mip, slice), index);
+        Ok(index)
+    }
+
     /// Returns a texture reference if the texture exists or none otherwise
     pub fn get_texture<'stack>(&'stack self, texture_name: &str) -> Option<&'stack D::Texture> {
         if self.textures.contains_key(texture_name) {
@@ -538,6 +1008,19 @@ impl<D> Pmfx<D> where D: gfx::Device {
         }
     }
 
+    /// Returns a texture reference by alias name (ie. `main_colour`) as defined in pmfx `aliases`, if `alias` is not
+    /// a known alias it is treated as a literal texture name
+    pub fn get_texture_by_alias<'stack>(&'stack self, alias: &str) -> Option<&'stack D::Texture> {
+        let texture_name = self.pmfx.aliases.get(alias).map(|name| name.as_str()).unwrap_or(alias);
+        self.get_texture(texture_name)
+    }
+
+    /// Returns a mutable texture reference if the texture exists or none otherwise, use this to write to pmfx managed
+    /// textures or bind them as uavs in custom render functions
+    pub fn get_texture_mut<'stack>(&'stack mut self, texture_name: &str) -> Option<&'stack mut D::Texture> {
+        self.textures.get_mut(texture_name).map(|tex| &mut tex.1.texture)
+    }
+
     /// Returns the tuple (width, height) of a texture
     pub fn get_texture_2d_size(&self, texture_name: &str) -> Option<(u64, u64)> {
         if self.textures.contains_key(texture_name) {
@@ -548,6 +1031,13 @@ impl<D> Pmfx<D> where D: gfx::Device {
         }
     }
 
+    /// Returns the name of the window a texture is sized relative to, or `None` if the texture has a fixed size
+    pub fn get_texture_window(&self, texture_name: &str) -> Option<String> {
+        self.textures.get(texture_name).and_then(|tex| {
+            tex.1.ratio.as_ref().map(|ratio| ratio.window.to_string())
+        })
+    }
+
     /// Create a view from information specified in pmfx file
     fn create_view(&mut self, device: &mut D, view_name: &str, graph_view_name: &str, info: &GraphViewInfo) -> Result<(), super::Error> {
         if !self.views.contains_key(graph_view_name) && self.pmfx.views.contains_key(view_name) {
@@ -559,7 +1049,8 @@ impl<D> Pmfx<D> where D: gfx::Device {
 
             // create textures for targets
             let mut render_targets = Vec::new();
-            for name in &pmfx_view.render_target {
+            for target in &pmfx_view.render_target {
+                let name = target.name();
                 self.create_texture(device, name)?;
 
                 // TODO: collect pattern
@@ -582,35 +1073,108 @@ impl<D> Pmfx<D> where D: gfx::Device {
                 .or_insert(HashSet::new()).insert(graph_view_name.to_string());
             }
 
+            // create dispatch targets and track them so the dispatch size is recalculated when the target resizes
+            if let Some(dispatch) = &info.dispatch {
+                for name in dispatch.uav_textures() {
+                    self.create_texture(device, &name)?;
+                    self.view_texture_refs.entry(name.to_string())
+                    .or_insert(HashSet::new()).insert(graph_view_name.to_string());
+                }
+            }
+
+            // read-only depth is tested against while sampled, so it cannot also be cleared
+            let read_only_depth = pmfx_view.read_only_depth.unwrap_or(false);
+            if read_only_depth && (pmfx_view.clear_depth.is_some() || pmfx_view.clear_stencil.is_some()) {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::pmfx:: view: {} cannot clear a read only depth stencil", view_name),
+                });
+            }
+
+            // views of a single mip or slice of the targets, read-only depth always needs its own view
+            let rt_subresource = pmfx_view.render_target.iter().any(|target| target.subresource(&pmfx_view).is_some());
+            let (rt_views, ds_view) = if pmfx_view.mip.is_some() || pmfx_view.slice.is_some() || rt_subresource || read_only_depth {
+                let mip = pmfx_view.mip.unwrap_or(0);
+                let slice = pmfx_view.slice.unwrap_or(0);
+                let rt_views = if rt_subresource {
+                    let mut rt_views = Vec::new();
+                    for target in &pmfx_view.render_target {
+                        let (mip, slice) = target.subresource(&pmfx_view).unwrap_or((0, 0));
+                        rt_views.push(self.get_subresource_view(device, target.name(), mip, slice, false, false)?);
+                    }
+                    Some(rt_views)
+                }
+                else {
+                    None
+                };
+                let ds_view = if !pmfx_view.depth_stencil.is_empty() {
+                    Some(self.get_subresource_view(
+                        device, &pmfx_view.depth_stencil[0], mip, slice, true, read_only_depth)?)
+                }
+                else {
+                    None
+                };
+                (rt_views, ds_view)
+            }
+            else {
+                (None, None)
+            };
+
             let mut size = (0, 0);
+            let mut window = None;
 
-            // array of targets by name
-            for name in &pmfx_view.render_target {
+            // array of targets by name, the size is of the mip being rendered into
+            let mut target_mip = pmfx_view.mip;
+            for target in &pmfx_view.render_target {
+                let name = target.name();
                 render_targets.push(self.get_texture(name).unwrap());
                 size = self.get_texture_2d_size(name).unwrap();
+                window = self.get_texture_window(name).or(window);
+                target_mip = target.subresource(&pmfx_view).map(|(mip, _)| mip);
             }
 
             // get depth stencil by name
             let depth_stencil = if !pmfx_view.depth_stencil.is_empty() {
                 let name = &pmfx_view.depth_stencil[0];
                 size = self.get_texture_2d_size(name).unwrap();
+                window = self.get_texture_window(name).or(window);
                 Some(self.get_texture(name).unwrap())
             }
             else {
                 None
             };
 
-            // pass for render targets with depth stencil
-            let render_target_pass = device
-            .create_render_pass(&gfx::RenderPassInfo {
-                render_targets,
-                rt_clear: to_gfx_clear_colour(pmfx_view.clear_colour),
-                depth_stencil,
-                ds_clear: to_gfx_clear_depth_stencil(pmfx_view.clear_depth, pmfx_view.clear_stencil),
-                resolve: false,
-                discard: false,
-            })
-            .unwrap();
+            // viewport covers the dimensions of the target mip
+            if let Some(mip) = target_mip {
+                size = ((size.0 >> mip).max(1), (size.1 >> mip).max(1));
+            }
+
+            // pass for render targets with depth stencil, compute views only dispatch and have no pass
+            let render_target_pass = if info.dispatch.is_none() {
+                let (rt_clear, rt_clears) = to_gfx_clear_colours(pmfx_view.clear_colour);
+                Some(device.create_render_pass(&gfx::RenderPassInfo {
+                    render_targets,
+                    rt_clear,
+                    rt_clears,
+                    rt_views,
+                    depth_stencil,
+                    ds_view,
+                    ds_clear: to_gfx_clear_depth_stencil(pmfx_view.clear_depth, pmfx_view.clear_stencil),
+                    resolve: false,
+                    discard: false,
+                })?)
+            }
+            else {
+                None
+            };
+
+            // only compute views which dispatch can run on the compute queue
+            let queue = info.queue.unwrap_or(gfx::QueueType::Graphics);
+            if queue == gfx::QueueType::Compute && render_target_pass.is_some() {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::pmfx:: view: {} has a render pass and cannot run on the compute queue",
+                        graph_view_name),
+                });
+            }
 
             // assing a view pipleine (if we supply 1 pipeline) for all draw calls in the view, otherwise leave it emptu
             let view_pipeline = if let Some(pipelines) = &info.pipelines {
@@ -625,6 +1189,22 @@ impl<D> Pmfx<D> where D: gfx::Device {
                 String::new()
             };
 
+            // group count is the target size / numthreads rounded up
+            let dispatch = if let Some(dispatch) = &info.dispatch {
+                let target_size = self.get_texture_2d_size(&dispatch.target).unwrap();
+                size = target_size;
+                window = self.get_texture_window(&dispatch.target).or(window);
+                let numthreads = dispatch.numthreads;
+                Some((gfx::Size3 {
+                    x: div_round_up(target_size.0 as u32, numthreads.x),
+                    y: div_round_up(target_size.1 as u32, numthreads.y),
+                    z: 1
+                }, numthreads))
+            }
+            else {
+                None
+            };
+
             let view = View::<D> {
                 graph_view_name: graph_view_name.to_string(),
                 pmfx_view_name: view_name.to_string(),
@@ -643,9 +1223,12 @@ impl<D> Pmfx<D> where D: gfx::Device {
                     right: size.0 as i32,
                     bottom: size.1 as i32
                 },
-                cmd_buf: device.create_cmd_buf(2),
+                cmd_buf: device.create_cmd_buf_for_queue(2, queue),
                 camera: pmfx_view.camera.to_string(),
-                view_pipeline
+                window,
+                dispatch,
+                view_pipeline,
+                shading_rate: pmfx_view.shading_rate
             };
 
             self.views.insert(graph_view_name.to_string(), 
@@ -680,70 +1263,89 @@ impl<D> Pmfx<D> where D: gfx::Device {
         Ok(())
     }
 
+    /// Returns true if `texture_name` exists and is an msaa texture which can be resolved
+    fn is_texture_resolvable(&self, texture_name: &str) -> bool {
+        self.get_texture(texture_name).map(|tex| tex.is_resolvable()).unwrap_or(false)
+    }
+
+    /// Resolves every array slice and mip of an msaa texture into its resolve resource, which is left in `target_state`
     fn create_resolve_transition(
         &mut self,
         device: &mut D,
-        texture_barriers: &mut HashMap<String, ResourceState>, 
+        texture_barriers: &mut HashMap<String, TextureBarrierState>, 
         view_name: &str, 
         texture_name: &str, 
         target_state: ResourceState) -> Result<(), super::Error> {
         if texture_barriers.contains_key(texture_name) {
-            let state = texture_barriers[texture_name];
-            if true {
-                // add barrier placeholder in the execute order
-                let barrier_name = format!("barrier_resolve-{}-{}", view_name, texture_name);
-                self.render_graph_execute_order.push(barrier_name.to_string());
+            let tracked = texture_barriers[texture_name].clone();
+            let state = tracked.state;
+
+            // prevent resolving non msaa surfaces
+            if !self.is_texture_resolvable(texture_name) {
+                return Err(super::Error {
+                    msg: format!("hotline_rs::pmfx:: view: {} cannot resolve texture: {}, it is not an msaa texture (samples > 1)",
+                        view_name, texture_name),
+                });
+            }
 
-                if let Some(tex) = self.get_texture(&texture_name) {
+            // add barrier placeholder in the execute order
+            let barrier_name = format!("barrier_resolve-{}-{}", view_name, texture_name);
+            self.render_graph_execute_order.push(RenderGraphNode::new(&barrier_name, gfx::QueueType::Graphics));
+
+            // subresources are ordered mip first, then array slice
+            let num_subresources = self.pmfx.textures.get(texture_name).map(|info| {
+                info.mip_levels.max(1) * info.array_levels.max(1)
+            }).unwrap_or(1);
+
+            let tex = self.get_texture(&texture_name).unwrap();
+
+            // transition main resource into resolve src
+            let mut cmd_buf = device.create_cmd_buf(1);
+            join_texture_subresources::<D>(&mut cmd_buf, tex, &tracked);
+            cmd_buf.transition_barrier(&gfx::TransitionBarrier {
+                texture: Some(tex),
+                buffer: None,
+                state_before: state,
+                state_after: ResourceState::ResolveSrc,
+            });
 
-                    // prevent resolving non msaa surfaces
-                    if !tex.is_resolvable() {
-                        return Err(super::Error {
-                            msg: format!("hotline_rs::pmfx:: texture: {} is not resolvable", texture_name),
-                        });
-                    }
+            // transition resolve resource into resolve dst
+            cmd_buf.transition_barrier_subresource(&gfx::TransitionBarrier {
+                    texture: Some(tex),
+                    buffer: None,
+                    state_before: target_state,
+                    state_after: ResourceState::ResolveDst,
+                },
+                Subresource::ResolveResource
+            );
+            
+            // perform the resolve for each array slice and mip
+            for subresource in 0..num_subresources {
+                cmd_buf.resolve_texture_subresource(tex, subresource)?;
+            }
 
-                    // transition main resource into resolve src
-                    let mut cmd_buf = device.create_cmd_buf(1);
-                    cmd_buf.transition_barrier(&gfx::TransitionBarrier {
-                        texture: Some(self.get_texture(&texture_name).unwrap()),
-                        buffer: None,
-                        state_before: state,
-                        state_after: ResourceState::ResolveSrc,
-                    });
+            // transition the resolve to shader resource for sampling
+            cmd_buf.transition_barrier_subresource(&gfx::TransitionBarrier {
+                    texture: Some(tex),
+                    buffer: None,
+                    state_before: ResourceState::ResolveDst,
+                    state_after: target_state,
+                },
+                Subresource::ResolveResource
+            );
 
-                    // transition resolve resource into resolve dst
-                    cmd_buf.transition_barrier_subresource(&gfx::TransitionBarrier {
-                            texture: Some(self.get_texture(&texture_name).unwrap()),
-                            buffer: None,
-                            state_before: target_state,
-                            state_after: ResourceState::ResolveDst,
-                        },
-                        Subresource::ResolveResource
-                    );
-                    
-                    // perform the resolve
-                    cmd_buf.resolve_texture_subresource(tex, 0)?;
-
-                    // transition the resolve to shader resource for sampling
-                    cmd_buf.transition_barrier_subresource(&gfx::TransitionBarrier {
-                            texture: Some(self.get_texture(&texture_name).unwrap()),
-                            buffer: None,
-                            state_before: ResourceState::ResolveDst,
-                            state_after: target_state,
-                        },
-                        Subresource::ResolveResource
-                    );
-
-                    // insert barrier
-                    cmd_buf.close()?;
-                    self.barriers.insert(barrier_name.to_string(), cmd_buf);
-
-                    // update track state
-                    texture_barriers.remove(texture_name);
-                    texture_barriers.insert(texture_name.to_string(), ResourceState::ResolveSrc);
-                }
-            }
+            // insert barrier
+            cmd_buf.close()?;
+            self.barriers.insert(barrier_name.to_string(), cmd_buf);
+
+            // update track state
+            texture_barriers.insert(texture_name.to_string(), TextureBarrierState::new(ResourceState::ResolveSrc));
+        }
+        else {
+            return Err(super::Error {
+                msg: format!("hotline_rs::pmfx:: view: {} cannot resolve texture: {}, it is not a render target in the graph",
+                    view_name, texture_name),
+            });
         }
         Ok(())
     }
@@ -751,36 +1353,113 @@ impl<D> Pmfx<D> where D: gfx::Device {
     fn create_texture_transition_barrier(
         &mut self,
         device: &mut D,
-        texture_barriers: &mut HashMap<String, ResourceState>, 
+        texture_barriers: &mut HashMap<String, TextureBarrierState>, 
         view_name: &str, 
         texture_name: &str, 
         target_state: ResourceState) -> Result<(), super::Error> {
         if texture_barriers.contains_key(texture_name) {
-            let state = texture_barriers[texture_name];
-            if state != target_state {
+            let tracked = texture_barriers[texture_name].clone();
+            let state = tracked.state;
+            if state != target_state || !tracked.subresources.is_empty() {
                 // add barrier placeholder in the execute order
                 let barrier_name = format!("barrier_{}-{}", view_name, texture_name);
-                self.render_graph_execute_order.push(barrier_name.to_string());          
+                self.render_graph_execute_order.push(RenderGraphNode::new(&barrier_name, gfx::QueueType::Graphics));
 
-                // create a command buffer
+                // create a command buffer, individually transitioned subresources must rejoin the whole resource first
+                let tex = self.get_texture(&texture_name).unwrap();
                 let mut cmd_buf = device.create_cmd_buf(1);
-                cmd_buf.transition_barrier(&gfx::TransitionBarrier {
-                    texture: Some(self.get_texture(&texture_name).unwrap()),
-                    buffer: None,
-                    state_before: state,
-                    state_after: target_state,
-                });
+                join_texture_subresources::<D>(&mut cmd_buf, tex, &tracked);
+                if state != target_state {
+                    cmd_buf.transition_barrier(&gfx::TransitionBarrier {
+                        texture: Some(tex),
+                        buffer: None,
+                        state_before: state,
+                        state_after: target_state,
+                    });
+                }
                 cmd_buf.close()?;
                 self.barriers.insert(barrier_name.to_string(), cmd_buf);
     
                 // update track state
-                texture_barriers.remove(texture_name);
-                texture_barriers.insert(texture_name.to_string(), target_state);
+                texture_barriers.insert(texture_name.to_string(), TextureBarrierState::new(target_state));
             }
         }
         Ok(())
     }
 
+    /// Creates a barrier which transitions a single subresource `mip` and `slice` of a texture into `target_state`,
+    /// the rest of the texture's subresources keep their current state
+    fn create_texture_subresource_transition_barrier(
+        &mut self,
+        device: &mut D,
+        texture_barriers: &mut HashMap<String, TextureBarrierState>,
+        view_name: &str,
+        texture_name: &str,
+        mip: u32,
+        slice: u32,
+        target_state: ResourceState) -> Result<(), super::Error> {
+        if let Some(tracked) = texture_barriers.get_mut(texture_name) {
+            let mip_levels = self.pmfx.textures.get(texture_name).map(|info| info.mip_levels).unwrap_or(1);
+            let subresource = gfx::subresource_index(mip, slice, mip_levels);
+            let state = tracked.subresources.get(&subresource).copied().unwrap_or(tracked.state);
+            if state != target_state {
+                // add barrier placeholder in the execute order
+                let barrier_name = format!("barrier_{}-{}-{}", view_name, texture_name, subresource);
+                self.render_graph_execute_order.push(RenderGraphNode::new(&barrier_name, gfx::QueueType::Graphics));
+
+                let mut cmd_buf = device.create_cmd_buf(1);
+                cmd_buf.transition_barrier_range(&gfx::TransitionBarrier {
+                        texture: Some(self.get_texture(texture_name).unwrap()),
+                        buffer: None,
+                        state_before: state,
+                        state_after: target_state,
+                    },
+                    subresource,
+                    1
+                );
+                cmd_buf.close()?;
+                self.barriers.insert(barrier_name, cmd_buf);
+
+                // update track state, subresources back in the whole resource state no longer need tracking
+                let tracked = texture_barriers.get_mut(texture_name).unwrap();
+                if target_state == tracked.state {
+                    tracked.subresources.remove(&subresource);
+                }
+                else {
+                    tracked.subresources.insert(subresource, target_state);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Transitions a compute view's dispatch target into unordered access, or if it is already in unordered access
+    /// because a previous compute view wrote to it, inserts a uav barrier so the writes complete before this view
+    fn create_texture_uav_barrier(
+        &mut self,
+        device: &mut D,
+        texture_barriers: &mut HashMap<String, TextureBarrierState>,
+        view_name: &str,
+        texture_name: &str) -> Result<(), super::Error> {
+        let in_uav = texture_barriers.get(texture_name).map(|tracked| {
+            tracked.state == ResourceState::UnorderedAccess && tracked.subresources.is_empty()
+        });
+        if in_uav == Some(true) {
+            let barrier_name = format!("barrier_uav-{}-{}", view_name, texture_name);
+            self.render_graph_execute_order.push(RenderGraphNode::new(&barrier_name, gfx::QueueType::Graphics));
+
+            let mut cmd_buf = device.create_cmd_buf(1);
+            cmd_buf.uav_barrier(self.get_texture(texture_name), None);
+            cmd_buf.close()?;
+            self.barriers.insert(barrier_name, cmd_buf);
+            Ok(())
+        }
+        else {
+            self.create_texture_transition_barrier(
+                device, texture_barriers, view_name, texture_name, ResourceState::UnorderedAccess)
+        }
+    }
+
     /// Create a render graph wih automatic resource barrier generation from info specified insie .pmfx file
     pub fn create_render_graph(&mut self, device: &mut D, graph_name: &str) -> Result<(), super::Error> {        
         // go through the graph sequentially, as the command lists are executed in order but generated 
@@ -791,6 +1470,7 @@ impl<D> Pmfx<D> where D: gfx::Device {
 
             // currently we just have 1 single execute graph and barrier set
             self.barriers.clear();
+            self.readbacks.clear();
             self.render_graph_execute_order.clear();
 
             // TODO: collect pattern
@@ -810,23 +1490,28 @@ impl<D> Pmfx<D> where D: gfx::Device {
             let mut barriers = self.pmfx.textures.iter().filter(|tex|{
                 tex.1.usage.contains(&ResourceState::ShaderResource) || 
                 tex.1.usage.contains(&ResourceState::RenderTarget) ||
-                tex.1.usage.contains(&ResourceState::DepthStencil)
+                tex.1.usage.contains(&ResourceState::DepthStencil) ||
+                tex.1.usage.contains(&ResourceState::UnorderedAccess)
             }).map(|tex|{
-              (tex.0.to_string(), ResourceState::ShaderResource)  
-            }).collect::<HashMap<String, ResourceState>>();
+              (tex.0.to_string(), TextureBarrierState::new(ResourceState::ShaderResource))
+            }).collect::<HashMap<String, TextureBarrierState>>();
 
             // loop over the graph multiple times adding views in depends on order, until we add all the views
             let mut to_add = self.pmfx.render_graphs[graph_name].len();
            
             let mut added = 0;
             let mut dependencies = HashSet::new();
+            let mut missing = HashSet::new();
             while added < to_add {
+                let added_before = added;
                 let pmfx_graph = self.pmfx.render_graphs[graph_name].clone();
                 for (graph_view_name, instance) in &pmfx_graph {
                     // allow missing views to be safely handled
                     if !self.pmfx.views.contains_key(&instance.view) {
-                        println!("hotline_rs::pmfx:: [warning] missing view {}", instance.view);
-                        to_add -= 1;
+                        if missing.insert(graph_view_name.to_string()) {
+                            println!("hotline_rs::pmfx:: [warning] missing view {}", instance.view);
+                            to_add -= 1;
+                        }
                         continue;
                     }
     
@@ -837,20 +1522,14 @@ impl<D> Pmfx<D> where D: gfx::Device {
     
                     // wait for dependencies
                     if let Some(depends_on) = &instance.depends_on {
-                        let mut passes = false;
-                        if depends_on.len() > 0 {
-                            for d in depends_on {
-                                if !pmfx_graph.contains_key(d) {
-                                    passes = true;
-                                    println!("hotline_rs::pmfx:: [warning] view {} missing dependency {}. ignoring", 
-                                        instance.view, d);
-                                }
-                                else if dependencies.contains(d) {
-                                    passes = true;
-                                }
-                                else {
-                                    passes = false;
-                                }
+                        let mut passes = true;
+                        for d in depends_on {
+                            if !pmfx_graph.contains_key(d) {
+                                println!("hotline_rs::pmfx:: [warning] view {} missing dependency {}. ignoring", 
+                                    instance.view, d);
+                            }
+                            else if !dependencies.contains(d) {
+                                passes = false;
                             }
                         }
 
@@ -859,22 +1538,56 @@ impl<D> Pmfx<D> where D: gfx::Device {
                         }
                     }
                     
+                    // graphics views and their barriers wait for any compute queue views they depend on
+                    let queue = instance.queue.unwrap_or(gfx::QueueType::Graphics);
+                    if queue == gfx::QueueType::Graphics {
+                        let waits = instance.depends_on.iter().flatten().filter(|d| {
+                            pmfx_graph.get(*d).and_then(|v| v.queue) == Some(gfx::QueueType::Compute)
+                        }).cloned().collect::<Vec<String>>();
+                        if !waits.is_empty() {
+                            self.render_graph_execute_order.push(RenderGraphNode {
+                                name: format!("wait_{}", graph_view_name),
+                                queue,
+                                waits
+                            });
+                        }
+                    }
+
                     // create transitions by inspecting view info
                     let pmfx_view = self.pmfx.views[&instance.view].clone();
     
-                    // if we need to write to a target we must make sure it is transitioned into render target state
-                    for rt_name in pmfx_view.render_target {
-                        self.create_texture_transition_barrier(
-                            device, &mut barriers, &instance.view, &rt_name, ResourceState::RenderTarget)?;
-    
+                    // if we need to write to a target we must make sure it is transitioned into render target state,
+                    // views which render into a single mip or slice only transition that subresource
+                    for target in &pmfx_view.render_target {
+                        if let Some((mip, slice)) = target.subresource(&pmfx_view) {
+                            self.create_texture_subresource_transition_barrier(
+                                device, &mut barriers, &instance.view, target.name(), mip, slice, ResourceState::RenderTarget)?;
+                        }
+                        else {
+                            self.create_texture_transition_barrier(
+                                device, &mut barriers, &instance.view, target.name(), ResourceState::RenderTarget)?;
+                        }
                     }
     
-                    // same for depth stencils
+                    // same for depth stencils, read-only depth stays readable from shaders
+                    let ds_state = if pmfx_view.read_only_depth.unwrap_or(false) {
+                        ResourceState::DepthStencilReadOnlyShaderResource
+                    }
+                    else {
+                        ResourceState::DepthStencil
+                    };
                     for ds_name in pmfx_view.depth_stencil {
                         self.create_texture_transition_barrier(
-                            device, &mut barriers, &instance.view, &ds_name, ResourceState::DepthStencil)?;
+                            device, &mut barriers, &instance.view, &ds_name, ds_state)?;
     
                     }
+
+                    // compute views write to their dispatch targets as unordered access
+                    if let Some(dispatch) = &instance.dispatch {
+                        for name in dispatch.uav_textures() {
+                            self.create_texture_uav_barrier(device, &mut barriers, graph_view_name, &name)?;
+                        }
+                    }
     
                     // create pipelines requested for this view instance with the pass format
                     if let Some(view_pipelines) = &instance.pipelines {
@@ -882,7 +1595,12 @@ impl<D> Pmfx<D> where D: gfx::Device {
                             let view = self.get_view(&graph_view_name)?;
                             let view = view.clone();
                             let view = view.lock().unwrap();
-                            self.create_pipeline(device, pipeline, &view.pass)?;
+                            if let Some(pass) = &view.pass {
+                                self.create_pipeline(device, pipeline, pass)?;
+                            }
+                            else {
+                                self.create_compute_pipeline(device, pipeline)?;
+                            }
                         }
     
                     }
@@ -890,21 +1608,92 @@ impl<D> Pmfx<D> where D: gfx::Device {
                     // push a view on
                     added += 1;
                     dependencies.insert(graph_view_name.to_string());
-                    self.render_graph_execute_order.push(graph_view_name.to_string());
+                    self.render_graph_execute_order.push(RenderGraphNode::new(graph_view_name, queue));
+
+                    // keep user enabled state through rebuilds, otherwise take it from data
+                    self.view_enabled.entry(graph_view_name.to_string())
+                        .or_insert(instance.enabled.unwrap_or(true));
+
+                    // insert resolves requested after the view, naming a non msaa texture is an error
+                    if let Some(resolve) = &instance.resolve {
+                        for texture_name in resolve {
+                            self.create_resolve_transition(
+                                device, &mut barriers, graph_view_name, texture_name, ResourceState::ShaderResource)?;
+                        }
+                    }
+
+                    // insert readback nodes after the view, which copy the texture in its current state
+                    if let Some(readback) = &instance.readback {
+                        for texture_name in readback {
+                            if self.get_texture(texture_name).is_none() {
+                                println!("hotline_rs::pmfx:: [warning] view {} missing readback texture {}. ignoring",
+                                    instance.view, texture_name);
+                                continue;
+                            }
+                            // the copy reads the whole resource so any individually transitioned subresources rejoin first
+                            if let Some(tracked) = barriers.get(texture_name).cloned() {
+                                self.create_texture_transition_barrier(
+                                    device, &mut barriers, graph_view_name, texture_name, tracked.state)?;
+                            }
+                            let state = barriers.get(texture_name).map(|tracked| tracked.state)
+                                .unwrap_or(ResourceState::ShaderResource);
+                            let readback_name = format!("readback_{}-{}", graph_view_name, texture_name);
+                            self.render_graph_execute_order.push(
+                                RenderGraphNode::new(&readback_name, gfx::QueueType::Graphics));
+                            self.readbacks.insert(readback_name, ReadBackNode {
+                                texture: texture_name.to_string(),
+                                state,
+                                cmd_buf: device.create_cmd_buf(2),
+                                recorded: false,
+                                request: None
+                            });
+                        }
+                    }
+                }
+
+                // nothing could be added this pass, so the remaining views depend on each other in a cycle
+                if added == added_before && added < to_add {
+                    let mut cycle = pmfx_graph.iter().filter(|(name, _)| {
+                        !dependencies.contains(*name) && !missing.contains(*name)
+                    }).map(|(name, instance)| {
+                        let unresolved = instance.depends_on.iter().flatten().filter(|d| {
+                            pmfx_graph.contains_key(*d) && !dependencies.contains(*d)
+                        }).cloned().collect::<Vec<String>>();
+                        format!("{} -> [{}]", name, unresolved.join(", "))
+                    }).collect::<Vec<String>>();
+                    cycle.sort();
+                    return Err(super::Error {
+                        msg: format!("hotline_rs::pmfx:: render graph: {} has an unsatisfiable dependency cycle: {}",
+                            graph_name, cycle.join(", "))
+                    });
                 }
             }
             
+            // all compute queue work must complete before the end of frame barriers and present
+            let compute_views = self.render_graph_execute_order.iter().filter(|node| {
+                node.queue == gfx::QueueType::Compute
+            }).map(|node| node.name.to_string()).collect::<Vec<String>>();
+            if !compute_views.is_empty() {
+                self.render_graph_execute_order.push(RenderGraphNode {
+                    name: "wait_eof".to_string(),
+                    queue: gfx::QueueType::Graphics,
+                    waits: compute_views
+                });
+                if self.queue_fence.is_none() {
+                    self.queue_fence = Some(device.create_fence()?);
+                }
+            }
+
             // finally all targets which are in the 'barriers' array are transitioned to shader resources (for debug views)
             let srvs = barriers.keys().map(|k|{
                 k.to_string()
             }).collect::<Vec<String>>();
 
             for name in srvs {
-                let result = self.create_resolve_transition(
-                    device, &mut barriers, "eof", &name, ResourceState::ShaderResource);
-               
-                if result.is_err() {
-                    // TODO: tell user without spewing out errors
+                // only msaa targets have a resolve resource, other textures just transition
+                if self.is_texture_resolvable(&name) {
+                    self.create_resolve_transition(
+                        device, &mut barriers, "eof", &name, ResourceState::ShaderResource)?;
                 }
 
                 self.create_texture_transition_barrier(
@@ -914,6 +1703,11 @@ impl<D> Pmfx<D> where D: gfx::Device {
             // track the current render graph for if we need to rebuild due to resize, or file modification
             self.active_render_graph = graph_name.to_string();
 
+            // report pipelines which will be missing at draw time up front
+            for err in self.validate_graph() {
+                println!("hotline_rs::pmfx:: [warning] {}", err.msg);
+            }
+
             Ok(())
         }
         else {
@@ -923,17 +1717,175 @@ impl<D> Pmfx<D> where D: gfx::Device {
         }
     }
 
+    /// Cross-checks the pipelines of every view in the active render graph against the view's pass format, returns
+    /// an error for each pipeline which is undefined, built for the wrong kind of view or missing for the pass format
+    pub fn validate_graph(&self) -> Vec<super::Error> {
+        let mut errors = Vec::new();
+        let graph = if let Some(graph) = self.pmfx.render_graphs.get(&self.active_render_graph) {
+            graph
+        }
+        else {
+            return errors;
+        };
+
+        for (graph_view_name, instance) in graph {
+            let view = if let Some(view) = self.views.get(graph_view_name) {
+                view.1.clone()
+            }
+            else {
+                continue;
+            };
+            let view = view.lock().unwrap();
+            for pipeline_name in instance.pipelines.iter().flatten() {
+                let permutations = if let Some(permutations) = self.pmfx.pipelines.get(pipeline_name) {
+                    permutations
+                }
+                else {
+                    errors.push(super::Error {
+                        msg: format!("hotline_rs::pmfx:: view: {} uses undefined pipeline: {}",
+                            graph_view_name, pipeline_name)
+                    });
+                    continue;
+                };
+                let is_compute = permutations.values().any(|pipeline| pipeline.cs.is_some());
+                match (&view.pass, is_compute) {
+                    (Some(_), true) => {
+                        errors.push(super::Error {
+                            msg: format!("hotline_rs::pmfx:: render view: {} uses compute pipeline: {}",
+                                graph_view_name, pipeline_name)
+                        });
+                    }
+                    (None, false) => {
+                        errors.push(super::Error {
+                            msg: format!("hotline_rs::pmfx:: compute view: {} uses render pipeline: {}",
+                                graph_view_name, pipeline_name)
+                        });
+                    }
+                    (None, true) => {
+                        if !self.compute_pipelines.contains_key(pipeline_name) {
+                            errors.push(super::Error {
+                                msg: format!("hotline_rs::pmfx:: compute pipeline: {} was not built for view: {}",
+                                    pipeline_name, graph_view_name)
+                            });
+                        }
+                    }
+                    (Some(pass), false) => {
+                        let fmt = pass.get_format_hash();
+                        let built = self.render_pipelines.get(&fmt).and_then(|formats| formats.get(pipeline_name));
+                        let missing = permutations.keys().filter(|permutation| {
+                            let mask = permutation.parse::<u32>().unwrap_or(0);
+                            !built.map(|built| built.contains_key(&mask)).unwrap_or(false)
+                        }).count();
+                        if missing > 0 {
+                            errors.push(super::Error {
+                                msg: format!("hotline_rs::pmfx:: pipeline: {} is missing {} permutation(s) for the pass format of view: {} ({})",
+                                    pipeline_name, missing, graph_view_name, fmt)
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    /// Creates the shaders used by all permutations of `pipeline_name` and loads the pipeline library of the pmfx
+    /// the pipeline was declared in, returns the pmfx folder which is the key of the library
+    fn create_pipeline_shaders(&mut self, device: &D, pipeline_name: &str) -> Result<String, super::Error> {
+        let folder = self.pmfx_folders[pipeline_name].to_string();
+        for (_, pipeline) in self.pmfx.pipelines[pipeline_name].clone() {
+            self.create_shader(device, Path::new(&folder), &pipeline.vs)?;
+            self.create_shader(device, Path::new(&folder), &pipeline.ps)?;
+            self.create_shader(device, Path::new(&folder), &pipeline.cs)?;
+            self.create_shader(device, Path::new(&folder), &pipeline.hs)?;
+            self.create_shader(device, Path::new(&folder), &pipeline.ds)?;
+            self.create_shader(device, Path::new(&folder), &pipeline.gs)?;
+        }
+
+        // pipelines without a descriptor layout get one built from shader reflection, otherwise warn about resources
+        // missing from the layout because the pipeline will fail to create
+        for (permutation, pipeline) in self.pmfx.pipelines[pipeline_name].clone() {
+            let mut stages = Vec::new();
+            let shaders = [
+                (gfx::ShaderVisibility::Vertex, &pipeline.vs),
+                (gfx::ShaderVisibility::Fragment, &pipeline.ps),
+                (gfx::ShaderVisibility::Compute, &pipeline.cs),
+                (gfx::ShaderVisibility::Hull, &pipeline.hs),
+                (gfx::ShaderVisibility::Domain, &pipeline.ds),
+                (gfx::ShaderVisibility::Geometry, &pipeline.gs)
+            ];
+            for (visibility, shader) in shaders {
+                if let Some(shader) = self.get_shader(shader) {
+                    stages.push((visibility, device.reflect_shader(shader)?));
+                }
+            }
+
+            let layout = &pipeline.descriptor_layout;
+            if layout.bindings.is_none() && layout.push_constants.is_none() && layout.static_samplers.is_none() {
+                let stages = stages.iter().map(|(visibility, reflection)| (*visibility, reflection)).collect::<Vec<_>>();
+                let pipelines = self.pmfx.pipelines.get_mut(pipeline_name).unwrap();
+                pipelines.get_mut(&permutation).unwrap().descriptor_layout = gfx::DescriptorLayout::from_reflection(&stages);
+            }
+            else {
+                for (_, reflection) in &stages {
+                    for missing in layout.find_missing_bindings(reflection) {
+                        println!("hotline_rs::pmfx:: [warning] pipeline {} binds {} (register {} space {}) which is not in its descriptor layout",
+                            pipeline_name, missing.name, missing.shader_register, missing.register_space);
+                    }
+                }
+            }
+        }
+        
+        // load the pipeline library for this pmfx if we have not already
+        if !self.pipeline_libraries.contains_key(&folder) {
+            let data = fs::read(get_pipeline_library_filepath(&folder)).ok();
+            let library = device.create_pipeline_library(data.as_deref())?;
+            self.pipeline_libraries.insert(folder.to_string(), library);
+        }
+        Ok(folder)
+    }
+
+    /// Create a ComputePipeline instance for pmfx_pipeline settings, used by compute views which have no RenderPass
+    pub fn create_compute_pipeline(&mut self, device: &D, pipeline_name: &str) -> Result<(), super::Error> {
+        if self.pmfx.pipelines.contains_key(pipeline_name) {
+            if self.compute_pipelines.contains_key(pipeline_name) {
+                return Ok(());
+            }
+            let folder = self.create_pipeline_shaders(device, pipeline_name)?;
+            let library = &self.pipeline_libraries[&folder];
+
+            // compute pipelines are not permuted, use the first one
+            if let Some((permutation, pipeline)) = self.pmfx.pipelines[pipeline_name].iter().next() {
+                if let Some(cs) = self.get_shader(&pipeline.cs) {
+                    println!("hotline_rs::pmfx:: creating pipeline: {}", pipeline_name);
+                    let library_name = format!("{}/{}/{}", pipeline_name, permutation, pipeline.hash);
+                    let pso = device.create_compute_pipeline_from_library(&gfx::ComputePipelineInfo {
+                        cs,
+                        descriptor_layout: pipeline.descriptor_layout.clone(),
+                    }, library, &library_name)?;
+                    println!("hotline_rs::pmfx:: compiled compute pipeline: {}", pipeline_name);
+                    self.compute_pipelines.insert(pipeline_name.to_string(), (pipeline.hash, pso));
+                    return Ok(());
+                }
+            }
+            Err(super::Error {
+                msg: format!("hotline_rs::pmfx:: pipeline: {} has no compute shader", pipeline_name),
+            })
+        }
+        else {
+            Err(super::Error {
+                msg: format!("hotline_rs::pmfx:: could not find pipeline: {}", pipeline_name),
+            })
+        }
+    }
+
     /// Create a RenderPipeline instance for the combination of pmfx_pipeline settings and an associated RenderPass
     pub fn create_pipeline(&mut self, device: &D, pipeline_name: &str, pass: &D::RenderPass) -> Result<(), super::Error> {              
         if self.pmfx.pipelines.contains_key(pipeline_name) {
             // first create shaders if necessary
-            let folder = self.pmfx_folders[pipeline_name].to_string();
-            for (_, pipeline) in self.pmfx.pipelines[pipeline_name].clone() {
-                self.create_shader(device, Path::new(&folder), &pipeline.vs)?;
-                self.create_shader(device, Path::new(&folder), &pipeline.ps)?;
-                self.create_shader(device, Path::new(&folder), &pipeline.cs)?;
-            }
-            
+            let folder = self.create_pipeline_shaders(device, pipeline_name)?;
+            let library = &self.pipeline_libraries[&folder];
+
             // create entry for this format if it does not exist
             let fmt = pass.get_format_hash();
             let format_pipeline = self.render_pipelines.entry(fmt).or_insert(HashMap::new());
@@ -945,46 +1897,82 @@ impl<D> Pmfx<D> where D: gfx::Device {
                 // we create a pipeline per-permutation
                 for (permutation, pipeline) in self.pmfx.pipelines[pipeline_name].clone() {    
                     // TODO: infer compute or graphics pipeline from pmfx
+                    // library names include the build hash so modified pipelines do not collide with stale entries
                     let cs = self.get_shader(&pipeline.cs);
                     if let Some(cs) = cs {
-                        let pso = device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+                        let library_name = format!("{}/{}/{}", pipeline_name, permutation, pipeline.hash);
+                        let pso = device.create_compute_pipeline_from_library(&gfx::ComputePipelineInfo {
                             cs,
                             descriptor_layout: pipeline.descriptor_layout.clone(),
-                        })?;
+                        }, library, &library_name)?;
                         println!("hotline_rs::pmfx:: compiled compute pipeline: {}", pipeline_name);
                         self.compute_pipelines.insert(pipeline_name.to_string(), (pipeline.hash, pso));
                     }
                     else {
                         let vertex_layout = pipeline.vertex_layout.as_ref().unwrap();
-                        let pso = device.create_render_pipeline(&gfx::RenderPipelineInfo {
-                            vs: self.get_shader(&pipeline.vs),
-                            fs: self.get_shader(&pipeline.ps),
-                            input_layout: vertex_layout.to_vec(),
-                            descriptor_layout: pipeline.descriptor_layout.clone(),
-                            raster_info: info_from_state(&pipeline.raster_state, &self.pmfx.raster_states),
-                            depth_stencil_info: info_from_state(&pipeline.depth_stencil_state, &self.pmfx.depth_stencil_states),
-                            blend_info: gfx::BlendInfo {
+                        let mask: u32 = permutation.parse().unwrap();
+                        let raster_info: gfx::RasterInfo = info_from_state(&pipeline.raster_state, &self.pmfx.raster_states);
+
+                        // each permutation also has a wireframe variant, which is a separate pso
+                        let wireframe_raster_info = gfx::RasterInfo {
+                            fill_mode: gfx::FillMode::Wireframe,
+                            ..raster_info
+                        };
+                        // pipelines without a blend state write a single target with blending disabled
+                        let blend_info = if let Some(blend_state) = &pipeline.blend_state {
+                            self.pmfx.blend_states.get(blend_state).cloned().ok_or_else(|| super::Error {
+                                msg: format!("hotline_rs::pmfx:: could not find blend state: {} for pipeline: {}",
+                                    blend_state, pipeline_name)
+                            })?
+                        }
+                        else {
+                            gfx::BlendInfo {
                                 alpha_to_coverage_enabled: false,
                                 independent_blend_enabled: false,
                                 render_target: vec![gfx::RenderTargetBlendInfo::default()],
-                            },
-                            topology: 
-                                if let Some(topology) = pipeline.topology {
-                                    topology
-                                }
-                                else {
-                                    gfx::Topology::TriangleList
-                                },
-                            patch_index: 0,
-                            pass,
-                        })?;
-                        
-                        println!("hotline_rs::pmfx:: compiled render pipeline: {}", pipeline_name);
-                        let format_pipeline = self.render_pipelines.get_mut(&fmt).unwrap();
-                        let permutations = format_pipeline.get_mut(pipeline_name).unwrap();  
-
-                        let mask = permutation.parse().unwrap();
-                        permutations.insert(mask, (pipeline.hash, pso));
+                            }
+                        };
+                        let blend_info = gfx::BlendInfo {
+                            alpha_to_coverage_enabled: pipeline.alpha_to_coverage
+                                .unwrap_or(blend_info.alpha_to_coverage_enabled),
+                            ..blend_info
+                        };
+
+                        let variants = [
+                            (mask, raster_info, String::new()),
+                            (mask | PERMUTATION_WIREFRAME, wireframe_raster_info, "/wireframe".to_string())
+                        ];
+
+                        for (mask, raster_info, suffix) in variants {
+                            let library_name = format!("{}/{}/{}/{}{}", pipeline_name, permutation, pipeline.hash, fmt, suffix);
+                            let pso = device.create_render_pipeline_from_library(&gfx::RenderPipelineInfo {
+                                vs: self.get_shader(&pipeline.vs),
+                                fs: self.get_shader(&pipeline.ps),
+                                hs: self.get_shader(&pipeline.hs),
+                                ds: self.get_shader(&pipeline.ds),
+                                gs: self.get_shader(&pipeline.gs),
+                                input_layout: vertex_layout.to_vec(),
+                                descriptor_layout: pipeline.descriptor_layout.clone(),
+                                raster_info,
+                                depth_stencil_info: info_from_state(&pipeline.depth_stencil_state, &self.pmfx.depth_stencil_states),
+                                blend_info: blend_info.clone(),
+                                topology: 
+                                    if let Some(topology) = pipeline.topology {
+                                        topology
+                                    }
+                                    else {
+                                        gfx::Topology::TriangleList
+                                    },
+                                patch_index: pipeline.patch_index.unwrap_or(0),
+                                sample_mask: pipeline.sample_mask.unwrap_or(u32::max_value()),
+                                pass,
+                            }, library, &library_name)?;
+                            
+                            println!("hotline_rs::pmfx:: compiled render pipeline: {}", pipeline_name);
+                            let format_pipeline = self.render_pipelines.get_mut(&fmt).unwrap();
+                            let permutations = format_pipeline.get_mut(pipeline_name).unwrap();  
+                            permutations.insert(mask, (pipeline.hash, pso));
+                        }
                     }
                 }
             }
@@ -998,6 +1986,15 @@ impl<D> Pmfx<D> where D: gfx::Device {
         }
     }
 
+    /// Serialize all pipeline libraries to disk alongside their pmfx so pipelines can be loaded without compiling
+    /// next time, call this on shutdown
+    pub fn save_pipeline_libraries(&self) -> Result<(), super::Error> {
+        for (folder, library) in &self.pipeline_libraries {
+            fs::write(get_pipeline_library_filepath(folder), library.serialize()?)?;
+        }
+        Ok(())
+    }
+
     /// Returns a pmfx defined pipeline compatible with the supplied format hash if it exists
     pub fn get_render_pipeline_for_format<'stack>(&'stack self, pipeline_name: &str, format_hash: u64) -> Result<&'stack D::RenderPipeline, super::Error> {
         self.get_render_pipeline_permutation_for_format(pipeline_name, 0, format_hash)
@@ -1042,8 +2039,22 @@ impl<D> Pmfx<D> where D: gfx::Device {
             self.reloader.complete_reload();
         }
 
+        // apply a stable power state toggled since the last frame, on failure the request is reverted
+        if self.stable_power_state != self.applied_stable_power_state {
+            match device.set_stable_power_state(self.stable_power_state) {
+                Ok(()) => self.applied_stable_power_state = self.stable_power_state,
+                Err(err) => {
+                    println!("hotline_rs::pmfx:: {}", err.msg);
+                    self.stable_power_state = self.applied_stable_power_state;
+                }
+            }
+        }
+
         // reset command buffers
         self.reset(swap_chain);
+
+        // record mips of textures loaded since the last frame
+        self.record_mip_generation(device, swap_chain);
         
         // reset errors
         // self.view_errors.lock().unwrap().clear();
@@ -1113,7 +2124,7 @@ impl<D> Pmfx<D> where D: gfx::Device {
 
                             let build_hash = self.pmfx.pipelines
                                 .get(name).unwrap()
-                                .get(&mask.to_string()).unwrap()
+                                .get(&(mask & !PERMUTATION_WIREFRAME).to_string()).unwrap()
                                 .hash;
 
                             if pipeline.0 != build_hash {
@@ -1155,19 +2166,23 @@ impl<D> Pmfx<D> where D: gfx::Device {
                     
                     // TODO: here we could only remove affected permutations
                     let format_pipelines = self.render_pipelines.get_mut(&pipeline.0).unwrap();
-                    format_pipelines.remove(&pipeline.1);
+                    if let Some(permutations) = format_pipelines.remove(&pipeline.1) {
+                        for (_, (_, pso)) in permutations {
+                            device.destroy_render_pipeline(pso);
+                        }
+                    }
 
                     // find first with the same format
                     let compatiblew_view = self.views.iter().find(|(_, view)| {
                         let pass = &view.1.lock().unwrap().pass;
-                        pass.get_format_hash() == pipeline.0
+                        pass.as_ref().map(|pass| pass.get_format_hash()) == Some(pipeline.0)
                     }).map(|v| v.0);
 
                     // create pipeline with the pass from compatible view
                     if let Some(compatiblew_view) = compatiblew_view {
                         let view = self.get_view(&compatiblew_view).unwrap().clone();
                         let view = view.lock().unwrap();
-                        self.create_pipeline(device, &pipeline.1, &view.pass).unwrap();
+                        self.create_pipeline(device, &pipeline.1, view.pass.as_ref().unwrap()).unwrap();
                     }
                     else {
                         println!("hotline::pmfx:: warning pipeline was not reloaded: {}", pipeline.1);
@@ -1194,6 +2209,12 @@ impl<D> Pmfx<D> where D: gfx::Device {
         for texture_name in texture_names {
             // remove the old and destroy
             let tex = self.textures.remove(texture_name).unwrap();
+            for rtv in tex.1.rt_views.values() {
+                device.destroy_render_target_view(*rtv);
+            }
+            for dsv in tex.1.ds_views.values().chain(tex.1.read_only_ds_views.values()) {
+                device.destroy_depth_stencil_view(*dsv);
+            }
             device.destroy_texture(tex.1.texture);
             // create with new dimensions from 'window_sizes'
             self.create_texture(device, &texture_name).unwrap();
@@ -1287,19 +2308,130 @@ impl<D> Pmfx<D> where D: gfx::Device {
     pub fn reset(&mut self, swap_chain: &D::SwapChain) {
         for (name, view) in &self.views {
             // rest only command buffers that are in use
-            if self.render_graph_execute_order.contains(name) {
+            if self.render_graph_execute_order.iter().any(|node| node.name == *name) {
                 let view = view.clone();
-                view.1.lock().unwrap().cmd_buf.reset(swap_chain);
+                let view = &mut view.1.lock().unwrap();
+                view.cmd_buf.reset(swap_chain);
+                view.cmd_buf.begin_timer(name);
+                // shading rate is reset with the command buffer, so it is set again each frame
+                if let (Some(rate), Some(_)) = (view.shading_rate, &view.pass) {
+                    view.cmd_buf.set_shading_rate(rate, [gfx::ShadingRateCombiner::Passthrough; 2]);
+                }
             }
         }
+
+        // record any requested readbacks
+        for (name, node) in &mut self.readbacks {
+            node.recorded = false;
+            if !self.readback_requests.contains(&node.texture) {
+                continue;
+            }
+            if let Some(tex) = self.textures.get(&node.texture) {
+                let tex = &tex.1.texture;
+                node.cmd_buf.reset(swap_chain);
+                node.cmd_buf.transition_barrier(&gfx::TransitionBarrier {
+                    texture: Some(tex),
+                    buffer: None,
+                    state_before: node.state,
+                    state_after: ResourceState::CopySrc,
+                });
+                let request = node.cmd_buf.read_back_texture(swap_chain, tex, 0);
+                node.cmd_buf.transition_barrier(&gfx::TransitionBarrier {
+                    texture: Some(tex),
+                    buffer: None,
+                    state_before: ResourceState::CopySrc,
+                    state_after: node.state,
+                });
+                match request.and_then(|request| node.cmd_buf.close().map(|_| request)) {
+                    Ok(request) => {
+                        node.request = Some(request);
+                        node.recorded = true;
+                    }
+                    Err(err) => {
+                        self.view_errors.lock().unwrap().entry(name.to_string()).or_insert(err.msg);
+                    }
+                }
+            }
+        }
+        self.readback_requests.clear();
+    }
+
+    /// Records mip generation of pending textures into `mips_cmd_buf`, which is submitted at the start of `execute`
+    /// so views sample complete mip chains
+    fn record_mip_generation(&mut self, device: &mut D, swap_chain: &D::SwapChain) {
+        self.mips_recorded = false;
+        if self.pending_mips.is_empty() {
+            return;
+        }
+        let cmd_buf = self.mips_cmd_buf.get_or_insert_with(|| device.create_cmd_buf(2));
+        cmd_buf.reset(swap_chain);
+        for texture_name in self.pending_mips.drain() {
+            if let Some((_, tracked)) = self.textures.get(&texture_name) {
+                if let Err(err) = device.generate_mips(cmd_buf, &tracked.texture) {
+                    self.view_errors.lock().unwrap().entry(texture_name).or_insert(err.msg);
+                }
+            }
+        }
+        match cmd_buf.close() {
+            Ok(()) => self.mips_recorded = true,
+            Err(err) => {
+                self.view_errors.lock().unwrap().entry("generate_mips".to_string()).or_insert(err.msg);
+            }
+        }
+    }
+
+    /// Enable or disable a graph view at runtime without rebuilding the render graph. Disabled views are closed but
+    /// not submitted in `execute` and their transitions are kept so resource state tracking remains correct
+    pub fn set_view_enabled(&mut self, view_name: &str, enabled: bool) {
+        self.view_enabled.insert(view_name.to_string(), enabled);
+    }
+
+    /// Returns true if the graph view is enabled, views are enabled by default
+    pub fn is_view_enabled(&self, view_name: &str) -> bool {
+        self.view_enabled.get(view_name).copied().unwrap_or(true)
+    }
+
+    /// Toggle wireframe rendering at runtime, render functions which opt in select the `PERMUTATION_WIREFRAME`
+    /// variant of their pipelines while this is enabled
+    pub fn set_wireframe(&mut self, enabled: bool) {
+        self.wireframe = enabled;
+    }
+
+    /// Returns true if wireframe rendering has been enabled with `set_wireframe`
+    pub fn is_wireframe(&self) -> bool {
+        self.wireframe
+    }
+
+    /// Request the gpu is locked to its base clock so view timings are reproducible, the request is applied with
+    /// `Device::set_stable_power_state` on the next `new_frame` and reverted if the device rejects it
+    pub fn set_stable_power_state(&mut self, enabled: bool) {
+        self.stable_power_state = enabled;
+    }
+
+    /// Returns true if a stable power state has been requested and not rejected by the device
+    pub fn is_stable_power_state(&self) -> bool {
+        self.stable_power_state
+    }
+
+    /// Request a readback of a texture which has a `readback` node in the active render graph. The copy is recorded
+    /// on the next call to `reset` and the result can be polled through `get_readback`
+    pub fn request_readback(&mut self, texture_name: &str) {
+        self.readback_requests.insert(texture_name.to_string());
+    }
+
+    /// Returns the most recent readback request issued for a texture, poll `is_complete` before mapping the data
+    pub fn get_readback(&self, texture_name: &str) -> Option<&D::ReadBackRequest> {
+        self.readbacks.values()
+            .filter(|node| node.texture == texture_name)
+            .find_map(|node| node.request.as_ref())
     }
 
     /// Returns a vector of information to call render functions. It returns a tuple (function_name, view_name)
     /// which is called as so: `function_name(view)` so functions can be re-used for different views
     pub fn get_render_graph_function_info(&self, render_graph: &str) -> Vec<(String, String)> {
         if self.pmfx.render_graphs.contains_key(render_graph) {
-            self.pmfx.render_graphs[render_graph].iter().map(|graph|{
-                (graph.1.function.to_string(), graph.0.to_string())
+            self.pmfx.render_graphs[render_graph].iter().filter_map(|graph|{
+                graph.1.function.as_ref().map(|function| (function.to_string(), graph.0.to_string()))
             }).collect()
         }
         else {
@@ -1330,27 +2462,120 @@ impl<D> Pmfx<D> where D: gfx::Device {
         */
     }
 
-    pub fn get_render_graph_execute_order(&self) -> &Vec<String> {
+    pub fn get_render_graph_execute_order(&self) -> &Vec<RenderGraphNode> {
         &self.render_graph_execute_order
     }
 
-    /// Execute command buffers in order
+    /// Execute command buffers in order, returns an error if the device has been removed
     pub fn execute(
         &mut self,
-        device: &mut D) {
+        device: &mut D) -> Result<(), super::Error> {
+        // fence values signalled by compute queue views this frame, disabled views do not signal
+        let mut fence_value = self.queue_fence_value;
+        let mut compute_signals = HashMap::new();
+
+        // mips generated this frame are submitted before any views which read them
+        if self.mips_recorded {
+            if let Some(cmd_buf) = &self.mips_cmd_buf {
+                device.execute(cmd_buf)?;
+            }
+            self.mips_recorded = false;
+        }
+
         for node in &self.render_graph_execute_order {
+            // wait on the gpu for views on another queue this node depends on
+            if let Some(fence) = &self.queue_fence {
+                for wait in &node.waits {
+                    if let Some(value) = compute_signals.get(wait) {
+                        device.queue_wait(node.queue, fence, *value)?;
+                    }
+                }
+            }
+
+            let node = &node.name;
             if self.barriers.contains_key(node) {
                 // transition barriers
-                device.execute(&self.barriers[node]);
+                device.execute(&self.barriers[node])?;
+            }
+            else if let Some(readback) = self.readbacks.get(node) {
+                // copy a texture into a readback buffer
+                if readback.recorded {
+                    device.execute(&readback.cmd_buf)?;
+                }
             }
             else if self.views.contains_key(node) {
-                // dispatch a view
                 let view = self.views[node].clone();
                 let view = &mut view.1.lock().unwrap();
-                view.cmd_buf.close().unwrap();
-                device.execute(&view.cmd_buf);
+
+                // disabled views are still reset each frame so render functions can record into them, they are
+                // closed without being submitted so the command buffer can be reset again next frame
+                if !self.is_view_enabled(node) {
+                    view.cmd_buf.end_timer();
+                    view.cmd_buf.close()?;
+                    continue;
+                }
+
+                // dispatch a view
+                // compute views are recorded here, after any commands from an optional view function
+                if view.pass.is_none() {
+                    if let Some((group_count, numthreads)) = view.dispatch {
+                        if let Some(pipeline) = self.get_compute_pipeline(&view.view_pipeline) {
+                            view.cmd_buf.set_compute_pipeline(pipeline);
+                            view.cmd_buf.set_compute_heap(0, device.get_shader_heap());
+                            view.cmd_buf.dispatch(group_count, numthreads);
+                        }
+                        else {
+                            self.log_error(node, &format!("hotline_rs::pmfx:: missing compute pipeline: {}", view.view_pipeline));
+                        }
+                    }
+                }
+
+                view.cmd_buf.end_timer();
+                view.cmd_buf.close()?;
+                match (view.cmd_buf.get_queue_type(), &self.queue_fence) {
+                    (gfx::QueueType::Compute, Some(fence)) => {
+                        // previous graphics work and barriers must complete before the compute view starts
+                        fence_value += 1;
+                        device.queue_signal(gfx::QueueType::Graphics, fence, fence_value)?;
+                        device.queue_wait(gfx::QueueType::Compute, fence, fence_value)?;
+                        device.execute(&view.cmd_buf)?;
+                        fence_value += 1;
+                        device.queue_signal(gfx::QueueType::Compute, fence, fence_value)?;
+                        compute_signals.insert(node.to_string(), fence_value);
+                    }
+                    _ => {
+                        device.execute(&view.cmd_buf)?;
+                    }
+                }
             }
         }
+        self.queue_fence_value = fence_value;
+        self.view_timings = device.get_timer_results();
+        Ok(())
+    }
+
+    /// Records all enabled views in the render graph in parallel on the rayon thread pool by calling
+    /// `record(pmfx, view)` for each one, then executes the command buffers in graph order on the calling thread.
+    /// Each view owns its own command buffer so `record` must only write to the `view` it is passed
+    pub fn execute_parallel<F>(
+        &mut self,
+        device: &mut D,
+        record: F) -> Result<(), super::Error> where F: Fn(&Self, &mut View<D>) + Sync, Self: Sync {
+        let views : Vec<ViewRef<D>> = self.render_graph_execute_order.iter().filter_map(|node| {
+            if self.is_view_enabled(&node.name) {
+                self.views.get(&node.name).map(|view| view.1.clone())
+            }
+            else {
+                None
+            }
+        }).collect();
+
+        let pmfx = &*self;
+        views.par_iter().for_each(|view| {
+            record(pmfx, &mut view.lock().unwrap());
+        });
+
+        self.execute(device)
     }
 
     /// Log an error with an assosiated view and message.
@@ -1404,6 +2629,24 @@ impl<D, A> imgui::UserInterface<D, A> for Pmfx<D> where D: gfx::Device, A: os::A
                 }
                 imgui.separator();
 
+                imgui.text("Views");
+                imgui.separator();
+                imgui.checkbox("Stable Power State", &mut self.stable_power_state);
+                for node in &self.render_graph_execute_order {
+                    let node = &node.name;
+                    if self.views.contains_key(node) {
+                        let mut enabled = self.view_enabled.get(node).copied().unwrap_or(true);
+                        if imgui.checkbox(node, &mut enabled) {
+                            self.view_enabled.insert(node.to_string(), enabled);
+                        }
+                        if let Some(ms) = self.view_timings.get(node) {
+                            imgui.same_line();
+                            imgui.text(&format!("{:.3}ms", ms));
+                        }
+                    }
+                }
+                imgui.separator();
+
                 imgui.text("Cameras");
                 imgui.separator();
                 for camera in self.cameras.keys() {
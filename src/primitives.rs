@@ -18,6 +18,32 @@ pub struct Vertex3D {
     pub bitangent: Vec3f,
 }
 
+impl Vertex3D {
+    /// Returns the input layout matching `Vertex3D` in slot 0.
+    /// Semantics are POSITION, TEXCOORD, NORMAL, TANGENT and BITANGENT.
+    /// All of the primitive builders generate tangents, so their meshes can be used for normal mapping.
+    pub fn input_layout() -> gfx::InputLayout {
+        let elements = [
+            ("POSITION", gfx::Format::RGB32f, 0),
+            ("TEXCOORD", gfx::Format::RG32f, 12),
+            ("NORMAL", gfx::Format::RGB32f, 20),
+            ("TANGENT", gfx::Format::RGB32f, 32),
+            ("BITANGENT", gfx::Format::RGB32f, 44),
+        ];
+        elements.iter().map(|(semantic, format, offset)| {
+            gfx::InputElementInfo {
+                semantic: String::from(*semantic),
+                index: 0,
+                format: *format,
+                input_slot: 0,
+                aligned_byte_offset: *offset,
+                input_slot_class: gfx::InputSlotClass::PerVertex,
+                step_rate: 0,
+            }
+        }).collect()
+    }
+}
+
 /// Generic structure for simple 2D textured meshes
 #[derive(Clone)]
 #[repr(C)]
@@ -51,10 +77,60 @@ fn basis_from_axis(axis: Vec3f) -> (Vec3f, Vec3f, Vec3f) {
     (right, up, at)
 }
 
+/// Computes per-vertex tangents and bitangents from the texcoords of the triangles in `indices`, tangents are
+/// orthogonalised against the existing vertex normals and bitangents are flipped for mirrored uv's
+pub fn compute_tangents(vertices: &mut [Vertex3D], indices: &[usize]) {
+    let mut tangents = vec![Vec3f::zero(); vertices.len()];
+    let mut bitangents = vec![Vec3f::zero(); vertices.len()];
+
+    // accumulate uv aligned tangents for each triangle
+    for tri in indices.chunks_exact(3) {
+        let (i0, i1, i2) = (tri[0], tri[1], tri[2]);
+        let e1 = vertices[i1].position - vertices[i0].position;
+        let e2 = vertices[i2].position - vertices[i0].position;
+        let uv1 = vertices[i1].texcoord - vertices[i0].texcoord;
+        let uv2 = vertices[i2].texcoord - vertices[i0].texcoord;
+
+        // skip triangles with degenerate uv's
+        let det = uv1.x * uv2.y - uv2.x * uv1.y;
+        if abs(det) < f32::small_epsilon() {
+            continue;
+        }
+
+        let r = 1.0 / det;
+        let t = (e1 * uv2.y - e2 * uv1.y) * r;
+        let b = (e2 * uv1.x - e1 * uv2.x) * r;
+        for i in tri {
+            tangents[*i] += t;
+            bitangents[*i] += b;
+        }
+    }
+
+    // gram-schmidt orthogonalise and keep the original basis if we could not compute a tangent
+    for (i, v) in vertices.iter_mut().enumerate() {
+        let n = v.normal;
+        let t = tangents[i] - n * dot(n, tangents[i]);
+        if mag(t) < f32::small_epsilon() {
+            continue;
+        }
+        let t = normalize(t);
+        let handedness = if dot(cross(n, t), bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+        v.tangent = t;
+        v.bitangent = cross(n, t) * handedness;
+    }
+}
+
 /// Utility to create faceted meshes with varying index sizes depending on the index requirements
 fn create_mesh_3d<D: gfx::Device>(dev: &mut D, vertices: Vec<Vertex3D>, indices: Vec<usize>) -> pmfx::Mesh<D> {
-    let max_index = vertices.len();
-    let index_buffer = if max_index > 65535 {
+    // pick 16 bit indices if all vertices can be addressed, otherwise use 32 bit
+    let index_format = if vertices.len() > 65536 {
+        gfx::Format::R32u
+    }
+    else {
+        gfx::Format::R16u
+    };
+
+    let index_buffer = if matches!(index_format, gfx::Format::R32u) {
         let mut indices32 : Vec<u32> = Vec::new();
         for i in &indices {
             indices32.push(*i as u32);
@@ -98,7 +174,8 @@ fn create_mesh_3d<D: gfx::Device>(dev: &mut D, vertices: Vec<Vertex3D>, indices:
             Some(vertices.as_slice())
         ).unwrap(),
         ib: index_buffer,
-        num_indices: indices.len() as u32
+        num_indices: indices.len() as u32,
+        index_format
     }
 }
 
@@ -156,7 +233,8 @@ pub fn create_unit_quad_mesh<D: gfx::Device>(dev: &mut D) -> pmfx::Mesh<D> {
             },
             Some(indices.as_slice())
         ).unwrap(),
-        num_indices: 6
+        num_indices: 6,
+        index_format: gfx::Format::R16u
     } 
 }
 
@@ -218,7 +296,8 @@ pub fn create_billboard_mesh<D: gfx::Device>(dev: &mut D) -> pmfx::Mesh<D> {
             },
             Some(indices.as_slice())
         ).unwrap(),
-        num_indices: 6
+        num_indices: 6,
+        index_format: gfx::Format::R16u
     } 
 }
 
@@ -281,6 +360,55 @@ pub fn create_plane_mesh<D: gfx::Device>(dev: &mut D, subdivisions: u32) -> pmfx
     create_mesh_3d(dev, vertices, indices)
 }
 
+/// Returns the vertices and indices of a unit grid facing +y direction with `rows` x `cols` quads sharing vertices,
+/// uv's span 0-1 over the whole grid. Vertex heights can be displaced for terrain before creating buffers, triangles
+/// are wound the same as the other primitives so the +y side is front facing
+pub fn create_grid_vertices(rows: usize, cols: usize) -> (Vec<Vertex3D>, Vec<usize>) {
+    let rows = max(rows, 1);
+    let cols = max(cols, 1);
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for r in 0..rows+1 {
+        let v = r as f32 / rows as f32;
+        for c in 0..cols+1 {
+            let u = c as f32 / cols as f32;
+            vertices.push(Vertex3D {
+                position: vec3f(-1.0 + u * 2.0, 0.0, -1.0 + v * 2.0),
+                texcoord: vec2f(u, v),
+                normal: Vec3f::unit_y(),
+                tangent: vec3f(1.0, 0.0, 0.0),
+                bitangent: vec3f(0.0, 0.0, 1.0),
+            });
+        }
+    }
+
+    let stride = cols + 1;
+    for r in 0..rows {
+        for c in 0..cols {
+            let i0 = r * stride + c;
+            let i1 = i0 + 1;
+            let i2 = i0 + stride + 1;
+            let i3 = i0 + stride;
+            indices.extend(vec![
+                i0, i1, i2,
+                i0, i2, i3
+            ]);
+        }
+    }
+
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
+}
+
+/// Create an indexed unit grid mesh facing +y direction with `rows` x `cols` quads sharing vertices, see
+/// `create_grid_vertices`
+pub fn create_grid_mesh<D: gfx::Device>(dev: &mut D, rows: usize, cols: usize) -> pmfx::Mesh<D> {
+    let (vertices, indices) = create_grid_vertices(rows, cols);
+    create_mesh_3d(dev, vertices, indices)
+}
+
 /// Create a an indexed unit tetrahedron mesh instance
 pub fn create_tetrahedron_mesh<D: gfx::Device>(dev: &mut D) -> pmfx::Mesh<D> {
     let pos = vec3f(0.0, -INV_PHI, 0.0);
@@ -539,6 +667,9 @@ pub fn create_cube_mesh<D: gfx::Device>(dev: &mut D) -> pmfx::Mesh<D> {
         20, 22, 21, 22, 20, 23   // bottom face
     ];
 
+    let mut vertices = vertices;
+    compute_tangents(&mut vertices, &indices);
+
     create_mesh_3d(dev, vertices, indices)
 }
 
@@ -856,7 +987,11 @@ pub fn create_icosasphere_mesh<D: gfx::Device>(dev: &mut D, subdivisions: u32) -
         }
     }
 
-    create_faceted_mesh_3d(dev, vertices)
+    // align tangents with uv's for normal mapping
+    let indices = (0..vertices.len()).collect::<Vec<usize>>();
+    compute_tangents(&mut vertices, &indices);
+
+    create_mesh_3d(dev, vertices, indices)
 }
 
 /// Create an indexed smooth sphere with subdivided icosophere vertices and smooth normals
@@ -877,7 +1012,57 @@ pub fn create_sphere_mesh<D: gfx::Device>(dev: &mut D, subdivisions: u32) -> pmf
         v.texcoord = Vec2f::new(x as f32, y as f32);
     }
 
-    create_faceted_mesh_3d(dev, vertices)
+    // tangents need to be re-aligned with the smooth normals and new uv's
+    let indices = (0..vertices.len()).collect::<Vec<usize>>();
+    compute_tangents(&mut vertices, &indices);
+
+    create_mesh_3d(dev, vertices, indices)
+}
+
+/// Create an indexed smooth uv sphere with `latitude_segments` rings from pole to pole and `longitude_segments` slices
+/// around the y-axis, the seam vertices are duplicated so uv's wrap 0-1 in both directions
+pub fn create_uv_sphere_mesh<D: gfx::Device>(dev: &mut D, latitude_segments: usize, longitude_segments: usize) -> pmfx::Mesh<D> {
+    let latitude_segments = max(latitude_segments, 2);
+    let longitude_segments = max(longitude_segments, 3);
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for lat in 0..latitude_segments+1 {
+        let v = lat as f32 / latitude_segments as f32;
+        let theta = v * f32::pi();
+        for lon in 0..longitude_segments+1 {
+            let u = lon as f32 / longitude_segments as f32;
+            let phi = u * f32::two_pi();
+            let p = vec3f(sin(theta) * cos(phi), cos(theta), sin(theta) * sin(phi));
+            let t = vec3f(-sin(phi), 0.0, cos(phi));
+            vertices.push(Vertex3D {
+                position: p,
+                texcoord: vec2f(u, v),
+                normal: p,
+                tangent: t,
+                bitangent: cross(p, t),
+            });
+        }
+    }
+
+    // the first and last rings collapse to a single point so only emit one triangle per quad there
+    let stride = longitude_segments + 1;
+    for lat in 0..latitude_segments {
+        for lon in 0..longitude_segments {
+            let a = lat * stride + lon;
+            let b = a + stride;
+            if lat != 0 {
+                indices.extend(vec![a, b, a + 1]);
+            }
+            if lat != latitude_segments - 1 {
+                indices.extend(vec![a + 1, b, b + 1]);
+            }
+        }
+    }
+
+    compute_tangents(&mut vertices, &indices);
+    create_mesh_3d(dev, vertices, indices)
 }
 
 pub fn create_cylinder_mesh<D: gfx::Device>(dev: &mut D, segments: usize) -> pmfx::Mesh<D> {
@@ -4,6 +4,7 @@
 use std::collections::HashMap;
 
 use hotline_rs::prelude::*;
+use hotline_rs::primitives;
 
 #[repr(C)]
 struct Vertex {
@@ -11,6 +12,65 @@ struct Vertex {
     color: [f32; 4],
 }
 
+/// Creates an app named `name` and a device from `device_info`, for tests which do not present frames
+fn create_test_device(
+    name: &str,
+    device_info: &gfx::DeviceInfo
+) -> (os_platform::App, gfx_platform::Device) {
+    let app = os_platform::App::create(os::AppInfo {
+        name: String::from(name),
+        window: false,
+        num_buffers: 0,
+        dpi_aware: true,
+    });
+    let dev = gfx_platform::Device::create(device_info);
+    (app, dev)
+}
+
+/// Creates an app, a device from `device_info` and a 640x480 window titled `name`, for tests which present frames
+fn create_test_window(
+    name: &str,
+    device_info: &gfx::DeviceInfo
+) -> (os_platform::App, gfx_platform::Device, os_platform::Window) {
+    let (mut app, dev) = create_test_device(name, device_info);
+    let win = app.create_window(os::WindowInfo {
+        title: String::from(name),
+        rect: os::Rect {
+            x: 0,
+            y: 0,
+            width: 640,
+            height: 480,
+        },
+        style: os::WindowStyleFlags::NONE,
+        parent_handle: None,
+    });
+    (app, dev, win)
+}
+
+/// Creates a test window with a double buffered RGBA8n swap chain and a command buffer to record each frame
+fn create_test_swap_chain(
+    name: &str,
+    device_info: &gfx::DeviceInfo
+) -> Result<(
+    os_platform::App,
+    gfx_platform::Device,
+    os_platform::Window,
+    gfx_platform::SwapChain,
+    gfx_platform::CmdBuf
+), hotline_rs::Error> {
+    let (app, mut dev, win) = create_test_window(name, device_info);
+    let swap_chain = dev.create_swap_chain::<os_platform::App>(&gfx::SwapChainInfo {
+        num_buffers: 2,
+        format: gfx::Format::RGBA8n,
+        clear_colour: None,
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
+    }, &win)?;
+    let cmd = dev.create_cmd_buf(2);
+    Ok((app, dev, win, swap_chain, cmd))
+}
+
 #[test]
 fn create_app() {
     let _app = os_platform::App::create(os::AppInfo {
@@ -34,7 +94,23 @@ fn create_d3d12_device() {
         shader_heap_size: 1,
         render_target_heap_size: 1,
         depth_stencil_heap_size: 1,
+        sampler_heap_size: 0,
+    });
+}
+
+#[test]
+fn adapter_feature_info() {
+    let (_app, dev) = create_test_device("adapter_feature_info", &gfx::DeviceInfo {
+        ..Default::default()
     });
+
+    // all d3d12 devices support at least shader model 5.1 and resource binding tier 1
+    let info = dev.get_adapter_info();
+    assert!(info.shader_model >= (5, 1));
+    assert!(info.resource_binding_tier >= 1);
+    assert!(info.wave_lane_count_min <= info.wave_lane_count_max);
+    assert_eq!(info.mesh_shader_supported(), info.mesh_shader_tier > 0);
+    assert_eq!(info.raytracing_supported(), info.raytracing_tier > 0);
 }
 
 #[test]
@@ -78,6 +154,7 @@ fn swap_chain_buffer() -> Result<(), hotline_rs::Error> {
         shader_heap_size: 0,
         render_target_heap_size: 2,
         depth_stencil_heap_size: 0,
+        sampler_heap_size: 0,
     });
     let mut win = app.create_window(os::WindowInfo {
         title: String::from("swap chain buffering"),
@@ -96,6 +173,9 @@ fn swap_chain_buffer() -> Result<(), hotline_rs::Error> {
         num_buffers: 2,
         format: gfx::Format::RGBA8n,
         clear_colour: None,
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
 
     let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&swap_chain_info, &win)?;
@@ -142,8 +222,8 @@ fn swap_chain_buffer() -> Result<(), hotline_rs::Error> {
 
         cmd.close()?;
 
-        dev.execute(&cmd);
-        swap_chain.swap(&dev);
+        dev.execute(&cmd)?;
+        swap_chain.swap(&dev)?;
 
         std::thread::sleep(std::time::Duration::from_millis(60));
         i = (i + 1) % clears_colours.len();
@@ -160,6 +240,82 @@ fn swap_chain_buffer() -> Result<(), hotline_rs::Error> {
     Ok(())
 }
 
+#[test]
+fn multi_window_swap_chains() -> Result<(), hotline_rs::Error> {
+    let (mut app, mut dev) = create_test_device("multi_window_swap_chains", &gfx::DeviceInfo {
+        adapter_name: None,
+        shader_heap_size: 0,
+        render_target_heap_size: 4,
+        depth_stencil_heap_size: 0,
+        sampler_heap_size: 0,
+    });
+
+    let swap_chain_info = gfx::SwapChainInfo {
+        num_buffers: 2,
+        format: gfx::Format::RGBA8n,
+        clear_colour: Some(gfx::ClearColour {
+            r: 0.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
+    };
+
+    let mut windows = Vec::new();
+    let mut swap_chains = Vec::new();
+    let mut cmd_bufs = Vec::new();
+    for i in 0..2 {
+        let win = app.create_window(os::WindowInfo {
+            title: format!("multi window {}", i),
+            rect: os::Rect {
+                x: i * 640,
+                y: 0,
+                width: 640,
+                height: 480,
+            },
+            style: os::WindowStyleFlags::NONE,
+            parent_handle: None,
+        });
+        swap_chains.push(dev.create_swap_chain::<os_platform::App>(&swap_chain_info, &win)?);
+        cmd_bufs.push(dev.create_cmd_buf(2));
+        windows.push(win);
+    }
+
+    let mut count = 0;
+    while app.run() {
+        for i in 0..windows.len() {
+            windows[i].update(&mut app);
+            swap_chains[i].update::<os_platform::App>(&mut dev, &windows[i], &mut cmd_bufs[i]);
+
+            cmd_bufs[i].reset(&swap_chains[i]);
+
+            let mut pass = swap_chains[i].get_backbuffer_pass_mut();
+            cmd_bufs[i].begin_render_pass(&mut pass);
+            cmd_bufs[i].end_render_pass();
+
+            cmd_bufs[i].close()?;
+
+            dev.execute(&cmd_bufs[i])?;
+            swap_chains[i].swap(&dev)?;
+        }
+
+        count += 1;
+        if count > 3 {
+            break;
+        }
+    }
+
+    for i in 0..swap_chains.len() {
+        swap_chains[i].wait_for_last_frame();
+        cmd_bufs[i].reset(&swap_chains[i]);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn draw_triangle() -> Result<(), hotline_rs::Error> {
     let mut app = os_platform::App::create(os::AppInfo {
@@ -197,6 +353,9 @@ fn draw_triangle() -> Result<(), hotline_rs::Error> {
             b: 0.60,
             a: 1.00,
         }),
+        max_frame_latency: None,
+        sync_interval: 1,
+        usage: gfx::TextureUsage::NONE,
     };
 
     let mut swap_chain = device.create_swap_chain::<os_platform::App>(&swap_chain_info, &window)?;
@@ -273,6 +432,9 @@ fn draw_triangle() -> Result<(), hotline_rs::Error> {
     let pso = device.create_render_pipeline(&gfx::RenderPipelineInfo {
         vs: Some(&vs),
         fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
         input_layout: vec![
             gfx::InputElementInfo {
                 semantic: String::from("POSITION"),
@@ -303,6 +465,7 @@ fn draw_triangle() -> Result<(), hotline_rs::Error> {
         },
         topology: gfx::Topology::TriangleList,
         patch_index: 0,
+        sample_mask: u32::max_value(),
         pass: swap_chain.get_backbuffer_pass(),
     })?;
 
@@ -328,10 +491,10 @@ fn draw_triangle() -> Result<(), hotline_rs::Error> {
         cmd.close()?;
 
         // execute command buffer
-        device.execute(&cmd);
+        device.execute(&cmd)?;
 
         // swap for the next frame
-        swap_chain.swap(&device);
+        swap_chain.swap(&device)?;
 
         break;
     }
@@ -343,186 +506,3301 @@ fn draw_triangle() -> Result<(), hotline_rs::Error> {
 }
 
 #[test]
-fn align_tests() {
-    // pow2
-    let val = gfx::align_pow2(101, 256);
-    assert_eq!(val % 256, 0);
-    let val = gfx::align_pow2(8861, 64);
-    assert_eq!(val % 64, 0);
-    let val = gfx::align_pow2(1280, 128);
-    assert_eq!(val % 128, 0);
-    let val = gfx::align_pow2(5, 4);
-    assert_eq!(val % 4, 0);
-    let val = gfx::align_pow2(19, 2);
-    assert_eq!(val % 2, 0);
-    // non pow2
-    let val = gfx::align(92, 133);
-    assert_eq!(val % 133, 0);
-    let val = gfx::align(172, 201);
-    assert_eq!(val % 201, 0);
-    let val = gfx::align(288, 1177);
-    assert_eq!(val % 1177, 0);
-    let val = gfx::align(1092, 52);
-    assert_eq!(val % 52, 0);
-    let val = gfx::align(5568, 21);
-    assert_eq!(val % 21, 0);
-}
+fn set_multiple_viewports() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("set_multiple_viewports", &gfx::DeviceInfo {
+        ..Default::default()
+    });
 
+    // split a 2x1 atlas into 2 viewports with matching scissors
+    let viewports = (0..2).map(|i| gfx::Viewport {
+        x: i as f32 * 256.0,
+        y: 0.0,
+        width: 256.0,
+        height: 256.0,
+        min_depth: 0.0,
+        max_depth: 1.0,
+    }).collect::<Vec<gfx::Viewport>>();
+    let scissors = viewports.iter().map(|vp| gfx::ScissorRect {
+        left: vp.x as i32,
+        top: vp.y as i32,
+        right: (vp.x + vp.width) as i32,
+        bottom: (vp.y + vp.height) as i32,
+    }).collect::<Vec<gfx::ScissorRect>>();
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.set_viewports(&viewports);
+    cmd.set_scissor_rects(&scissors);
+    cmd.set_viewport(&viewports[0]);
+    cmd.set_scissor_rect(&scissors[0]);
+    cmd.close()?;
+    device.execute(&cmd)?;
 
-// client tests must run 1 at a time, this boots the client with empty user info
-fn boot_empty_client() {
-    println!("test: boot_empty_client");
-    let config = client::UserConfig {
-        main_window_rect: HotlineInfo::default().window_rect,
-        console_window_rect: None,
-        plugins: None,
-        plugin_data: Some(HashMap::new())
-    };
+    Ok(())
+}
 
-    // create client
-    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
-        name: "boot_empty_client".to_string(),
-        user_config: Some(config),
+#[test]
+fn grow_shader_heap() -> Result<(), hotline_rs::Error> {
+    use gfx::Heap;
+    let (_app, mut device) = create_test_device("grow_shader_heap", &gfx::DeviceInfo {
+        shader_heap_size: 1,
         ..Default::default()
-    }).unwrap();
-    
-    // run
-    ctx.run_once();
-}
+    });
 
-/// Load the basic empty plugin, should print and close gracefully
-/// plugin ui
-/// plugin unload
-/// plugin setup
-/// plugin update
-fn boot_client_empty_plugin() {
-    println!("test: boot_client_empty_plugin");
-    let mut config = client::UserConfig {
-        main_window_rect: HotlineInfo::default().window_rect,
-        console_window_rect: None,
-        plugins: Some(HashMap::new()),
-        plugin_data: Some(HashMap::new())
+    let info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
     };
 
-    // empty plugin
-    if let Some(plugins) = &mut config.plugins {
-        plugins.insert("empty".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../plugins")
-        });
-    }
-
-    // create client
-    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
-        name: "boot_client_empty_plugin".to_string(),
-        user_config: Some(config),
-        ..Default::default()
-    }).unwrap();
-    
-    // run
-    ctx.run_once();
-}
+    // the heap is full after the first texture and grows automatically
+    let tex0 = device.create_texture::<u8>(&info, None)?;
+    let tex1 = device.create_texture::<u8>(&info, None)?;
 
-/// Boots the client with a plugin that does not exist, should load gracefully and notify the missing plugin
-fn boot_client_missing_plugin() {
-    println!("test: boot_client_missing_plugin");
-    let mut config = client::UserConfig {
-        main_window_rect: HotlineInfo::default().window_rect,
-        console_window_rect: None,
-        plugins: Some(HashMap::new()),
-        plugin_data: Some(HashMap::new())
-    };
+    // grow explicitly and allocate again, existing indices must remain stable
+    device.get_shader_heap_mut().grow(8)?;
+    let tex2 = device.create_texture::<u8>(&info, None)?;
+    assert_eq!(tex0.get_srv_index(), Some(0));
+    assert_eq!(tex1.get_srv_index(), Some(1));
+    assert_eq!(tex2.get_srv_index(), Some(2));
 
-    // empty plugin
-    if let Some(plugins) = &mut config.plugins {
-        plugins.insert("missing".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../missing")
-        });
-    }
+    Ok(())
+}
 
-    // create client
-    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
-        name: "boot_client_missing_plugin".to_string(),
-        user_config: Some(config),
+#[test]
+fn chain_render_target_heap() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("chain_render_target_heap", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        depth_stencil_heap_size: 1,
         ..Default::default()
-    }).unwrap();
-    
-    // run
-    ctx.run_once();
-}
+    });
 
-/// Boots the client with the ecs plugin and ecs_demos plugin but with no `PluginData`
-fn boot_client_ecs_plugin() {
-    println!("test: boot_client_ecs_plugin");
-    let mut config = client::UserConfig {
-        main_window_rect: HotlineInfo::default().window_rect,
-        console_window_rect: None,
-        plugins: Some(HashMap::new()),
-        plugin_data: Some(HashMap::new()),
+    let rt_info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
     };
 
-    // ecs plugin with no demo active
-    if let Some(plugins) = &mut config.plugins {
-        plugins.insert("ecs".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../plugins")
-        });
-        plugins.insert("ecs_demos".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../plugins")
-        });
+    let ds_info = gfx::TextureInfo {
+        format: gfx::Format::D24nS8u,
+        usage: gfx::TextureUsage::DEPTH_STENCIL,
+        initial_state: gfx::ResourceState::DepthStencil,
+        ..rt_info
+    };
+
+    // allocate well beyond the initial heap sizes, new heaps are chained on when full
+    let mut textures = Vec::new();
+    for _ in 0..8 {
+        textures.push(device.create_texture::<u8>(&rt_info, None)?);
+        textures.push(device.create_texture::<u8>(&ds_info, None)?);
     }
 
-    // create client
-    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
-        name: "boot_client_ecs_plugin".to_string(),
-        user_config: Some(config),
-        ..Default::default()
-    }).unwrap();
-    
-    // run
-    ctx.run_once();
+    // views of subresources are allocated from the chained heaps and can be destroyed
+    let rtv = device.create_render_target_view(&textures[14], 0, 0)?;
+    let dsv = device.create_depth_stencil_view(&textures[15], 0, 0)?;
+    device.destroy_render_target_view(rtv);
+    device.destroy_depth_stencil_view(dsv);
+
+    Ok(())
 }
 
-/// Boots the client with the ecs plugin and ecs_demos with the primitives sample active
-fn boot_client_ecs_plugin_demo(demo_name: &str) {
-    println!("test: boot_client_ecs_plugin");
-    let mut config = client::UserConfig {
-        main_window_rect: HotlineInfo::default().window_rect,
-        console_window_rect: None,
-        plugins: Some(HashMap::new()),
-        plugin_data: Some(HashMap::new()),
-    };
+#[test]
+fn transition_mip_range() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("transition_mip_range", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        ..Default::default()
+    });
 
-    // ecs plugin with no demo active
-    if let Some(plugins) = &mut config.plugins {
-        plugins.insert("ecs".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../plugins")
-        });
-        plugins.insert("ecs_demos".to_string(), PluginInfo {
-            path: hotline_rs::get_data_path("../plugins")
-        });
-    }
+    let texture = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 5,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::UNORDERED_ACCESS,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, None)?;
+
+    // transition only mip 2 to unordered access and back again
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition_barrier_range(&gfx::TransitionBarrier {
+        texture: Some(&texture),
+        buffer: None,
+        state_before: gfx::ResourceState::ShaderResource,
+        state_after: gfx::ResourceState::UnorderedAccess,
+    }, 2, 1);
+    cmd.transition_barrier_range(&gfx::TransitionBarrier {
+        texture: Some(&texture),
+        buffer: None,
+        state_before: gfx::ResourceState::UnorderedAccess,
+        state_after: gfx::ResourceState::ShaderResource,
+    }, 2, 1);
+    cmd.close()?;
+
+    // the whole resource remains in its tracked state
+    assert!(texture.get_state() == gfx::ResourceState::ShaderResource);
 
-    if let Some(plugin_data) = &mut config.plugin_data {
-        plugin_data.insert("ecs".to_string(), format!("{{\"active_demo\": \"{}\"}}", demo_name));
-    }
+    Ok(())
+}
 
-    // create client
-    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
-        name: demo_name.to_string(),
-        user_config: Some(config),
+#[test]
+fn transition_array_slice_mip() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("transition_array_slice_mip", &gfx::DeviceInfo {
+        shader_heap_size: 2,
         ..Default::default()
-    }).unwrap();
-    
-    // run
-    ctx.run_once();
+    });
+
+    let texture = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 4,
+        mip_levels: 5,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, None)?;
+
+    // subresources are ordered mip first, then array slice
+    assert_eq!(gfx::subresource_index(0, 0, 5), 0);
+    assert_eq!(gfx::subresource_index(4, 0, 5), 4);
+    assert_eq!(gfx::subresource_index(1, 2, 5), 11);
+    assert_eq!(gfx::subresource_index(0, 3, 0), 3);
+
+    // render into mip 1 of slice 2 and transition it back
+    let subresource = gfx::subresource_index(1, 2, 5);
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition_barrier_range(&gfx::TransitionBarrier {
+        texture: Some(&texture),
+        buffer: None,
+        state_before: gfx::ResourceState::ShaderResource,
+        state_after: gfx::ResourceState::RenderTarget,
+    }, subresource, 1);
+    cmd.transition_barrier_range(&gfx::TransitionBarrier {
+        texture: Some(&texture),
+        buffer: None,
+        state_before: gfx::ResourceState::RenderTarget,
+        state_after: gfx::ResourceState::ShaderResource,
+    }, subresource, 1);
+    cmd.close()?;
+
+    assert!(texture.get_state() == gfx::ResourceState::ShaderResource);
+
+    Ok(())
 }
 
 #[test]
-fn client_tests() {
-    // build the plugins
-    //hotline_rs::plugin::build_all();
+fn upload_volume_and_array_textures() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("upload_volume_and_array_textures", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        ..Default::default()
+    });
 
-    // run the tests.. they need to run 1 by 1 as we cant have 2 clients running concurrently
-    //boot_empty_client();
+    // 3D colour lookup table with each texel storing its own coordinate
+    let lut_size = 16;
+    let mut lut = Vec::new();
+    for b in 0..lut_size {
+        for g in 0..lut_size {
+            for r in 0..lut_size {
+                lut.extend_from_slice(&[(r * 16) as u8, (g * 16) as u8, (b * 16) as u8, 0xff]);
+            }
+        }
+    }
+    let lut_texture = device.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture3D,
+        format: gfx::Format::RGBA8n,
+        width: lut_size,
+        height: lut_size,
+        depth: lut_size as u32,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, Some(lut.as_slice()))?;
+    assert!(lut_texture.get_srv_index().is_some());
+
+    // 2D array with 4 layers of 2 byte texels
+    let layers = vec![0u16; 8 * 8 * 4];
+    let array_texture = device.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::R16u,
+        width: 8,
+        height: 8,
+        depth: 1,
+        array_levels: 4,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, Some(layers.as_slice()))?;
+    assert!(array_texture.get_srv_index().is_some());
+
+    Ok(())
+}
+
+#[test]
+fn create_cubemap_textures() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("create_cubemap_textures", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        ..Default::default()
+    });
+
+    let cube_info = |array_levels| gfx::TextureInfo {
+        tex_type: gfx::TextureType::TextureCube,
+        format: gfx::Format::RGBA8n,
+        width: 8,
+        height: 8,
+        depth: 1,
+        array_levels,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    };
+
+    // single cube with data for each face, and an array of 2 cubes
+    let faces = vec![0u8; 8 * 8 * 4 * 6];
+    let cube = device.create_texture(&cube_info(6), Some(faces.as_slice()))?;
+    assert!(cube.get_srv_index().is_some());
+    let cube_array = device.create_texture::<u8>(&cube_info(12), None)?;
+    assert!(cube_array.get_srv_index().is_some());
+
+    // faces must be a multiple of 6
+    assert!(device.create_texture::<u8>(&cube_info(5), None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn create_subresource_views() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("create_subresource_views", &gfx::DeviceInfo {
+        render_target_heap_size: 4,
+        depth_stencil_heap_size: 4,
+        ..Default::default()
+    });
+
+    // 4 cascade shadow map with a colour target of the same size with mips
+    let cascades = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::D32f,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 4,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::DEPTH_STENCIL,
+        initial_state: gfx::ResourceState::DepthStencil,
+    }, None)?;
+    let colour = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 4,
+        mip_levels: 3,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+
+    let rtv = device.create_render_target_view(&colour, 2, 3)?;
+    let dsv = device.create_depth_stencil_view(&cascades, 0, 3)?;
+    device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&colour],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: Some(vec![rtv]),
+        depth_stencil: Some(&cascades),
+        ds_view: Some(dsv),
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+
+    // out of range subresources and mismatched usage
+    assert!(device.create_render_target_view(&colour, 3, 0).is_err());
+    assert!(device.create_depth_stencil_view(&cascades, 0, 4).is_err());
+    assert!(device.create_render_target_view(&cascades, 0, 0).is_err());
+
+    device.destroy_render_target_view(rtv);
+    device.destroy_depth_stencil_view(dsv);
+
+    Ok(())
+}
+
+#[test]
+fn clear_stencil_only() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("clear_stencil_only", &gfx::DeviceInfo {
+        depth_stencil_heap_size: 2,
+        ..Default::default()
+    });
+
+    let depth_stencil_info = |format| gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::DEPTH_STENCIL,
+        initial_state: gfx::ResourceState::DepthStencil,
+    };
+    let depth_stencil = device.create_texture::<u8>(&depth_stencil_info(gfx::Format::D24nS8u), None)?;
+    let depth_only = device.create_texture::<u8>(&depth_stencil_info(gfx::Format::D32f), None)?;
+
+    // clear stencil and preserve depth, a stencil clear on a depth only format is ignored
+    let mut passes = Vec::new();
+    for target in [&depth_stencil, &depth_only] {
+        passes.push(device.create_render_pass(&gfx::RenderPassInfo {
+            render_targets: Vec::new(),
+            rt_clear: None,
+            rt_clears: None,
+            rt_views: None,
+            depth_stencil: Some(target),
+            ds_view: None,
+            ds_clear: Some(gfx::ClearDepthStencil {
+                depth: None,
+                stencil: Some(0x7f),
+            }),
+            resolve: false,
+            discard: false,
+        })?);
+    }
+
+    let mut cmd = device.create_cmd_buf(1);
+    for pass in &passes {
+        cmd.begin_render_pass(pass);
+        cmd.end_render_pass();
+    }
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn read_only_depth_stencil_view() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("read_only_depth_stencil_view", &gfx::DeviceInfo {
+        depth_stencil_heap_size: 2,
+        ..Default::default()
+    });
+
+    // depth written in one pass then tested against and sampled in the next
+    let depth = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::D24nS8u,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::DEPTH_STENCIL | gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::DepthStencil,
+    }, None)?;
+
+    let dsv = device.create_read_only_depth_stencil_view(&depth, 0, 0)?;
+    let pass_info = |ds_view, ds_clear| gfx::RenderPassInfo {
+        render_targets: Vec::new(),
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: Some(&depth),
+        ds_view,
+        ds_clear,
+        resolve: false,
+        discard: false,
+    };
+    let write_pass = device.create_render_pass(&pass_info(None, Some(gfx::ClearDepthStencil {
+        depth: Some(1.0),
+        stencil: None,
+    })))?;
+    let read_pass = device.create_render_pass(&pass_info(Some(dsv), None))?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_render_pass(&write_pass);
+    cmd.end_render_pass();
+    cmd.transition_barrier(&gfx::TransitionBarrier {
+        texture: Some(&depth),
+        buffer: None,
+        state_before: gfx::ResourceState::DepthStencil,
+        state_after: gfx::ResourceState::DepthStencilReadOnlyShaderResource,
+    });
+    cmd.begin_render_pass(&read_pass);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    // read-only views need a depth stencil texture
+    let colour = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    assert!(device.create_read_only_depth_stencil_view(&colour, 0, 0).is_err());
+
+    device.destroy_depth_stencil_view(dsv);
+
+    Ok(())
+}
+
+#[test]
+fn upload_texture_formats() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("upload_texture_formats", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        ..Default::default()
+    });
+
+    // odd widths so the row pitch of the source data differs from the aligned upload pitch
+    let formats = [
+        gfx::Format::R16f,
+        gfx::Format::R32f,
+        gfx::Format::RGBA8n,
+        gfx::Format::RGBA32f
+    ];
+    for format in formats {
+        let width = 13;
+        let height = 7;
+        let data = vec![0u8; gfx::size_for_format(format, width, height, 1) as usize];
+        let texture = device.create_texture(&gfx::TextureInfo {
+            tex_type: gfx::TextureType::Texture2D,
+            format,
+            width,
+            height,
+            depth: 1,
+            array_levels: 1,
+            mip_levels: 1,
+            samples: 1,
+            usage: gfx::TextureUsage::SHADER_RESOURCE,
+            initial_state: gfx::ResourceState::ShaderResource,
+        }, Some(data.as_slice()))?;
+        assert!(texture.get_srv_index().is_some());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn create_block_compressed_textures() -> Result<(), hotline_rs::Error> {
+    use gfx::Texture;
+    let (_app, mut device) = create_test_device("create_block_compressed_textures", &gfx::DeviceInfo {
+        shader_heap_size: 6,
+        ..Default::default()
+    });
+
+    // 4x4 blocks of 8 or 16 bytes, the 8x8 mip chain is 8x8, 4x4, 2x2 (rounded up to a block), 1x1 (a block)
+    assert_eq!(gfx::row_pitch_for_format(gfx::Format::BC1n, 16), 32);
+    assert_eq!(gfx::size_for_format(gfx::Format::BC7n, 16, 16, 1), 256);
+    assert_eq!(gfx::mip_chain_size_for_format(gfx::Format::BC1n, 8, 8, 1, 4), 32 + 8 + 8 + 8);
+
+    let formats = [
+        gfx::Format::BC1n,
+        gfx::Format::BC3n,
+        gfx::Format::BC4n,
+        gfx::Format::BC5n,
+        gfx::Format::BC6H,
+        gfx::Format::BC7n
+    ];
+    for format in formats {
+        let info = gfx::TextureInfo {
+            tex_type: gfx::TextureType::Texture2D,
+            format,
+            width: 8,
+            height: 8,
+            depth: 1,
+            array_levels: 1,
+            mip_levels: 4,
+            samples: 1,
+            usage: gfx::TextureUsage::SHADER_RESOURCE,
+            initial_state: gfx::ResourceState::ShaderResource,
+        };
+        let data = vec![0u8; gfx::mip_chain_size_for_format(format, 8, 8, 1, 4) as usize];
+        let texture = device.create_texture(&info, Some(data.as_slice()))?;
+        assert!(texture.get_srv_index().is_some());
+    }
+
+    // sizes must be a whole number of blocks
+    let info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::BC7n,
+        width: 10,
+        height: 8,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    };
+    assert!(device.create_texture::<u8>(&info, None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn upload_mip_chain() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("upload_mip_chain", &gfx::DeviceInfo {
+        shader_heap_size: 3,
+        ..Default::default()
+    });
+
+    let mut info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 4,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    };
+
+    // each mip level filled with its own index
+    let mut data = Vec::new();
+    for mip in 0..info.mip_levels {
+        let texels = (info.width >> mip) * (info.height >> mip);
+        data.extend(std::iter::repeat([mip as u8, 0, 0, 0xff]).take(texels as usize).flatten());
+    }
+    assert_eq!(data.len() as u64, gfx::mip_chain_size_for_format(info.format, info.width, info.height, 1, info.mip_levels));
+    device.create_texture(&info, Some(data.as_slice()))?;
+
+    // top mip only is still valid, other sizes are not
+    device.create_texture(&info, Some(&data[0..16 * 16 * 4]))?;
+    info.mip_levels = 5;
+    assert!(device.create_texture(&info, Some(data.as_slice())).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn generate_mips_non_power_of_two() -> Result<(), hotline_rs::Error> {
+    use gfx::ReadBackRequest;
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("generate_mips_non_power_of_two", &gfx::DeviceInfo {
+        shader_heap_size: 16,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // 7x5 floors to 3x2 then 1x1, for each array layer. a box filter of a single value keeps the value
+    let mut info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 7,
+        height: 5,
+        depth: 1,
+        array_levels: 2,
+        mip_levels: 3,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::GENERATE_MIP_MAPS,
+        initial_state: gfx::ResourceState::CopySrc,
+    };
+    let layer_values = [[0x40u8, 0x80, 0xc0, 0xff], [0x10u8, 0x20, 0x30, 0x40]];
+    let data: Vec<u8> = layer_values.iter().flat_map(|value| value.repeat(7 * 5)).collect();
+    let texture = dev.create_texture(&info, Some(data.as_slice()))?;
+
+    cmd.reset(&swap_chain);
+    dev.generate_mips(&mut cmd, &texture)?;
+    let mut requests = Vec::new();
+    for layer in 0..2 {
+        for mip in 1..3 {
+            let request = cmd.read_back_texture(&swap_chain, &texture, gfx::subresource_index(mip, layer, 3))?;
+            requests.push((mip, layer, request));
+        }
+    }
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+    swap_chain.wait_for_last_frame();
+
+    for (mip, layer, request) in &requests {
+        assert!(request.is_complete(&swap_chain));
+        let rb = request.map(&gfx::MapInfo {
+            subresource: 0,
+            read_start: 0,
+            read_end: usize::MAX
+        })?;
+        let (width, height) = ((7 >> mip) as usize, (5 >> mip) as usize);
+        let expected = layer_values[*layer as usize].repeat(width);
+        for y in 0..height {
+            assert_eq!(&rb.data[y * rb.row_pitch..y * rb.row_pitch + width * 4], expected.as_slice());
+        }
+        request.unmap();
+    }
+    cmd.reset(&swap_chain);
+
+    // textures created without GENERATE_MIP_MAPS are rejected
+    info.usage = gfx::TextureUsage::SHADER_RESOURCE;
+    let texture = dev.create_texture(&info, Some(data.as_slice()))?;
+    assert!(dev.generate_mips(&mut cmd, &texture).is_err());
+    cmd.close()?;
+
+    // block compressed formats cannot be written as unordered access
+    info.usage = gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::GENERATE_MIP_MAPS;
+    info.format = gfx::Format::BC1n;
+    info.width = 8;
+    info.height = 8;
+    assert!(dev.create_texture::<u8>(&info, None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn read_back_texture_fence() -> Result<(), hotline_rs::Error> {
+    use gfx::ReadBackRequest;
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("read_back_texture_fence", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // texture with known contents ready to copy from
+    let data: Vec<u8> = (0..16 * 16 * 4).map(|i| (i % 255) as u8).collect();
+    let texture = dev.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::CopySrc,
+    }, Some(data.as_slice()))?;
+
+    cmd.reset(&swap_chain);
+    let request = cmd.read_back_texture(&swap_chain, &texture, 0)?;
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+
+    // busy wait on the swap chain fence
+    while !request.is_complete(&swap_chain) {
+        std::thread::yield_now();
+    }
+
+    let check_request = |request: &gfx_platform::ReadBackRequest| -> Result<(), hotline_rs::Error> {
+        let rb = request.map(&gfx::MapInfo {
+            subresource: 0,
+            read_start: 0,
+            read_end: usize::MAX
+        })?;
+        for y in 0..16 {
+            let row = &rb.data[y * rb.row_pitch..y * rb.row_pitch + 16 * 4];
+            assert_eq!(row, &data[y * 16 * 4..(y + 1) * 16 * 4]);
+        }
+        request.unmap();
+        Ok(())
+    };
+    check_request(&request)?;
+    let held_request = request;
+
+    // submit outside of the swap chain frame and track completion with a user fence
+    swap_chain.wait_for_last_frame();
+    let fence = dev.create_fence()?;
+    cmd.reset(&swap_chain);
+    let mut request = cmd.read_back_texture(&swap_chain, &texture, 0)?;
+    request.set_fence(&fence, 1);
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    dev.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    dev.wait(&fence, 1)?;
+    assert!(request.is_complete(&swap_chain));
+    check_request(&request)?;
+
+    // read back a cleared texture for more frames than there are backbuffers, a request which is still held owns its
+    // buffer so its data must not be overwritten
+    let cleared = dev.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::CopySrc,
+    }, Some(vec![0u8; 16 * 16 * 4].as_slice()))?;
+    for _ in 0..swap_chain.get_num_buffers() * 2 {
+        swap_chain.new_frame();
+        cmd.reset(&swap_chain);
+        let _ = cmd.read_back_texture(&swap_chain, &cleared, 0)?;
+        cmd.close()?;
+        dev.execute(&cmd)?;
+        swap_chain.swap(&dev)?;
+    }
+    swap_chain.wait_for_last_frame();
+    check_request(&held_request)?;
+
+    swap_chain.wait_for_last_frame();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn read_back_texture_subresource() -> Result<(), hotline_rs::Error> {
+    use gfx::ReadBackRequest;
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("read_back_texture_subresource", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // R32u picking style texture with 2 mips, each texel of mip 1 holds its index
+    let mut data: Vec<u32> = vec![0; 8 * 8];
+    data.extend(0..4 * 4);
+    let texture = dev.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::R32u,
+        width: 8,
+        height: 8,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 2,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::CopySrc,
+    }, Some(data.as_slice()))?;
+
+    cmd.reset(&swap_chain);
+    assert!(cmd.read_back_texture(&swap_chain, &texture, 2).is_err());
+    let request = cmd.read_back_texture(&swap_chain, &texture, 1)?;
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+    swap_chain.wait_for_last_frame();
+    assert!(request.is_complete(&swap_chain));
+
+    let rb = request.map(&gfx::MapInfo {
+        subresource: 0,
+        read_start: 0,
+        read_end: usize::MAX
+    })?;
+    for y in 0..4 {
+        let row = &rb.data[y * rb.row_pitch..y * rb.row_pitch + 4 * 4];
+        let expected: Vec<u8> = (y as u32 * 4..y as u32 * 4 + 4).flat_map(|i| i.to_ne_bytes()).collect();
+        assert_eq!(row, expected.as_slice());
+    }
+    request.unmap();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn read_back_r32f_texture() -> Result<(), hotline_rs::Error> {
+    use gfx::ReadBackRequest;
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("read_back_r32f_texture", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // 4 byte single channel texels with an odd width, each row must be read from the source at its own pitch
+    let width = 13;
+    let height = 7;
+    let data: Vec<f32> = (0..width * height).map(|i| i as f32).collect();
+    let texture = dev.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::R32f,
+        width: width as u64,
+        height: height as u64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::CopySrc,
+    }, Some(data.as_slice()))?;
+
+    cmd.reset(&swap_chain);
+    let request = cmd.read_back_texture(&swap_chain, &texture, 0)?;
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+    swap_chain.wait_for_last_frame();
+    assert!(request.is_complete(&swap_chain));
+
+    let rb = request.map(&gfx::MapInfo {
+        subresource: 0,
+        read_start: 0,
+        read_end: usize::MAX
+    })?;
+    let src_row_pitch = gfx::row_pitch_for_format(gfx::Format::R32f, width as u64) as usize;
+    for y in 0..height {
+        let row = &rb.data[y * rb.row_pitch..y * rb.row_pitch + src_row_pitch];
+        let expected: Vec<u8> = data[y * width..(y + 1) * width].iter().flat_map(|v| v.to_ne_bytes()).collect();
+        assert_eq!(row, expected.as_slice());
+    }
+    request.unmap();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn read_back_volume_texture() -> Result<(), hotline_rs::Error> {
+    use gfx::ReadBackRequest;
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("read_back_volume_texture", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // each texel of the volume stores its own index so every depth slice is unique
+    let (width, height, depth) = (5, 3, 4);
+    let data: Vec<u32> = (0..(width * height * depth) as u32).collect();
+    let texture = dev.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture3D,
+        format: gfx::Format::R32u,
+        width: width as u64,
+        height: height as u64,
+        depth: depth as u32,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::CopySrc,
+    }, Some(data.as_slice()))?;
+
+    cmd.reset(&swap_chain);
+    let request = cmd.read_back_texture(&swap_chain, &texture, 0)?;
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+    swap_chain.wait_for_last_frame();
+    assert!(request.is_complete(&swap_chain));
+
+    let rb = request.map(&gfx::MapInfo {
+        subresource: 0,
+        read_start: 0,
+        read_end: usize::MAX
+    })?;
+    assert!(rb.slice_pitch >= rb.row_pitch * height);
+    let src_row_pitch = gfx::row_pitch_for_format(gfx::Format::R32u, width as u64) as usize;
+    for z in 0..depth {
+        for y in 0..height {
+            let offset = z * rb.slice_pitch + y * rb.row_pitch;
+            let row = &rb.data[offset..offset + src_row_pitch];
+            let first = (z * height + y) * width;
+            let expected: Vec<u8> = data[first..first + width].iter().flat_map(|v| v.to_ne_bytes()).collect();
+            assert_eq!(row, expected.as_slice());
+        }
+    }
+    request.unmap();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn gpu_timers() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, _win, mut swap_chain, mut cmd) = create_test_swap_chain("gpu_timers", &gfx::DeviceInfo {
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // nested timers
+    cmd.reset(&swap_chain);
+    cmd.begin_timer("outer");
+    cmd.begin_timer("inner");
+    cmd.end_timer();
+    cmd.end_timer();
+    cmd.close()?;
+    dev.execute(&cmd)?;
+    swap_chain.swap(&dev)?;
+    swap_chain.wait_for_last_frame();
+
+    let results = dev.get_timer_results();
+    assert!(results["outer"] >= results["inner"]);
+
+    // unmatched timers are an error
+    cmd.reset(&swap_chain);
+    cmd.begin_timer("unmatched");
+    assert!(cmd.close().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn stable_power_state() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, _win, swap_chain, _cmd) = create_test_swap_chain("stable_power_state", &gfx::DeviceInfo {
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // requires developer mode, so this can fail but it must return an error rather than panic
+    if dev.set_stable_power_state(true).is_ok() {
+        dev.set_stable_power_state(false)?;
+    }
+
+    // pmfx applies the request on the next frame and reverts it if the device rejects it
+    let mut pmfx = Pmfx::<gfx_platform::Device>::create();
+    pmfx.set_stable_power_state(true);
+    pmfx.new_frame(&mut dev, &swap_chain);
+    if pmfx.is_stable_power_state() {
+        pmfx.set_stable_power_state(false);
+        pmfx.new_frame(&mut dev, &swap_chain);
+        assert!(!pmfx.is_stable_power_state());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn destroy_in_flight_resources() -> Result<(), hotline_rs::Error> {
+    let (mut app, mut dev, mut win, mut swap_chain, mut cmd) = create_test_swap_chain("destroy_in_flight_resources", &gfx::DeviceInfo {
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    let cs = dev.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, "[numthreads(1, 1, 1)] void CSMain() {}".as_bytes())?;
+
+    // recreate and destroy a pipeline and buffer every frame while the previous frames are still in flight
+    for _ in 0..16 {
+        win.update(&mut app);
+        swap_chain.update::<os_platform::App>(&mut dev, &win, &mut cmd);
+
+        let pipeline = dev.create_compute_pipeline(&gfx::ComputePipelineInfo {
+            cs: &cs,
+            descriptor_layout: gfx::DescriptorLayout::default(),
+        })?;
+        let buffer = dev.create_buffer::<u8>(&gfx::BufferInfo {
+            usage: gfx::BufferUsage::Vertex,
+            cpu_access: gfx::CpuAccessFlags::NONE,
+            format: gfx::Format::Unknown,
+            stride: 16,
+            num_elements: 16,
+        }, None)?;
+
+        cmd.reset(&swap_chain);
+        cmd.set_compute_pipeline(&pipeline);
+        cmd.set_vertex_buffer(&buffer, 0);
+        cmd.dispatch(gfx::Size3 { x: 1, y: 1, z: 1 }, gfx::Size3 { x: 1, y: 1, z: 1 });
+        cmd.close()?;
+
+        dev.execute(&cmd)?;
+        dev.destroy_compute_pipeline(pipeline);
+        dev.destroy_buffer(buffer);
+
+        swap_chain.swap(&dev)?;
+        dev.clean_up_resources(&swap_chain);
+    }
+
+    swap_chain.wait_for_last_frame();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn create_unaligned_constant_buffers() -> Result<(), hotline_rs::Error> {
+    use gfx::Buffer;
+    let (_app, mut device) = create_test_device("create_unaligned_constant_buffers", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        ..Default::default()
+    });
+
+    // a single matrix is smaller than the 256 byte constant buffer alignment, the view is padded to fit
+    for cpu_access in [gfx::CpuAccessFlags::NONE, gfx::CpuAccessFlags::WRITE] {
+        let buffer = device.create_buffer(&gfx::BufferInfo {
+            usage: gfx::BufferUsage::ConstantBuffer,
+            cpu_access,
+            format: gfx::Format::Unknown,
+            stride: 64,
+            num_elements: 1,
+        }, Some(&[0.0f32; 16]))?;
+        assert!(buffer.get_srv_index().is_some());
+    }
+    device.flush_uploads()?;
+
+    Ok(())
+}
+
+#[test]
+fn recreate_constant_buffers() -> Result<(), hotline_rs::Error> {
+    use gfx::Buffer;
+    let (mut app, mut dev, mut win, mut swap_chain, mut cmd) = create_test_swap_chain("recreate_constant_buffers", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        render_target_heap_size: 2,
+        ..Default::default()
+    })?;
+
+    // recreate a constant buffer every frame, the cbv heap slot is reclaimed once the buffer is no longer in flight
+    let mut max_cbv_index = 0;
+    for _ in 0..32 {
+        win.update(&mut app);
+        swap_chain.update::<os_platform::App>(&mut dev, &win, &mut cmd);
+
+        let buffer = dev.create_buffer(&gfx::BufferInfo {
+            usage: gfx::BufferUsage::ConstantBuffer,
+            cpu_access: gfx::CpuAccessFlags::NONE,
+            format: gfx::Format::Unknown,
+            stride: 256,
+            num_elements: 1,
+        }, Some(&[0u8; 256]))?;
+        max_cbv_index = max_cbv_index.max(buffer.get_srv_index().unwrap());
+
+        cmd.reset(&swap_chain);
+        cmd.close()?;
+        dev.execute(&cmd)?;
+        dev.destroy_buffer(buffer);
+
+        swap_chain.swap(&dev)?;
+        dev.clean_up_resources(&swap_chain);
+    }
+
+    assert!(max_cbv_index < 8);
+
+    swap_chain.wait_for_last_frame();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn recreate_msaa_textures() -> Result<(), hotline_rs::Error> {
+    let (mut app, mut dev, mut win, mut swap_chain, mut cmd) = create_test_swap_chain("recreate_msaa_textures", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        render_target_heap_size: 4,
+        ..Default::default()
+    })?;
+
+    // recreate an msaa texture every frame as if the window was resizing, the srv and resolved srv must be freed
+    let mut max_srv_index = 0;
+    for i in 0..32 {
+        win.update(&mut app);
+        swap_chain.update::<os_platform::App>(&mut dev, &win, &mut cmd);
+
+        let tex = dev.create_texture::<u8>(&gfx::TextureInfo {
+            tex_type: gfx::TextureType::Texture2D,
+            format: gfx::Format::RGBA8n,
+            width: 64 + i,
+            height: 64 + i,
+            depth: 1,
+            array_levels: 1,
+            mip_levels: 1,
+            samples: 4,
+            usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::RENDER_TARGET,
+            initial_state: gfx::ResourceState::ShaderResource,
+        }, None)?;
+        max_srv_index = max_srv_index.max(tex.get_srv_index().unwrap());
+
+        cmd.reset(&swap_chain);
+        cmd.close()?;
+        dev.execute(&cmd)?;
+        dev.destroy_texture(tex);
+
+        swap_chain.swap(&dev)?;
+        dev.clean_up_resources(&swap_chain);
+    }
+
+    // only the textures still in flight hold descriptors, so the heap usage must stay bounded
+    assert!(max_srv_index < 16);
+
+    swap_chain.wait_for_last_frame();
+    cmd.reset(&swap_chain);
+
+    Ok(())
+}
+
+#[test]
+fn swap_chain_backbuffer_srv() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, win) = create_test_window("swap_chain_backbuffer_srv", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        render_target_heap_size: 2,
+        ..Default::default()
+    });
+    let swap_chain = dev.create_swap_chain::<os_platform::App>(&gfx::SwapChainInfo {
+        num_buffers: 2,
+        format: gfx::Format::RGBA8n,
+        clear_colour: None,
+        max_frame_latency: Some(1),
+        sync_interval: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+    }, &win)?;
+
+    // each backbuffer has its own srv
+    let current = swap_chain.get_backbuffer_texture().get_srv_index();
+    let previous = swap_chain.get_previous_backbuffer_texture().get_srv_index();
+    assert!(current.is_some());
+    assert!(previous.is_some());
+    assert_ne!(current, previous);
+
+    Ok(())
+}
+
+#[test]
+fn swap_chain_sync_interval() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, win) = create_test_window("swap_chain_sync_interval", &gfx::DeviceInfo {
+        render_target_heap_size: 4,
+        ..Default::default()
+    });
+    let mut info = gfx::SwapChainInfo {
+        num_buffers: 2,
+        format: gfx::Format::RGBA8n,
+        clear_colour: None,
+        max_frame_latency: None,
+        sync_interval: 5,
+        usage: gfx::TextureUsage::NONE,
+    };
+    assert!(dev.create_swap_chain::<os_platform::App>(&info, &win).is_err());
+
+    // uncapped presents, then back to vsync
+    info.sync_interval = 0;
+    let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&info, &win)?;
+    let mut cmd = dev.create_cmd_buf(2);
+    for vsync in [false, false, true, true] {
+        swap_chain.set_vsync(vsync);
+        cmd.reset(&swap_chain);
+        cmd.close()?;
+        dev.execute(&cmd)?;
+        swap_chain.swap(&dev)?;
+    }
+    swap_chain.wait_for_last_frame();
+
+    Ok(())
+}
+
+#[test]
+fn swap_chain_wait_for_frame() -> Result<(), hotline_rs::Error> {
+    use gfx::Fence;
+    let (_app, mut dev, win) = create_test_window("swap_chain_wait_for_frame", &gfx::DeviceInfo {
+        render_target_heap_size: 3,
+        ..Default::default()
+    });
+    let num_buffers = 3;
+    let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&gfx::SwapChainInfo {
+        num_buffers,
+        format: gfx::Format::RGBA8n,
+        clear_colour: None,
+        max_frame_latency: None,
+        sync_interval: 0,
+        usage: gfx::TextureUsage::NONE,
+    }, &win)?;
+    let mut cmd = dev.create_cmd_buf(num_buffers);
+
+    // each frame signals its frame number before present, once new_frame returns the frame which last used the
+    // same backbuffer (num_buffers frames ago) must have completed on the gpu
+    let fence = dev.create_fence()?;
+    for frame in 1..16 {
+        swap_chain.new_frame();
+        if frame > num_buffers as u64 {
+            assert!(fence.get_completed_value() >= frame - num_buffers as u64);
+        }
+        cmd.reset(&swap_chain);
+        cmd.close()?;
+        dev.execute(&cmd)?;
+        dev.signal(&fence, frame)?;
+        swap_chain.swap(&dev)?;
+    }
+    swap_chain.wait_for_last_frame();
+
+    Ok(())
+}
+
+#[test]
+fn hdr_swap_chain_formats() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, win) = create_test_window("hdr_swap_chain_formats", &gfx::DeviceInfo {
+        render_target_heap_size: 4,
+        ..Default::default()
+    });
+    let adapter_info = dev.get_adapter_info();
+    assert!(adapter_info.max_luminance >= adapter_info.min_luminance);
+
+    // hdr formats fall back to srgb output on displays which do not support them
+    for format in [gfx::Format::RGB10A2n, gfx::Format::RGBA16f] {
+        let mut swap_chain = dev.create_swap_chain::<os_platform::App>(&gfx::SwapChainInfo {
+            num_buffers: 2,
+            format,
+            clear_colour: None,
+            max_frame_latency: None,
+            sync_interval: 1,
+            usage: gfx::TextureUsage::NONE,
+        }, &win)?;
+        swap_chain.swap(&dev)?;
+        swap_chain.wait_for_last_frame();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pipeline_library_round_trip() -> Result<(), hotline_rs::Error> {
+    use gfx::PipelineLibrary;
+    let (_app, dev) = create_test_device("pipeline_library_round_trip", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    let cs = dev.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, "[numthreads(1, 1, 1)] void CSMain() {}".as_bytes())?;
+    let info = gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout::default(),
+    };
+
+    // first run compiles and stores the pipeline
+    let library = dev.create_pipeline_library(None)?;
+    dev.create_compute_pipeline_from_library(&info, &library, "compute")?;
+    let data = library.serialize()?;
+    assert!(!data.is_empty());
+
+    // second run loads from the serialized blob, invalid blobs start a new library
+    let library = dev.create_pipeline_library(Some(data.as_slice()))?;
+    dev.create_compute_pipeline_from_library(&info, &library, "compute")?;
+    let library = dev.create_pipeline_library(Some(&[0u8; 16]))?;
+    dev.create_compute_pipeline_from_library(&info, &library, "compute")?;
+
+    Ok(())
+}
+
+#[test]
+fn occlusion_queries() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device, _win, mut swap_chain, mut cmd) = create_test_swap_chain("occlusion_queries", &gfx::DeviceInfo {
+        render_target_heap_size: 3,
+        ..Default::default()
+    })?;
+
+    // fullscreen triangle from the vertex id
+    let src = "
+        float4 VSMain(uint id : SV_VertexID) : SV_POSITION
+        {
+            float2 uv = float2((id << 1) & 2, id & 2);
+            return float4(uv * float2(2.0, -2.0) + float2(-1.0, 1.0), 0.0, 1.0);
+        }
+
+        float4 PSMain(float4 position : SV_POSITION) : SV_TARGET
+        {
+            return float4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let vs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Vertex,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("VSMain"),
+            target: String::from("vs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+    let fs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Fragment,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("PSMain"),
+            target: String::from("ps_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+    let pipeline = device.create_render_pipeline(&gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
+        input_layout: Vec::new(),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        topology: gfx::Topology::TriangleList,
+        patch_index: 0,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    })?;
+
+    // query 0 draws a triangle and query 1 draws nothing
+    swap_chain.new_frame();
+    cmd.reset(&swap_chain);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 16, height: 16 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.begin_occlusion_query(0);
+    cmd.draw_instanced(3, 1, 0, 0);
+    cmd.end_occlusion_query(0);
+    cmd.begin_occlusion_query(1);
+    cmd.end_occlusion_query(1);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    // results are read back once the frame fence has been signalled
+    device.execute(&cmd)?;
+    swap_chain.swap(&device)?;
+    swap_chain.wait_for_last_frame();
+
+    let results = device.get_occlusion_results();
+    assert!(results[0]);
+    assert!(!results[1]);
+
+    Ok(())
+}
+
+#[test]
+fn tessellation_pipeline() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("tessellation_pipeline", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+
+    // a single triangle patch from the vertex id which is subdivided by the tessellator
+    let src = "
+        struct ControlPoint { float4 position : POSITION; };
+        struct PatchConstants { float edges[3] : SV_TessFactor; float inside : SV_InsideTessFactor; };
+
+        ControlPoint VSMain(uint id : SV_VertexID)
+        {
+            ControlPoint cp;
+            float2 uv = float2((id << 1) & 2, id & 2);
+            cp.position = float4(uv * float2(2.0, -2.0) + float2(-1.0, 1.0), 0.0, 1.0);
+            return cp;
+        }
+
+        PatchConstants PatchMain(InputPatch<ControlPoint, 3> patch)
+        {
+            PatchConstants pc;
+            pc.edges[0] = 4.0;
+            pc.edges[1] = 4.0;
+            pc.edges[2] = 4.0;
+            pc.inside = 4.0;
+            return pc;
+        }
+
+        [domain(\"tri\")]
+        [partitioning(\"integer\")]
+        [outputtopology(\"triangle_cw\")]
+        [outputcontrolpoints(3)]
+        [patchconstantfunc(\"PatchMain\")]
+        ControlPoint HSMain(InputPatch<ControlPoint, 3> patch, uint i : SV_OutputControlPointID)
+        {
+            return patch[i];
+        }
+
+        [domain(\"tri\")]
+        float4 DSMain(PatchConstants pc, float3 bary : SV_DomainLocation,
+            const OutputPatch<ControlPoint, 3> patch) : SV_POSITION
+        {
+            return patch[0].position * bary.x + patch[1].position * bary.y + patch[2].position * bary.z;
+        }
+
+        float4 PSMain(float4 position : SV_POSITION) : SV_TARGET
+        {
+            return float4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let compile = |shader_type, entry_point: &str, target: &str| {
+        device.create_shader(&gfx::ShaderInfo {
+            shader_type,
+            compile_info: Some(gfx::ShaderCompileInfo {
+                entry_point: String::from(entry_point),
+                target: String::from(target),
+                flags: gfx::ShaderCompileFlags::NONE,
+            }),
+        }, src.as_bytes())
+    };
+    let vs = compile(gfx::ShaderType::Vertex, "VSMain", "vs_5_0")?;
+    let hs = compile(gfx::ShaderType::Hull, "HSMain", "hs_5_0")?;
+    let ds = compile(gfx::ShaderType::Domain, "DSMain", "ds_5_0")?;
+    let fs = compile(gfx::ShaderType::Fragment, "PSMain", "ps_5_0")?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+
+    // patch index is the number of control points - 1
+    let pipeline = device.create_render_pipeline(&gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: Some(&hs),
+        ds: Some(&ds),
+        gs: None,
+        input_layout: Vec::new(),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        topology: gfx::Topology::PatchList,
+        patch_index: 2,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    })?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 16, height: 16 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.draw_instanced(3, 1, 0, 0);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.signal(&fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn geometry_shader_pipeline() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("geometry_shader_pipeline", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+
+    // a single point is expanded into a fullscreen triangle
+    let src = "
+        struct VSOutput { float4 position : SV_POSITION; };
+
+        VSOutput VSMain(uint id : SV_VertexID)
+        {
+            VSOutput output;
+            output.position = float4(0.0, 0.0, 0.0, 1.0);
+            return output;
+        }
+
+        [maxvertexcount(3)]
+        void GSMain(point VSOutput input[1], inout TriangleStream<VSOutput> stream)
+        {
+            for(uint id = 0; id < 3; ++id)
+            {
+                VSOutput output;
+                float2 uv = float2((id << 1) & 2, id & 2);
+                output.position = input[0].position + float4(uv * float2(2.0, -2.0) + float2(-1.0, 1.0), 0.0, 0.0);
+                stream.Append(output);
+            }
+        }
+
+        float4 PSMain(VSOutput input) : SV_TARGET
+        {
+            return float4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let compile = |shader_type, entry_point: &str, target: &str| {
+        device.create_shader(&gfx::ShaderInfo {
+            shader_type,
+            compile_info: Some(gfx::ShaderCompileInfo {
+                entry_point: String::from(entry_point),
+                target: String::from(target),
+                flags: gfx::ShaderCompileFlags::NONE,
+            }),
+        }, src.as_bytes())
+    };
+    let vs = compile(gfx::ShaderType::Vertex, "VSMain", "vs_5_0")?;
+    let gs = compile(gfx::ShaderType::Geometry, "GSMain", "gs_5_0")?;
+    let fs = compile(gfx::ShaderType::Fragment, "PSMain", "ps_5_0")?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+
+    let pipeline = device.create_render_pipeline(&gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: Some(&gs),
+        input_layout: Vec::new(),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        topology: gfx::Topology::PointList,
+        patch_index: 0,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    })?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 16, height: 16 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.draw_instanced(1, 1, 0, 0);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.signal(&fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn primitive_tangent_input_layout() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("primitive_tangent_input_layout", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+
+    // the vertex shader consumes every element of the primitive vertex layout, including tangents
+    let src = "
+        struct VSInput {
+            float3 position : POSITION;
+            float2 texcoord : TEXCOORD;
+            float3 normal : NORMAL;
+            float3 tangent : TANGENT;
+            float3 bitangent : BITANGENT;
+        };
+
+        struct VSOutput {
+            float4 position : SV_POSITION;
+            float3 tangent : TEXCOORD0;
+        };
+
+        VSOutput VSMain(VSInput input)
+        {
+            VSOutput output;
+            output.position = float4(input.position * 0.5, 1.0);
+            output.position.z = 0.5;
+            output.tangent = input.tangent * dot(input.normal, input.normal) + input.bitangent * input.texcoord.x;
+            return output;
+        }
+
+        float4 PSMain(VSOutput input) : SV_TARGET
+        {
+            return float4(input.tangent * 0.5 + 0.5, 1.0);
+        }
+    ";
+    let compile = |shader_type, entry_point: &str, target: &str| {
+        device.create_shader(&gfx::ShaderInfo {
+            shader_type,
+            compile_info: Some(gfx::ShaderCompileInfo {
+                entry_point: String::from(entry_point),
+                target: String::from(target),
+                flags: gfx::ShaderCompileFlags::NONE,
+            }),
+        }, src.as_bytes())
+    };
+    let vs = compile(gfx::ShaderType::Vertex, "VSMain", "vs_5_0")?;
+    let fs = compile(gfx::ShaderType::Fragment, "PSMain", "ps_5_0")?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+
+    let input_layout = primitives::Vertex3D::input_layout();
+    assert_eq!(input_layout.iter().map(|element| element.semantic.as_str()).collect::<Vec<_>>(),
+        ["POSITION", "TEXCOORD", "NORMAL", "TANGENT", "BITANGENT"]);
+    let pipeline = device.create_render_pipeline(&gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
+        input_layout,
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        topology: gfx::Topology::TriangleList,
+        patch_index: 0,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    })?;
+
+    let mesh = primitives::create_cube_mesh(&mut device);
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 16, height: 16 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.set_index_buffer(&mesh.ib);
+    cmd.set_vertex_buffer(&mesh.vb, 0);
+    cmd.draw_indexed_instanced(mesh.num_indices, 1, 0, 0, 0);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.signal(&fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn primitive_grid_winding() {
+    use maths_rs::{cross, dot};
+
+    // triangles are wound so (b - a) x (c - a) points away from the face normal, the same as the cube faces
+    let (vertices, indices) = primitives::create_grid_vertices(3, 5);
+    assert_eq!(vertices.len(), 4 * 6);
+    assert_eq!(indices.len(), 3 * 5 * 6);
+    for tri in indices.chunks(3) {
+        let (a, b, c) = (&vertices[tri[0]], &vertices[tri[1]], &vertices[tri[2]]);
+        let n = cross(b.position - a.position, c.position - a.position);
+        for v in [a, b, c] {
+            assert!(v.normal.y > 0.99);
+            assert!(dot(n, v.normal) < 0.0);
+        }
+    }
+}
+
+#[test]
+fn mesh_shader_pipeline() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("mesh_shader_pipeline", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+    if !device.get_adapter_info().mesh_shader_supported() {
+        return Ok(());
+    }
+
+    // a single triangle output from one mesh shader group
+    let src = "
+        struct VSOutput {
+            float4 position : SV_POSITION;
+        };
+
+        [outputtopology(\"triangle\")]
+        [numthreads(1, 1, 1)]
+        void MSMain(out vertices VSOutput verts[3], out indices uint3 tris[1]) {
+            SetMeshOutputCounts(3, 1);
+            verts[0].position = float4(-1.0, -1.0, 0.0, 1.0);
+            verts[1].position = float4(0.0, 1.0, 0.0, 1.0);
+            verts[2].position = float4(1.0, -1.0, 0.0, 1.0);
+            tris[0] = uint3(0, 1, 2);
+        }
+
+        float4 PSMain(VSOutput input) : SV_TARGET {
+            return float4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let ms = match device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Mesh,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("MSMain"),
+            target: String::from("ms_6_5"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes()) {
+        Ok(ms) => ms,
+        // dxc is optional and only loaded for shader model 6
+        Err(err) if err.msg.contains("dxcompiler.dll") => return Ok(()),
+        Err(err) => return Err(err)
+    };
+    let fs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Fragment,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("PSMain"),
+            target: String::from("ps_6_5"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+    let pipeline = device.create_mesh_pipeline(&gfx::MeshPipelineInfo {
+        amp: None,
+        ms: &ms,
+        fs: Some(&fs),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        pass: &pass,
+    })?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 16, height: 16 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.dispatch_mesh(gfx::Size3 { x: 1, y: 1, z: 1 });
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn raytracing_dispatch_rays() -> Result<(), hotline_rs::Error> {
+    use gfx::Buffer;
+    use gfx::RaytracingTLAS;
+    let (_app, mut device) = create_test_device("raytracing_dispatch_rays", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+    if !device.get_adapter_info().raytracing_supported() {
+        return Ok(());
+    }
+
+    // trace a single ray at a triangle and write 1 on hit or 2 on miss
+    let src = "
+        cbuffer indices : register(b0) {
+            uint scene_index;
+            uint output_index;
+        };
+        RaytracingAccelerationStructure scenes[] : register(t0);
+        RWStructuredBuffer<uint> outputs[] : register(u0);
+
+        struct Payload {
+            uint result;
+        };
+
+        [shader(\"raygeneration\")]
+        void RayGen() {
+            RayDesc ray;
+            ray.Origin = float3(0.0, 0.0, 1.0);
+            ray.Direction = float3(0.0, 0.0, -1.0);
+            ray.TMin = 0.0;
+            ray.TMax = 10.0;
+            Payload payload = { 0 };
+            TraceRay(scenes[scene_index], RAY_FLAG_NONE, 0xff, 0, 1, 0, ray, payload);
+            outputs[output_index][0] = payload.result;
+        }
+
+        [shader(\"closesthit\")]
+        void ClosestHit(inout Payload payload, BuiltInTriangleIntersectionAttributes attribs) {
+            payload.result = 1;
+        }
+
+        [shader(\"miss\")]
+        void Miss(inout Payload payload) {
+            payload.result = 2;
+        }
+    ";
+    let library = match device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Library,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::new(),
+            target: String::from("lib_6_3"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes()) {
+        Ok(library) => library,
+        // dxc is optional and only loaded for shader model 6
+        Err(err) if err.msg.contains("dxcompiler.dll") => return Ok(()),
+        Err(err) => return Err(err)
+    };
+
+    let vertices: [f32; 9] = [
+        -1.0, -1.0, 0.0,
+        0.0, 1.0, 0.0,
+        1.0, -1.0, 0.0
+    ];
+    let vertex_buffer = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Vertex,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 12,
+        num_elements: 3,
+    }, Some(&vertices))?;
+    let output = device.create_buffer::<u32>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::RWStructured,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 4,
+        num_elements: 1,
+    }, None)?;
+    device.flush_uploads()?;
+
+    let blas = device.create_raytracing_blas(&gfx::RaytracingBLASInfo {
+        geometry: vec![gfx::RaytracingGeometryInfo {
+            vertex_buffer: &vertex_buffer,
+            vertex_format: gfx::Format::RGB32f,
+            vertex_count: 3,
+            vertex_stride: 12,
+            index_buffer: None,
+            index_format: gfx::Format::Unknown,
+            index_count: 0,
+            opaque: true,
+        }],
+        flags: gfx::AccelerationStructureBuildFlags::PREFER_FAST_TRACE,
+    })?;
+    let tlas = device.create_raytracing_tlas(&gfx::RaytracingTLASInfo {
+        instances: vec![gfx::RaytracingInstanceInfo {
+            blas: &blas,
+            transform: [
+                1.0, 0.0, 0.0, 0.0,
+                0.0, 1.0, 0.0, 0.0,
+                0.0, 0.0, 1.0, 0.0
+            ],
+            instance_id: 0,
+            mask: 0xff,
+            hit_group_index: 0,
+        }],
+        flags: gfx::AccelerationStructureBuildFlags::PREFER_FAST_TRACE,
+    })?;
+
+    let indices: [u32; 4] = [tlas.get_srv_index() as u32, output.get_uav_index().unwrap() as u32, 0, 0];
+    let constants = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::ConstantBuffer,
+        cpu_access: gfx::CpuAccessFlags::WRITE,
+        format: gfx::Format::Unknown,
+        stride: 16,
+        num_elements: 1,
+    }, Some(&indices))?;
+
+    let pipeline = device.create_raytracing_pipeline(&gfx::RaytracingPipelineInfo {
+        library: &library,
+        hit_groups: vec![gfx::RaytracingHitGroup {
+            name: String::from("HitGroup"),
+            closest_hit: Some(String::from("ClosestHit")),
+            any_hit: None,
+            intersection: None,
+        }],
+        descriptor_layout: gfx::DescriptorLayout {
+            bindings: Some(vec![gfx::DescriptorBinding {
+                visibility: gfx::ShaderVisibility::Compute,
+                shader_register: 0,
+                register_space: 0,
+                binding_type: gfx::DescriptorType::RootConstantBuffer,
+                num_descriptors: None,
+            }]),
+            ..Default::default()
+        }.with_bindless_table(0),
+        max_payload_size: 4,
+        max_attribute_size: 8,
+        max_recursion_depth: 1,
+    })?;
+    let table = device.create_raytracing_shader_binding_table(&gfx::RaytracingShaderBindingTableInfo {
+        pipeline: &pipeline,
+        ray_generation_shader: String::from("RayGen"),
+        miss_shaders: vec![String::from("Miss")],
+        hit_groups: vec![String::from("HitGroup")],
+    })?;
+
+    // unknown exports are reported instead of writing a null record
+    assert!(device.create_raytracing_shader_binding_table(&gfx::RaytracingShaderBindingTableInfo {
+        pipeline: &pipeline,
+        ray_generation_shader: String::from("Missing"),
+        miss_shaders: Vec::new(),
+        hit_groups: Vec::new(),
+    }).is_err());
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition_buffer(&vertex_buffer, gfx::ResourceState::NonPixelShaderResource);
+    cmd.build_raytracing_blas(&blas);
+    cmd.build_raytracing_tlas(&tlas);
+    cmd.transition_buffer(&output, gfx::ResourceState::UnorderedAccess);
+    cmd.set_raytracing_pipeline(&pipeline);
+    cmd.set_bindless_heap(device.get_shader_heap());
+    cmd.set_compute_constant_buffer(0, &constants, 0);
+    cmd.dispatch_rays(&table, gfx::Size3 { x: 1, y: 1, z: 1 });
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn clear_unordered_access_views() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("clear_unordered_access_views", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        ..Default::default()
+    });
+
+    let texture = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::UNORDERED_ACCESS,
+        initial_state: gfx::ResourceState::UnorderedAccess,
+    }, None)?;
+    let histogram = device.create_buffer::<u32>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::RWRaw,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 4,
+        num_elements: 256,
+    }, None)?;
+    let vertex_buffer = device.create_buffer::<u32>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Vertex,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 4,
+        num_elements: 4,
+    }, None)?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition_buffer(&histogram, gfx::ResourceState::UnorderedAccess);
+    cmd.clear_unordered_access_view_float(Some(&texture), None, device.get_shader_heap(), [0.0, 0.5, 1.0, 1.0])?;
+    cmd.clear_unordered_access_view_uint(None, Some(&histogram), device.get_shader_heap(), [0; 4])?;
+
+    // resources without a uav cannot be cleared
+    assert!(cmd.clear_unordered_access_view_uint(None, Some(&vertex_buffer), device.get_shader_heap(), [0; 4]).is_err());
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn copy_textures() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("copy_textures", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        ..Default::default()
+    });
+
+    let info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    };
+    let data = vec![0xffu8; 16 * 16 * 4];
+    let current = device.create_texture(&info, Some(data.as_slice()))?;
+    let history = device.create_texture::<u8>(&info, None)?;
+    device.flush_uploads()?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition(&current, gfx::ResourceState::CopySrc);
+    cmd.transition(&history, gfx::ResourceState::CopyDst);
+
+    // whole resource copy, then copy the top left quarter into the bottom right
+    cmd.copy_texture(&history, &current);
+    cmd.copy_texture_region(&history, 0, gfx::Size3 { x: 8, y: 8, z: 0 }, &current, 0, Some(gfx::TextureRegion {
+        left: 0,
+        top: 0,
+        front: 0,
+        right: 8,
+        bottom: 8,
+        back: 1,
+    }));
+    cmd.copy_texture_region(&history, 0, gfx::Size3 { x: 0, y: 0, z: 0 }, &current, 0, None);
+
+    cmd.transition(&current, gfx::ResourceState::ShaderResource);
+    cmd.transition(&history, gfx::ResourceState::ShaderResource);
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn copy_buffer_region() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("copy_buffer_region", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        ..Default::default()
+    });
+
+    // copy a count from a structured buffer into the instance count of indirect args
+    let counts: [u32; 4] = [7, 0, 0, 0];
+    let count_buffer = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Structured,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 4,
+        num_elements: 4,
+    }, Some(&counts))?;
+    let args = device.create_buffer::<gfx::DrawArguments>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::IndirectArgument,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: std::mem::size_of::<gfx::DrawArguments>(),
+        num_elements: 1,
+    }, None)?;
+    device.flush_uploads()?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition_buffer(&count_buffer, gfx::ResourceState::CopySrc);
+    cmd.transition_buffer(&args, gfx::ResourceState::CopyDst);
+    cmd.copy_buffer_region(&args, 4, &count_buffer, 0, 4);
+    cmd.transition_buffer(&args, gfx::ResourceState::IndirectArgument);
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn variable_rate_shading() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("variable_rate_shading", &gfx::DeviceInfo {
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+
+    let src = "
+        float4 VSMain(uint id : SV_VertexID) : SV_POSITION
+        {
+            float2 uv = float2((id << 1) & 2, id & 2);
+            return float4(uv * float2(2.0, -2.0) + float2(-1.0, 1.0), 0.0, 1.0);
+        }
+
+        float4 PSMain(float4 position : SV_POSITION) : SV_TARGET
+        {
+            return float4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let vs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Vertex,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("VSMain"),
+            target: String::from("vs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+    let fs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Fragment,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("PSMain"),
+            target: String::from("ps_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    let target = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 64,
+        height: 64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    }, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&target],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+    let pipeline = device.create_render_pipeline(&gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
+        input_layout: Vec::new(),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target: vec![gfx::RenderTargetBlendInfo::default()],
+        },
+        topology: gfx::Topology::TriangleList,
+        patch_index: 0,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    })?;
+
+    // a shading rate image with a texel per tile, filled with the 2x2 rate (0x5)
+    let tile_size = device.get_adapter_info().shading_rate_image_tile_size.max(1);
+    let tiles = 64 / tile_size;
+    let rate_image = device.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::R8u,
+        width: tiles as u64,
+        height: tiles as u64,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, Some(&vec![0x5u8; (tiles * tiles) as usize]))?;
+    device.flush_uploads()?;
+
+    // commands are skipped on devices which do not support variable rate shading
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.transition(&rate_image, gfx::ResourceState::ShadingRateSource);
+    cmd.begin_render_pass(&pass);
+    let rect = os::Rect { x: 0, y: 0, width: 64, height: 64 };
+    cmd.set_viewport(&gfx::Viewport::from(rect));
+    cmd.set_scissor_rect(&gfx::ScissorRect::from(rect));
+    cmd.set_render_pipeline(&pipeline);
+    cmd.set_shading_rate(gfx::ShadingRate::Rate2x2, [gfx::ShadingRateCombiner::Passthrough; 2]);
+    cmd.draw_instanced(3, 1, 0, 0);
+    cmd.set_shading_rate_image(Some(&rate_image));
+    cmd.set_shading_rate(gfx::ShadingRate::Rate1x1,
+        [gfx::ShadingRateCombiner::Passthrough, gfx::ShadingRateCombiner::Override]);
+    cmd.draw_instanced(3, 1, 0, 0);
+    cmd.set_shading_rate_image(None);
+    cmd.end_render_pass();
+    cmd.close()?;
+
+    let fence = device.create_fence()?;
+    device.execute(&cmd)?;
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    Ok(())
+}
+
+#[test]
+fn independent_render_target_blend() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("independent_render_target_blend", &gfx::DeviceInfo {
+        render_target_heap_size: 2,
+        ..Default::default()
+    });
+
+    let src = "
+        float4 VSMain(uint id : SV_VertexID) : SV_POSITION
+        {
+            return float4(0.0, 0.0, 0.0, 1.0);
+        }
+
+        struct PSOutput
+        {
+            float4 albedo : SV_TARGET0;
+            float4 light : SV_TARGET1;
+        };
+
+        PSOutput PSMain(float4 position : SV_POSITION)
+        {
+            PSOutput output;
+            output.albedo = float4(1.0, 1.0, 1.0, 1.0);
+            output.light = float4(1.0, 1.0, 1.0, 1.0);
+            return output;
+        }";
+
+    let vs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Vertex,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("VSMain"),
+            target: String::from("vs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+    let fs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Fragment,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("PSMain"),
+            target: String::from("ps_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    // 2 target mrt pass
+    let target_info = gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::RenderTarget,
+    };
+    let albedo = device.create_texture::<u8>(&target_info, None)?;
+    let light = device.create_texture::<u8>(&target_info, None)?;
+    let pass = device.create_render_pass(&gfx::RenderPassInfo {
+        render_targets: vec![&albedo, &light],
+        rt_clear: None,
+        rt_clears: None,
+        rt_views: None,
+        depth_stencil: None,
+        ds_view: None,
+        ds_clear: None,
+        resolve: false,
+        discard: false,
+    })?;
+
+    // target 0 opaque, target 1 additive
+    let additive = gfx::RenderTargetBlendInfo {
+        blend_enabled: true,
+        src_blend: gfx::BlendFactor::One,
+        dst_blend: gfx::BlendFactor::One,
+        src_blend_alpha: gfx::BlendFactor::One,
+        dst_blend_alpha: gfx::BlendFactor::One,
+        ..Default::default()
+    };
+    let pipeline_info = |render_target| gfx::RenderPipelineInfo {
+        vs: Some(&vs),
+        fs: Some(&fs),
+        hs: None,
+        ds: None,
+        gs: None,
+        input_layout: Vec::new(),
+        descriptor_layout: gfx::DescriptorLayout::default(),
+        raster_info: gfx::RasterInfo::default(),
+        depth_stencil_info: gfx::DepthStencilInfo::default(),
+        blend_info: gfx::BlendInfo {
+            alpha_to_coverage_enabled: false,
+            independent_blend_enabled: false,
+            render_target,
+        },
+        topology: gfx::Topology::TriangleList,
+        patch_index: 0,
+        sample_mask: u32::max_value(),
+        pass: &pass,
+    };
+    device.create_render_pipeline(&pipeline_info(vec![gfx::RenderTargetBlendInfo::default(), additive]))?;
+    device.create_render_pipeline(&pipeline_info(vec![additive]))?;
+
+    // blend infos must match the number of targets
+    assert!(device.create_render_pipeline(&pipeline_info(vec![additive; 3])).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn create_bindless_pipeline() -> Result<(), hotline_rs::Error> {
+    let (_app, dev) = create_test_device("create_bindless_pipeline", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    // textures are indexed from the start of the shader heap by srv and uav index
+    let src = "
+        cbuffer indices : register(b0) {
+            uint srv_index;
+            uint uav_index;
+        };
+        Texture2D textures[] : register(t0);
+        RWTexture2D<float4> rw_textures[] : register(u0);
+
+        [numthreads(8, 8, 1)]
+        void CSMain(uint3 did : SV_DispatchThreadID) {
+            rw_textures[uav_index][did.xy] = textures[srv_index].Load(int3(did.xy, 0));
+        }";
+    let cs = dev.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_1"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    let layout = gfx::DescriptorLayout {
+        push_constants: Some(vec![gfx::PushConstantInfo {
+            visibility: gfx::ShaderVisibility::Compute,
+            shader_register: 0,
+            register_space: 0,
+            num_values: 2,
+        }]),
+        ..Default::default()
+    }.with_bindless_table(0);
+
+    let bindings = layout.bindings.as_ref().unwrap();
+    assert_eq!(bindings.len(), 2);
+    assert!(bindings.iter().all(|binding| binding.num_descriptors.is_none()));
+
+    dev.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: layout,
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn bind_root_constant_buffer() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("bind_root_constant_buffer", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        ..Default::default()
+    });
+
+    let src = "
+        cbuffer per_object : register(b0) { float4 data[16]; };
+        RWStructuredBuffer<float4> output : register(u0);
+        [numthreads(1, 1, 1)] void CSMain() { output[0] = data[0]; }
+    ";
+    let cs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    // the root constant buffer takes slot 0 before the uav table in slot 1
+    let pipeline = device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout {
+            bindings: Some(vec![
+                gfx::DescriptorBinding {
+                    visibility: gfx::ShaderVisibility::Compute,
+                    shader_register: 0,
+                    register_space: 0,
+                    binding_type: gfx::DescriptorType::RootConstantBuffer,
+                    num_descriptors: None,
+                },
+                gfx::DescriptorBinding {
+                    visibility: gfx::ShaderVisibility::Compute,
+                    shader_register: 0,
+                    register_space: 0,
+                    binding_type: gfx::DescriptorType::UnorderedAccess,
+                    num_descriptors: Some(1),
+                },
+            ]),
+            push_constants: None,
+            static_samplers: None,
+        },
+    })?;
+
+    // per object data for 2 objects, each at a 256 byte aligned offset
+    let constants = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::ConstantBuffer,
+        cpu_access: gfx::CpuAccessFlags::WRITE,
+        format: gfx::Format::Unknown,
+        stride: 256,
+        num_elements: 2,
+    }, Some(&[1.0f32; 128]))?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.set_compute_pipeline(&pipeline);
+    for object in 0..2 {
+        cmd.set_compute_constant_buffer(0, &constants, object * 256);
+        cmd.dispatch(gfx::Size3 { x: 1, y: 1, z: 1 }, gfx::Size3 { x: 1, y: 1, z: 1 });
+    }
+    cmd.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn bind_root_descriptors() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("bind_root_descriptors", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        ..Default::default()
+    });
+
+    let src = "
+        cbuffer per_frame : register(b0) { float4 scale; };
+        StructuredBuffer<float4> input : register(t0);
+        RWStructuredBuffer<float4> output : register(u0);
+        [numthreads(1, 1, 1)] void CSMain() { output[0] = input[0] * scale; }
+    ";
+    let cs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    // root descriptors take slots 0, 1 and 2 in binding order with no descriptor tables
+    let bindings = [
+        gfx::DescriptorType::RootConstantBuffer,
+        gfx::DescriptorType::RootShaderResource,
+        gfx::DescriptorType::RootUnorderedAccess
+    ].into_iter().map(|binding_type| gfx::DescriptorBinding {
+        visibility: gfx::ShaderVisibility::Compute,
+        shader_register: 0,
+        register_space: 0,
+        binding_type,
+        num_descriptors: None,
+    }).collect();
+
+    let pipeline = device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout {
+            bindings: Some(bindings),
+            ..Default::default()
+        },
+    })?;
+
+    let constants = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::ConstantBuffer,
+        cpu_access: gfx::CpuAccessFlags::WRITE,
+        format: gfx::Format::Unknown,
+        stride: 16,
+        num_elements: 1,
+    }, Some(&[2.0f32; 4]))?;
+
+    let input = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Structured,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 16,
+        num_elements: 1,
+    }, Some(&[1.0f32; 4]))?;
+
+    let output = device.create_buffer::<u8>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::RWStructured,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 16,
+        num_elements: 1,
+    }, None)?;
+
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.set_compute_pipeline(&pipeline);
+    cmd.set_compute_constant_buffer(0, &constants, 0);
+    cmd.set_compute_root_shader_resource(1, &input, 0);
+    cmd.set_compute_root_unordered_access(2, &output, 0);
+    cmd.dispatch(gfx::Size3 { x: 1, y: 1, z: 1 }, gfx::Size3 { x: 1, y: 1, z: 1 });
+    cmd.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn reflect_shader_bindings() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("reflect_shader_bindings", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    let src = "
+        cbuffer per_pass : register(b0) { float4 scale; };
+        Texture2D textures[4] : register(t0, space1);
+        SamplerState linear : register(s0);
+        RWTexture2D<float4> output : register(u0);
+        [numthreads(8, 8, 1)]
+        void CSMain(uint3 did : SV_DispatchThreadID) {
+            output[did.xy] = textures[3].SampleLevel(linear, float2(0.0, 0.0), 0) * scale;
+        }
+    ";
+    let cs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_1"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, src.as_bytes())?;
+
+    let reflection = device.reflect_shader(&cs)?;
+    assert_eq!(reflection.bindings.len(), 4);
+    let textures = reflection.bindings.iter().find(|binding| binding.name == "textures").unwrap();
+    assert!(textures.binding_type == gfx::DescriptorType::ShaderResource);
+    assert_eq!(textures.register_space, 1);
+    assert_eq!(textures.num_descriptors, Some(4));
+    let output = reflection.bindings.iter().find(|binding| binding.name == "output").unwrap();
+    assert!(output.binding_type == gfx::DescriptorType::UnorderedAccess);
+
+    // a layout built from reflection binds everything and creates a pipeline
+    let layout = gfx::DescriptorLayout::from_reflection(&[(gfx::ShaderVisibility::Compute, &reflection)]);
+    assert!(layout.find_missing_bindings(&reflection).is_empty());
+    device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: layout,
+    })?;
+
+    // binding the cbuffer as push constants leaves the other resources missing
+    let layout = gfx::DescriptorLayout {
+        push_constants: Some(vec![gfx::PushConstantInfo {
+            visibility: gfx::ShaderVisibility::Compute,
+            shader_register: 0,
+            register_space: 0,
+            num_values: 4,
+        }]),
+        ..Default::default()
+    };
+    assert_eq!(layout.find_missing_bindings(&reflection).len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn compile_dxil_shader() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("compile_dxil_shader", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    // wave intrinsics require shader model 6
+    let src = "
+        RWStructuredBuffer<uint> output : register(u0);
+        [numthreads(32, 1, 1)]
+        void CSMain(uint3 did : SV_DispatchThreadID) {
+            output[did.x] = WaveActiveSum(did.x);
+        }
+    ";
+    let info = gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_6_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    };
+    let cs = match device.create_shader(&info, src.as_bytes()) {
+        Ok(cs) => cs,
+        // dxc is optional and only loaded for shader model 6
+        Err(err) if err.msg.contains("dxcompiler.dll") => return Ok(()),
+        Err(err) => return Err(err)
+    };
+
+    let reflection = device.reflect_shader(&cs)?;
+    assert_eq!(reflection.bindings.len(), 1);
+    device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout::from_reflection(&[(gfx::ShaderVisibility::Compute, &reflection)]),
+    })?;
+
+    // compile errors are reported from dxc
+    assert!(device.create_shader(&info, "void CSMain() { undeclared = 1; }".as_bytes()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn report_compile_errors() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("report_compile_errors", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    // shader errors are copied from the error blob
+    let info = gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    };
+    for _ in 0..2 {
+        let err = device.create_shader(&info, "[numthreads(1, 1, 1)] void CSMain() { undeclared = 1; }".as_bytes());
+        let msg = err.err().unwrap().msg;
+        assert!(msg.contains("undeclared"));
+        assert!(!msg.contains('\0'));
+    }
+
+    // overlapping ranges fail to serialize the root signature
+    let cs = device.create_shader(&info, "[numthreads(1, 1, 1)] void CSMain() {}".as_bytes())?;
+    let srv = gfx::DescriptorBinding {
+        visibility: gfx::ShaderVisibility::Compute,
+        shader_register: 0,
+        register_space: 0,
+        binding_type: gfx::DescriptorType::ShaderResource,
+        num_descriptors: Some(1),
+    };
+    let result = device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout {
+            bindings: Some(vec![srv.clone(), srv]),
+            ..Default::default()
+        },
+    });
+    assert!(!result.err().unwrap().msg.is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn validate_compute_to_graphics_state() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("validate_compute_to_graphics_state", &gfx::DeviceInfo {
+        shader_heap_size: 2,
+        ..Default::default()
+    });
+
+    // texture written by a compute pass
+    let texture = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::UNORDERED_ACCESS,
+        initial_state: gfx::ResourceState::UnorderedAccess,
+    }, None)?;
+
+    // sampling without a transition is reported, and passes once transitioned
+    let mut cmd = device.create_cmd_buf(1);
+    assert!(cmd.validate_texture_state(&texture, gfx::ResourceState::ShaderResource).is_err());
+    cmd.transition(&texture, gfx::ResourceState::ShaderResource);
+    assert!(cmd.validate_texture_state(&texture, gfx::ResourceState::ShaderResource).is_ok());
+    cmd.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn execute_multiple_cmd_bufs() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("execute_multiple_cmd_bufs", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    // record each command buffer on its own thread
+    let mut cmds = device.create_cmd_buf_bundle(4, 1);
+    assert_eq!(cmds.len(), 4);
+    std::thread::scope(|scope| {
+        for (i, cmd) in cmds.iter_mut().enumerate() {
+            scope.spawn(move || {
+                cmd.begin_event(0xff0000ff, &format!("worker {}", i));
+                cmd.end_event();
+                cmd.close().unwrap();
+            });
+        }
+    });
+
+    let fence = device.create_fence()?;
+    device.execute_multiple(&cmds.iter().collect::<Vec<_>>())?;
+    device.execute_multiple(&[])?;
+    device.signal(&fence, 1)?;
+    device.wait(&fence, 1)?;
+
+    // command buffers for different queues cannot be submitted together
+    let compute = device.create_cmd_buf_for_queue(1, gfx::QueueType::Compute);
+    assert!(device.execute_multiple(&[&cmds[0], &compute]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn device_fence_signal_wait() -> Result<(), hotline_rs::Error> {
+    use gfx::Fence;
+    let (_app, device) = create_test_device("device_fence_signal_wait", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    let fence = device.create_fence()?;
+    assert_eq!(fence.get_completed_value(), 0);
+
+    for value in 1..4 {
+        device.signal(&fence, value)?;
+        device.wait(&fence, value)?;
+        assert!(fence.get_completed_value() >= value);
+    }
+
+    // waiting directly on the fence
+    device.queue_signal(gfx::QueueType::Graphics, &fence, 4)?;
+    fence.wait_cpu(4)?;
+    assert!(fence.get_completed_value() >= 4);
+
+    Ok(())
+}
+
+#[test]
+fn async_compute_queue_sync() -> Result<(), hotline_rs::Error> {
+    use gfx::Fence;
+    let (_app, device) = create_test_device("async_compute_queue_sync", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        ..Default::default()
+    });
+
+    let cs = device.create_shader(&gfx::ShaderInfo {
+        shader_type: gfx::ShaderType::Compute,
+        compile_info: Some(gfx::ShaderCompileInfo {
+            entry_point: String::from("CSMain"),
+            target: String::from("cs_5_0"),
+            flags: gfx::ShaderCompileFlags::NONE,
+        }),
+    }, "[numthreads(1, 1, 1)] void CSMain() {}".as_bytes())?;
+
+    let pipeline = device.create_compute_pipeline(&gfx::ComputePipelineInfo {
+        cs: &cs,
+        descriptor_layout: gfx::DescriptorLayout::default(),
+    })?;
+
+    // record work on the compute queue
+    let mut compute_cmd = device.create_cmd_buf_for_queue(1, gfx::QueueType::Compute);
+    assert_eq!(compute_cmd.get_queue_type(), gfx::QueueType::Compute);
+    compute_cmd.set_compute_pipeline(&pipeline);
+    compute_cmd.dispatch(gfx::Size3 { x: 1, y: 1, z: 1 }, gfx::Size3 { x: 1, y: 1, z: 1 });
+    compute_cmd.close()?;
+
+    // the graphics queue consumes the compute work by waiting on the gpu for the compute fence
+    let compute_fence = device.create_fence()?;
+    let graphics_fence = device.create_fence()?;
+    device.execute(&compute_cmd)?;
+    device.queue_signal(gfx::QueueType::Compute, &compute_fence, 1)?;
+    device.queue_wait(gfx::QueueType::Graphics, &compute_fence, 1)?;
+    device.queue_signal(gfx::QueueType::Graphics, &graphics_fence, 1)?;
+
+    device.wait(&graphics_fence, 1)?;
+    assert!(compute_fence.get_completed_value() >= 1);
+    assert!(graphics_fence.get_completed_value() >= 1);
+
+    Ok(())
+}
+
+#[test]
+fn batched_uploads_flush() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("batched_uploads_flush", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        ..Default::default()
+    });
+
+    // multiple uploads are batched into a single submission
+    let data = vec![0xffu8; 256];
+    let mut buffers = Vec::new();
+    for _ in 0..8 {
+        buffers.push(device.create_buffer(&gfx::BufferInfo {
+            usage: gfx::BufferUsage::Vertex,
+            cpu_access: gfx::CpuAccessFlags::NONE,
+            format: gfx::Format::Unknown,
+            stride: 16,
+            num_elements: 16,
+        }, Some(data.as_slice()))?);
+    }
+    let _texture = device.create_texture(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 8,
+        height: 8,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, Some(data.as_slice()))?;
+
+    device.flush_uploads()?;
+
+    // flushing with nothing pending is fine
+    device.flush_uploads()?;
+    Ok(())
+}
+
+#[test]
+fn upload_buffer_region_ring() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("upload_buffer_region_ring", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    let mut info = gfx::BufferInfo {
+        usage: gfx::BufferUsage::Vertex,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: 1024,
+        num_elements: 1024,
+    };
+    let buffer = device.create_buffer::<u8>(&info, None)?;
+
+    // enough regions to wrap the ring several times
+    let data = vec![0x7fu8; 1024 * 1024];
+    for _ in 0..32 {
+        device.upload_buffer_region(&buffer, 0, data.as_slice())?;
+    }
+    device.flush_uploads()?;
+
+    // regions out of bounds and cpu writable buffers are rejected
+    assert!(device.upload_buffer_region(&buffer, 1, data.as_slice()).is_err());
+    info.cpu_access = gfx::CpuAccessFlags::WRITE;
+    let cpu_buffer = device.create_buffer::<u8>(&info, None)?;
+    assert!(device.upload_buffer_region(&cpu_buffer, 0, &data[0..16]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn create_structured_buffers() -> Result<(), hotline_rs::Error> {
+    use gfx::Buffer;
+    let (_app, mut device) = create_test_device("create_structured_buffers", &gfx::DeviceInfo {
+        shader_heap_size: 10,
+        ..Default::default()
+    });
+
+    let particles = vec![[0.0f32; 8]; 100];
+    let info = gfx::BufferInfo {
+        usage: gfx::BufferUsage::Structured,
+        cpu_access: gfx::CpuAccessFlags::NONE,
+        format: gfx::Format::Unknown,
+        stride: std::mem::size_of::<[f32; 8]>(),
+        num_elements: particles.len(),
+    };
+
+    // read only structured buffers only have an srv
+    let structured = device.create_buffer(&info, Some(particles.as_slice()))?;
+    assert!(structured.get_srv_index().is_some());
+    assert!(structured.get_uav_index().is_none());
+
+    // read-write and append buffers have both
+    for usage in [gfx::BufferUsage::RWStructured, gfx::BufferUsage::AppendStructured] {
+        let rw = device.create_buffer(&gfx::BufferInfo {
+            usage,
+            ..info
+        }, Some(particles.as_slice()))?;
+        assert!(rw.get_srv_index().is_some());
+        assert!(rw.get_uav_index().is_some());
+        assert_ne!(rw.get_srv_index(), rw.get_uav_index());
+    }
+
+    // raw buffers are addressed in 4 byte elements
+    let raw = device.create_buffer(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::RWRaw,
+        ..info
+    }, Some(particles.as_slice()))?;
+    assert!(raw.get_srv_index().is_some());
+    assert!(raw.get_uav_index().is_some());
+    assert!(device.create_buffer::<u8>(&gfx::BufferInfo {
+        usage: gfx::BufferUsage::Raw,
+        stride: 3,
+        num_elements: 1,
+        ..info
+    }, None).is_err());
+    device.flush_uploads()?;
+
+    Ok(())
+}
+
+#[test]
+fn split_transition_barrier() -> Result<(), hotline_rs::Error> {
+    let (_app, mut device) = create_test_device("split_transition_barrier", &gfx::DeviceInfo {
+        shader_heap_size: 1,
+        render_target_heap_size: 1,
+        ..Default::default()
+    });
+
+    let texture = device.create_texture::<u8>(&gfx::TextureInfo {
+        tex_type: gfx::TextureType::Texture2D,
+        format: gfx::Format::RGBA8n,
+        width: 16,
+        height: 16,
+        depth: 1,
+        array_levels: 1,
+        mip_levels: 1,
+        samples: 1,
+        usage: gfx::TextureUsage::SHADER_RESOURCE | gfx::TextureUsage::RENDER_TARGET,
+        initial_state: gfx::ResourceState::ShaderResource,
+    }, None)?;
+
+    let barrier = gfx::TransitionBarrier {
+        texture: Some(&texture),
+        buffer: None,
+        state_before: gfx::ResourceState::ShaderResource,
+        state_after: gfx::ResourceState::RenderTarget,
+    };
+
+    // begin at the top of the frame, the state only changes once the transition has ended
+    let mut cmd = device.create_cmd_buf(1);
+    cmd.begin_transition(&barrier);
+    assert!(texture.get_state() == gfx::ResourceState::ShaderResource);
+    cmd.end_transition(&barrier);
+    assert!(texture.get_state() == gfx::ResourceState::RenderTarget);
+    cmd.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn create_dynamic_samplers() -> Result<(), hotline_rs::Error> {
+    use gfx::Sampler;
+    let (_app, mut device) = create_test_device("create_dynamic_samplers", &gfx::DeviceInfo {
+        sampler_heap_size: 2,
+        ..Default::default()
+    });
+
+    let point = device.create_sampler(&gfx::SamplerInfo {
+        filter: gfx::SamplerFilter::Point,
+        ..Default::default()
+    })?;
+    let linear = device.create_sampler(&gfx::SamplerInfo {
+        filter: gfx::SamplerFilter::Linear,
+        border_colour: Some(0xff0000ff),
+        ..Default::default()
+    })?;
+    assert_ne!(point.get_index(), linear.get_index());
+
+    // the sampler heap is full
+    assert!(device.create_sampler(&gfx::SamplerInfo::default()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn device_removed_state() -> Result<(), hotline_rs::Error> {
+    let (_app, device) = create_test_device("device_removed_state", &gfx::DeviceInfo {
+        ..Default::default()
+    });
+
+    // breadcrumbs can only be read once the device has been removed
+    assert!(!device.is_device_removed());
+    assert!(device.get_dred_breadcrumbs().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn align_tests() {
+    // pow2
+    let val = gfx::align_pow2(101, 256);
+    assert_eq!(val % 256, 0);
+    let val = gfx::align_pow2(8861, 64);
+    assert_eq!(val % 64, 0);
+    let val = gfx::align_pow2(1280, 128);
+    assert_eq!(val % 128, 0);
+    let val = gfx::align_pow2(5, 4);
+    assert_eq!(val % 4, 0);
+    let val = gfx::align_pow2(19, 2);
+    assert_eq!(val % 2, 0);
+    // non pow2
+    let val = gfx::align(92, 133);
+    assert_eq!(val % 133, 0);
+    let val = gfx::align(172, 201);
+    assert_eq!(val % 201, 0);
+    let val = gfx::align(288, 1177);
+    assert_eq!(val % 1177, 0);
+    let val = gfx::align(1092, 52);
+    assert_eq!(val % 52, 0);
+    let val = gfx::align(5568, 21);
+    assert_eq!(val % 21, 0);
+}
+
+
+// client tests must run 1 at a time, this boots the client with empty user info
+fn boot_empty_client() {
+    println!("test: boot_empty_client");
+    let config = client::UserConfig {
+        main_window_rect: HotlineInfo::default().window_rect,
+        console_window_rect: None,
+        plugins: None,
+        plugin_data: Some(HashMap::new())
+    };
+
+    // create client
+    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
+        name: "boot_empty_client".to_string(),
+        user_config: Some(config),
+        ..Default::default()
+    }).unwrap();
+    
+    // run
+    ctx.run_once();
+}
+
+/// Load the basic empty plugin, should print and close gracefully
+/// plugin ui
+/// plugin unload
+/// plugin setup
+/// plugin update
+fn boot_client_empty_plugin() {
+    println!("test: boot_client_empty_plugin");
+    let mut config = client::UserConfig {
+        main_window_rect: HotlineInfo::default().window_rect,
+        console_window_rect: None,
+        plugins: Some(HashMap::new()),
+        plugin_data: Some(HashMap::new())
+    };
+
+    // empty plugin
+    if let Some(plugins) = &mut config.plugins {
+        plugins.insert("empty".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../plugins")
+        });
+    }
+
+    // create client
+    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
+        name: "boot_client_empty_plugin".to_string(),
+        user_config: Some(config),
+        ..Default::default()
+    }).unwrap();
+    
+    // run
+    ctx.run_once();
+}
+
+/// Boots the client with a plugin that does not exist, should load gracefully and notify the missing plugin
+fn boot_client_missing_plugin() {
+    println!("test: boot_client_missing_plugin");
+    let mut config = client::UserConfig {
+        main_window_rect: HotlineInfo::default().window_rect,
+        console_window_rect: None,
+        plugins: Some(HashMap::new()),
+        plugin_data: Some(HashMap::new())
+    };
+
+    // empty plugin
+    if let Some(plugins) = &mut config.plugins {
+        plugins.insert("missing".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../missing")
+        });
+    }
+
+    // create client
+    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
+        name: "boot_client_missing_plugin".to_string(),
+        user_config: Some(config),
+        ..Default::default()
+    }).unwrap();
+    
+    // run
+    ctx.run_once();
+}
+
+/// Boots the client with the ecs plugin and ecs_demos plugin but with no `PluginData`
+fn boot_client_ecs_plugin() {
+    println!("test: boot_client_ecs_plugin");
+    let mut config = client::UserConfig {
+        main_window_rect: HotlineInfo::default().window_rect,
+        console_window_rect: None,
+        plugins: Some(HashMap::new()),
+        plugin_data: Some(HashMap::new()),
+    };
+
+    // ecs plugin with no demo active
+    if let Some(plugins) = &mut config.plugins {
+        plugins.insert("ecs".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../plugins")
+        });
+        plugins.insert("ecs_demos".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../plugins")
+        });
+    }
+
+    // create client
+    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
+        name: "boot_client_ecs_plugin".to_string(),
+        user_config: Some(config),
+        ..Default::default()
+    }).unwrap();
+    
+    // run
+    ctx.run_once();
+}
+
+/// Boots the client with the ecs plugin and ecs_demos with the primitives sample active
+fn boot_client_ecs_plugin_demo(demo_name: &str) {
+    println!("test: boot_client_ecs_plugin");
+    let mut config = client::UserConfig {
+        main_window_rect: HotlineInfo::default().window_rect,
+        console_window_rect: None,
+        plugins: Some(HashMap::new()),
+        plugin_data: Some(HashMap::new()),
+    };
+
+    // ecs plugin with no demo active
+    if let Some(plugins) = &mut config.plugins {
+        plugins.insert("ecs".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../plugins")
+        });
+        plugins.insert("ecs_demos".to_string(), PluginInfo {
+            path: hotline_rs::get_data_path("../plugins")
+        });
+    }
+
+    if let Some(plugin_data) = &mut config.plugin_data {
+        plugin_data.insert("ecs".to_string(), format!("{{\"active_demo\": \"{}\"}}", demo_name));
+    }
+
+    // create client
+    let ctx : Client<gfx_platform::Device, os_platform::App> = Client::create(HotlineInfo {
+        name: demo_name.to_string(),
+        user_config: Some(config),
+        ..Default::default()
+    }).unwrap();
+    
+    // run
+    ctx.run_once();
+}
+
+#[test]
+fn client_tests() {
+    // build the plugins
+    //hotline_rs::plugin::build_all();
+
+    // run the tests.. they need to run 1 by 1 as we cant have 2 clients running concurrently
+    //boot_empty_client();
 
     /*
     boot_client_empty_plugin();
@@ -535,4 +3813,247 @@ fn client_tests() {
     boot_client_ecs_plugin_demo("test_missing_systems");
     boot_client_ecs_plugin_demo("test_missing_render_graph");
     */
-}
\ No newline at end of file
+}
+
+#[test]
+fn pmfx_disabled_view() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, _win, mut swap_chain, _cmd) = create_test_swap_chain("pmfx_disabled_view", &gfx::DeviceInfo {
+        shader_heap_size: 4,
+        render_target_heap_size: 4,
+        ..Default::default()
+    })?;
+
+    // minimal pmfx with 2 graph views of the same view rendering into a single target
+    let folder = std::env::temp_dir().join("pmfx_disabled_view");
+    std::fs::create_dir_all(&folder)?;
+    std::fs::write(folder.join("pmfx_disabled_view.json"), r#"{
+        "shaders": {},
+        "pipelines": {},
+        "depth_stencil_states": {},
+        "raster_states": {},
+        "textures": {
+            "target": {
+                "width": 64, "height": 64, "depth": 1, "mip_levels": 1, "array_levels": 1, "samples": 1,
+                "format": "RGBA8n", "usage": ["RenderTarget", "ShaderResource"], "hash": 0
+            }
+        },
+        "views": {
+            "view": {
+                "render_target": ["target"], "depth_stencil": [], "viewport": [0, 0, 1, 1, 0, 1],
+                "scissor": [0, 0, 1, 1], "camera": "", "hash": 0
+            }
+        },
+        "render_graphs": {
+            "graph": {
+                "enabled_view": { "view": "view" },
+                "disabled_view": { "view": "view", "depends_on": ["enabled_view"] }
+            }
+        },
+        "dependencies": []
+    }"#)?;
+
+    let mut pmfx = Pmfx::<gfx_platform::Device>::create();
+    pmfx.load(&folder.to_string_lossy())?;
+    pmfx.create_render_graph(&mut dev, "graph")?;
+    pmfx.set_view_enabled("disabled_view", false);
+
+    // disabled views are reset every frame, so they must be closed to be reset again when their backbuffer comes
+    // around, run for more frames than there are swap chain buffers
+    for _ in 0..swap_chain.get_num_buffers() * 2 + 1 {
+        pmfx.new_frame(&mut dev, &swap_chain);
+        pmfx.execute(&mut dev)?;
+        swap_chain.swap(&dev)?;
+    }
+    swap_chain.wait_for_last_frame();
+
+    Ok(())
+}
+
+#[test]
+fn pmfx_execute_parallel() -> Result<(), hotline_rs::Error> {
+    let (_app, mut dev, _win, mut swap_chain, _cmd) = create_test_swap_chain("pmfx_execute_parallel", &gfx::DeviceInfo {
+        shader_heap_size: 8,
+        render_target_heap_size: 8,
+        ..Default::default()
+    })?;
+
+    // 2 independent views which clear their own target to a different colour and read it back
+    let folder = std::env::temp_dir().join("pmfx_execute_parallel");
+    std::fs::create_dir_all(&folder)?;
+    std::fs::write(folder.join("pmfx_execute_parallel.json"), r#"{
+        "shaders": {},
+        "pipelines": {},
+        "depth_stencil_states": {},
+        "raster_states": {},
+        "textures": {
+            "target_a": {
+                "width": 64, "height": 64, "depth": 1, "mip_levels": 1, "array_levels": 1, "samples": 1,
+                "format": "RGBA8n", "usage": ["RenderTarget", "ShaderResource"], "hash": 0
+            },
+            "target_b": {
+                "width": 64, "height": 64, "depth": 1, "mip_levels": 1, "array_levels": 1, "samples": 1,
+                "format": "RGBA8n", "usage": ["RenderTarget", "ShaderResource"], "hash": 0
+            }
+        },
+        "views": {
+            "view_a": {
+                "render_target": ["target_a"], "depth_stencil": [], "viewport": [0, 0, 1, 1, 0, 1],
+                "scissor": [0, 0, 1, 1], "clear_colour": [1, 0, 0, 1], "camera": "", "hash": 0
+            },
+            "view_b": {
+                "render_target": ["target_b"], "depth_stencil": [], "viewport": [0, 0, 1, 1, 0, 1],
+                "scissor": [0, 0, 1, 1], "clear_colour": [0, 1, 0, 1], "camera": "", "hash": 0
+            }
+        },
+        "render_graphs": {
+            "graph": {
+                "view_a": { "view": "view_a", "readback": ["target_a"] },
+                "view_b": { "view": "view_b", "readback": ["target_b"] }
+            }
+        },
+        "dependencies": []
+    }"#)?;
+
+    let mut pmfx = Pmfx::<gfx_platform::Device>::create();
+    pmfx.load(&folder.to_string_lossy())?;
+    pmfx.create_render_graph(&mut dev, "graph")?;
+
+    let record = |_: &Pmfx<gfx_platform::Device>, view: &mut pmfx::View<gfx_platform::Device>| {
+        if let Some(pass) = &view.pass {
+            view.cmd_buf.begin_render_pass(pass);
+            view.cmd_buf.end_render_pass();
+        }
+    };
+
+    // render a frame serially then in parallel and read back the targets of each
+    let mut results = Vec::new();
+    for parallel in [false, true] {
+        pmfx.request_readback("target_a");
+        pmfx.request_readback("target_b");
+        pmfx.new_frame(&mut dev, &swap_chain);
+        if parallel {
+            pmfx.execute_parallel(&mut dev, record)?;
+        }
+        else {
+            for name in ["view_a", "view_b"] {
+                let view = pmfx.get_view(name)?;
+                record(&pmfx, &mut view.lock().unwrap());
+            }
+            pmfx.execute(&mut dev)?;
+        }
+        swap_chain.swap(&dev)?;
+        swap_chain.wait_for_last_frame();
+
+        let mut pixels = Vec::new();
+        for texture in ["target_a", "target_b"] {
+            let request = pmfx.get_readback(texture).unwrap();
+            assert!(request.is_complete(&swap_chain));
+            let data = request.map(&gfx::MapInfo {
+                subresource: 0,
+                read_start: 0,
+                read_end: usize::MAX
+            })?;
+            pixels.push(data.data[0..4].to_vec());
+            request.unmap();
+        }
+        results.push(pixels);
+    }
+
+    assert_eq!(results[0], vec![vec![255, 0, 0, 255], vec![0, 255, 0, 255]]);
+    assert_eq!(results[0], results[1]);
+
+    Ok(())
+}
+
+#[test]
+fn imgui_render_to_pass() -> Result<(), hotline_rs::Error> {
+    let (mut app, mut dev, mut win, mut swap_chain, mut cmd) = create_test_swap_chain("imgui_render_to_pass", &gfx::DeviceInfo {
+        shader_heap_size: 8,
+        render_target_heap_size: 4,
+        ..Default::default()
+    })?;
+
+    // offscreen pmfx target for the ui
+    let folder = std::env::temp_dir().join("imgui_render_to_pass");
+    std::fs::create_dir_all(&folder)?;
+    std::fs::write(folder.join("imgui_render_to_pass.json"), r#"{
+        "shaders": {},
+        "pipelines": {},
+        "depth_stencil_states": {},
+        "raster_states": {},
+        "textures": {
+            "ui_target": {
+                "width": 640, "height": 480, "depth": 1, "mip_levels": 1, "array_levels": 1, "samples": 1,
+                "format": "RGBA8n", "usage": ["RenderTarget", "ShaderResource"], "hash": 0
+            }
+        },
+        "views": {
+            "ui": {
+                "render_target": ["ui_target"], "depth_stencil": [], "viewport": [0, 0, 1, 1, 0, 1],
+                "scissor": [0, 0, 1, 1], "clear_colour": [0, 0, 0, 0], "camera": "", "hash": 0
+            }
+        },
+        "render_graphs": {
+            "graph": {
+                "ui": { "view": "ui" }
+            }
+        },
+        "dependencies": []
+    }"#)?;
+
+    let mut pmfx = Pmfx::<gfx_platform::Device>::create();
+    pmfx.load(&folder.to_string_lossy())?;
+    pmfx.create_render_graph(&mut dev, "graph")?;
+
+    // no fonts uses the imgui default font
+    let mut imgui = imgui::ImGui::create(&mut imgui::ImGuiInfo::<gfx_platform::Device, os_platform::App> {
+        device: &mut dev,
+        swap_chain: &mut swap_chain,
+        main_window: &win,
+        fonts: Vec::new(),
+    })?;
+
+    // draw the ui into the offscreen target and the backbuffer in the same frame, for more frames than there are
+    // backbuffers so each set of imgui buffers is reused
+    for _ in 0..swap_chain.get_num_buffers() * 2 {
+        swap_chain.new_frame();
+        pmfx.new_frame(&mut dev, &swap_chain);
+        imgui.new_frame(&mut app, &mut win, &mut dev);
+        imgui.demo();
+
+        {
+            let view = pmfx.get_view("ui")?;
+            let mut view = view.lock().unwrap();
+            let view = &mut *view;
+            let pass = view.pass.as_ref().unwrap();
+            view.cmd_buf.begin_render_pass(pass);
+            imgui.render_to_pass(&mut dev, &mut view.cmd_buf, pass)?;
+            view.cmd_buf.end_render_pass();
+        }
+
+        cmd.reset(&swap_chain);
+        cmd.transition_barrier(&gfx::TransitionBarrier {
+            texture: Some(swap_chain.get_backbuffer_texture()),
+            buffer: None,
+            state_before: gfx::ResourceState::Present,
+            state_after: gfx::ResourceState::RenderTarget,
+        });
+        cmd.begin_render_pass(swap_chain.get_backbuffer_pass());
+        imgui.render(&mut app, &mut win, &mut dev, &mut cmd);
+        cmd.end_render_pass();
+        cmd.transition_barrier(&gfx::TransitionBarrier {
+            texture: Some(swap_chain.get_backbuffer_texture()),
+            buffer: None,
+            state_before: gfx::ResourceState::RenderTarget,
+            state_after: gfx::ResourceState::Present,
+        });
+        cmd.close()?;
+
+        pmfx.execute(&mut dev)?;
+        dev.execute(&cmd)?;
+        swap_chain.swap(&dev)?;
+    }
+    swap_chain.wait_for_last_frame();
+
+    Ok(())
+}